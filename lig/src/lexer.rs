@@ -2,9 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use crate::Location;
 use ligature::{Element, LigatureError};
 use logos::{Lexer, Logos};
-use crate::Location;
 
 #[derive(Logos, Debug, PartialEq, Eq, Clone)]
 #[logos()]
@@ -15,12 +15,10 @@ pub enum Token {
     #[regex(r#""(([^\x00-\x1F"\\]|\\["\\/bfnrt]|\\u[0-9a-fA-F]{4})*)""#, string)]
     String(String),
 
-    #[regex("[_a-zA-Z]+[_a-zA-Z0-9.?]*", name, priority = 2)]
+    //Note: the below regex is based on the one for IRIs, currently , () and [] have been removed
+    #[regex("[a-zA-Z0-9-._~:/?#@!$&'*+;%=\\x{00A0}-\\x{D7FF}\\x{F900}-\\x{FDCF}\\x{FDF0}-\\x{FFEF}\\x{10000}-\\x{1FFFD}\\x{20000}-\\x{2FFFD}\\x{30000}-\\x{3FFFD}\\x{40000}-\\x{4FFFD}\\x{50000}-\\x{5FFFD}\\x{60000}-\\x{6FFFD}\\x{70000}-\\x{7FFFD}\\x{80000}-\\x{8FFFD}\\x{90000}-\\x{9FFFD}\\x{A0000}-\\x{AFFFD}\\x{B0000}-\\x{BFFFD}\\x{C0000}-\\x{CFFFD}\\x{D0000}-\\x{DFFFD}\\x{E1000}-\\x{EFFFD}]+", name)]
     Name(Element),
 
-    // #[regex("`[a-zA-Z0-9-._~:/?#\\[\\]@!$&'()*+,;%=\\x{00A0}-\\x{D7FF}\\x{F900}-\\x{FDCF}\\x{FDF0}-\\x{FFEF}\\x{10000}-\\x{1FFFD}\\x{20000}-\\x{2FFFD}\\x{30000}-\\x{3FFFD}\\x{40000}-\\x{4FFFD}\\x{50000}-\\x{5FFFD}\\x{60000}-\\x{6FFFD}\\x{70000}-\\x{7FFFD}\\x{80000}-\\x{8FFFD}\\x{90000}-\\x{9FFFD}\\x{A0000}-\\x{AFFFD}\\x{B0000}-\\x{BFFFD}\\x{C0000}-\\x{CFFFD}\\x{D0000}-\\x{DFFFD}\\x{E1000}-\\x{EFFFD}]+`", name)]
-    // Name(Name),
-
     #[token("(")]
     OpenParen,
 
@@ -39,6 +37,9 @@ pub enum Token {
     #[token("]")]
     CloseSquare,
 
+    #[token(",")]
+    Comma,
+
     #[regex("--.*\n?", comment)]
     Comment(String),
 }
@@ -50,20 +51,37 @@ fn trim_string(value: &str) -> &str {
     chars.as_str()
 }
 
-fn string(lex: &mut Lexer<Token>) -> Option<String> {
-    Some(trim_string(lex.slice()).to_string())
+/// Reverse the escaping done by `write_string`.
+fn unescape(value: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next()? {
+                'b' => result.push('\u{08}'),
+                'f' => result.push('\u{0C}'),
+                'n' => result.push('\n'),
+                'r' => result.push('\r'),
+                't' => result.push('\t'),
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    result.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                }
+                c => result.push(c),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Some(result)
 }
 
-// fn name(lex: &mut Lexer<Token>) -> Option<String> {
-//     Some(lex.slice().to_string())
-// }
+fn string(lex: &mut Lexer<Token>) -> Option<String> {
+    unescape(trim_string(lex.slice()))
+}
 
 fn name(lex: &mut Lexer<Token>) -> Option<Element> {
-    todo!()
-    // match Identifier::new(lex.slice().trim_start_matches("`").trim_end_matches("`")) {
-    //     Ok(identifier) => Some(identifier),
-    //     Err(_) => None,
-    // }
+    Some(Element(lex.slice().to_owned(), None, None))
 }
 
 fn comment(lex: &mut Lexer<Token>) -> Option<String> {
@@ -74,23 +92,32 @@ fn ws(lex: &mut Lexer<Token>) -> Option<String> {
     Some(lex.slice().to_string())
 }
 
-pub fn tokenize(script: &str) -> Result<Vec<Location<Token>>, LigatureError> {
+pub fn tokenize(script: &str) -> Result<Vec<Location<Token>>, Location<LigatureError>> {
     let lexer = Token::lexer(script).spanned();
     let mut results = vec![];
     for (token, range) in lexer {
         match token {
             Ok(token) => results.push(Location(token, range.start)),
-            Err(_) => return Err(LigatureError(String::from("Error tokenizing input."))),
+            Err(_) => {
+                return Err(Location(
+                    LigatureError(format!(
+                        "Error tokenizing input at {}.",
+                        crate::line_and_column(script, range.start)
+                    )),
+                    range.start,
+                ))
+            }
         }
     }
     Ok(results)
 }
 
-pub fn tokenize_and_filter(script: &str) -> Result<Vec<Location<Token>>, LigatureError> {
+pub fn tokenize_and_filter(script: &str) -> Result<Vec<Location<Token>>, Location<LigatureError>> {
     let tokens = tokenize(script);
     tokens.map(|mut tokens| {
-        tokens
-            .retain(|Location(token, _)| !matches!(token, Token::Comment(_)) && !matches!(token, Token::WS(_)));
+        tokens.retain(|Location(token, _)| {
+            !matches!(token, Token::Comment(_)) && !matches!(token, Token::WS(_))
+        });
         tokens
     })
 }
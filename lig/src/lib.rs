@@ -7,9 +7,7 @@
 
 //#![deny(missing_docs)]
 
-use std::fmt::Debug;
-
-use lexer::Token;
+use ligature::LigatureError;
 
 pub mod lexer;
 pub mod read;
@@ -22,15 +20,20 @@ pub mod write;
 
 /// Escape a String value.
 pub fn write_string(string: &str) -> String {
-    //TODO this could be done better
-    let escaped_string = string
-        .replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        //.replace("\f", "\\b") <-- TODO not sure how to handle this or if I really need to
-        //.replace("\b", "\\b") <-- TODO not sure how to handle this or if I really need to
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t");
+    let mut escaped_string = String::new();
+    for c in string.chars() {
+        match c {
+            '\\' => escaped_string += "\\\\",
+            '"' => escaped_string += "\\\"",
+            '\u{08}' => escaped_string += "\\b",
+            '\u{0C}' => escaped_string += "\\f",
+            '\n' => escaped_string += "\\n",
+            '\r' => escaped_string += "\\r",
+            '\t' => escaped_string += "\\t",
+            c if c < ' ' => escaped_string += &format!("\\u{:04x}", c as u32),
+            c => escaped_string.push(c),
+        }
+    }
     format!("\"{}\"", escaped_string)
 }
 
@@ -38,6 +41,20 @@ pub fn write_string(string: &str) -> String {
 /// Store location information alongside a value.
 pub struct Location<T: PartialEq + Eq>(pub T, pub usize);
 
+impl From<Location<LigatureError>> for LigatureError {
+    fn from(error: Location<LigatureError>) -> Self {
+        error.0
+    }
+}
+
+/// Describe an offset into a script as a line and column, both starting at 1.
+pub(crate) fn line_and_column(script: &str, offset: usize) -> String {
+    let before = &script[..offset.min(script.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    format!("line {}, column {}", line, column)
+}
+
 // #[derive(Debug, Serialize)]
 // /// Structure used for debugging or inspecting code.
 // pub struct Introspection {
@@ -2,23 +2,124 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use logos::Logos;
-use crate::lexer::Token;
-
-pub fn tokenize(script: &str) -> Result<Vec<Token>, LigatureError> {
-    let lexer = Token::lexer(script);
-    let mut results = vec![];
-    for token in lexer {
-        match token {
-            Ok(token) => results.push(token),
-            Err(_) => return Err(LigatureError(String::from("Error tokenizing input."))),
-        }
+use std::collections::BTreeSet;
+
+use crate::lexer::{tokenize_and_filter, Token};
+use crate::{line_and_column, Location};
+use gaze::Gaze;
+use ligature::{Element, Entry, LigatureError};
+
+/// Read a network written in the Lig format.
+/// An empty script is read as an empty network.
+pub fn read(script: &str) -> Result<BTreeSet<Entry>, Location<LigatureError>> {
+    let tokens = tokenize_and_filter(script)?;
+    let mut gaze = Gaze::from_vec(tokens);
+    if gaze.is_complete() {
+        return Ok(BTreeSet::new());
+    }
+    expect(script, &mut gaze, Token::OpenBrace, "`{`")?;
+    let result = read_entries(script, &mut gaze)?;
+    match gaze.next() {
+        None => Ok(result),
+        Some(Location(token, offset)) => error(script, offset, "end of input", &token),
+    }
+}
+
+fn error<T>(
+    script: &str,
+    offset: usize,
+    expected: &str,
+    found: &Token,
+) -> Result<T, Location<LigatureError>> {
+    Err(Location(
+        LigatureError(format!(
+            "Error reading Lig at {}, expected {} but found {:?}.",
+            line_and_column(script, offset),
+            expected,
+            found
+        )),
+        offset,
+    ))
+}
+
+fn end_of_input<T>(script: &str, expected: &str) -> Result<T, Location<LigatureError>> {
+    Err(Location(
+        LigatureError(format!(
+            "Error reading Lig at {}, expected {} but reached end of input.",
+            line_and_column(script, script.len()),
+            expected
+        )),
+        script.len(),
+    ))
+}
+
+fn expect(
+    script: &str,
+    gaze: &mut Gaze<Location<Token>>,
+    expected: Token,
+    description: &str,
+) -> Result<(), Location<LigatureError>> {
+    match gaze.next() {
+        Some(Location(token, _)) if token == expected => Ok(()),
+        Some(Location(token, offset)) => error(script, offset, description, &token),
+        None => end_of_input(script, description),
+    }
+}
+
+fn read_element(
+    script: &str,
+    gaze: &mut Gaze<Location<Token>>,
+) -> Result<Location<Token>, Location<LigatureError>> {
+    match gaze.next() {
+        Some(Location(Token::Name(name), offset)) => Ok(Location(Token::Name(name), offset)),
+        Some(Location(Token::String(value), offset)) => Ok(Location(Token::String(value), offset)),
+        Some(Location(token, offset)) => error(script, offset, "an Element", &token),
+        None => end_of_input(script, "an Element"),
+    }
+}
+
+fn to_element(token: Token) -> Element {
+    match token {
+        Token::Name(element) => element,
+        Token::String(value) => Element(value, None, None),
+        _ => unreachable!(),
     }
-    Ok(results)
 }
 
-pub fn read(script: &str) -> Result<Vec<Role>, LigatureError> {
-    todo!()
-    //let tokens = tokenize(script)?;
-    //read_tokens(tokens)
+fn read_entries(
+    script: &str,
+    gaze: &mut Gaze<Location<Token>>,
+) -> Result<BTreeSet<Entry>, Location<LigatureError>> {
+    let mut result = BTreeSet::new();
+    loop {
+        if let Some(Location(Token::CloseBrace, _)) = gaze.peek() {
+            gaze.next();
+            return Ok(result);
+        }
+        let first = to_element(read_element(script, gaze)?.0);
+        let Location(second, _) = read_element(script, gaze)?;
+        let third = to_element(read_element(script, gaze)?.0);
+        let entry = match second {
+            Token::Name(Element(name, None, None)) if name == ":" => Entry::Extends {
+                element: first,
+                concept: third,
+            },
+            Token::Name(Element(name, None, None)) if name == "¬:" => Entry::NotExtends {
+                element: first,
+                concept: third,
+            },
+            second => Entry::Role {
+                first,
+                second: third,
+                role: to_element(second),
+            },
+        };
+        result.insert(entry);
+        match gaze.next() {
+            Some(Location(Token::Comma, _)) => (),
+            Some(Location(Token::CloseBrace, _)) => return Ok(result),
+            Some(Location(token, offset)) => return error(script, offset, "`,` or `}`", &token),
+            None => return end_of_input(script, "`,` or `}`"),
+        }
+    }
 }
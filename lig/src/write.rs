@@ -4,8 +4,60 @@
 
 use std::collections::BTreeSet;
 
-use ligature::Entry;
+use crate::{lexer::Token, write_string};
+use ligature::{Element, Entry, LigatureError};
+use logos::Logos;
 
-pub fn write(value: &BTreeSet<Entry>) -> String {
-    "".to_owned()
+/// Write an Element's value, quoting it unless it can be read back as a single name.
+/// `:` and `¬:` are always quoted so they aren't confused with Extends and NotExtends.
+/// Lig has no way to write Namespaces or LangTags, so Elements with either are an error.
+pub fn write_element(element: &Element) -> Result<String, LigatureError> {
+    let value = match element {
+        Element(value, None, None) => value,
+        Element(value, _, _) => {
+            return Err(LigatureError(format!(
+                "`{value}` has a Namespace or LangTag, which Lig can't represent."
+            )))
+        }
+    };
+    let mut lexer = Token::lexer(value);
+    let is_name = matches!(lexer.next(), Some(Ok(Token::Name(_))))
+        && lexer.next().is_none()
+        && value != ":"
+        && value != "¬:";
+    if is_name {
+        Ok(value.to_owned())
+    } else {
+        Ok(write_string(value))
+    }
+}
+
+/// Write a network in the Lig format, one Entry per line.
+/// Fails if an Element has a Namespace or LangTag, since Lig only supports an Element's value.
+pub fn write(value: &BTreeSet<Entry>) -> Result<String, LigatureError> {
+    if value.is_empty() {
+        return Ok("{}\n".to_owned());
+    }
+    let mut entries: Vec<String> = vec![];
+    for entry in value {
+        entries.push(match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => format!(
+                "  {} {} {}",
+                write_element(first)?,
+                write_element(role)?,
+                write_element(second)?
+            ),
+            Entry::Extends { element, concept } => {
+                format!("  {} : {}", write_element(element)?, write_element(concept)?)
+            }
+            Entry::NotExtends { element, concept } => {
+                format!("  {} ¬: {}", write_element(element)?, write_element(concept)?)
+            }
+        });
+    }
+    Ok(format!("{{\n{}\n}}\n", entries.join(",\n")))
 }
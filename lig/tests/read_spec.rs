@@ -3,31 +3,70 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use lig::read::read;
-use ligature::{LigatureError, Element, Role, Value};
+use lig::Location;
+use ligature::{Element, Entry, LigatureError};
+use std::collections::BTreeSet;
 
 #[test]
 fn read_empty_set_of_statements() -> Result<(), LigatureError> {
     let s = "";
-    let expected: Vec<Role> = vec![];
+    let expected: BTreeSet<Entry> = BTreeSet::new();
     assert_eq!(read(s)?, expected);
+    assert_eq!(read("{}")?, expected);
     Ok(())
 }
 
 #[test]
 fn read_set_of_statements() -> Result<(), LigatureError> {
     let s = "{ e a 123,\ne2 a e\n }";
-    let expected = vec![
-        Role {
-            first: Element("e".to_string()),
-            second: Element("a".to_string()),
-            role: Value::Integer(123),
+    let expected = BTreeSet::from([
+        Entry::Role {
+            first: Element("e".to_string(), None, None),
+            second: Element("123".to_string(), None, None),
+            role: Element("a".to_string(), None, None),
         },
-        Role {
-            first: Element("e2".to_string()),
-            second: Element("a".to_string()),
-            role: Value::Name(Element("e".to_string())),
+        Entry::Role {
+            first: Element("e2".to_string(), None, None),
+            second: Element("e".to_string(), None, None),
+            role: Element("a".to_string(), None, None),
         },
-    ];
+    ]);
     assert_eq!(read(s)?, expected);
     Ok(())
 }
+
+#[test]
+fn read_extends_strings_and_comments() -> Result<(), LigatureError> {
+    let s = "-- people\n{\n  e : Person, -- extends\n  e ¬: Robot,\n  e name \"Bob \\\"B\\\" Smith\",\n  e \":\" f,\n}";
+    let expected = BTreeSet::from([
+        Entry::Extends {
+            element: Element("e".to_string(), None, None),
+            concept: Element("Person".to_string(), None, None),
+        },
+        Entry::NotExtends {
+            element: Element("e".to_string(), None, None),
+            concept: Element("Robot".to_string(), None, None),
+        },
+        Entry::Role {
+            first: Element("e".to_string(), None, None),
+            second: Element("Bob \"B\" Smith".to_string(), None, None),
+            role: Element("name".to_string(), None, None),
+        },
+        Entry::Role {
+            first: Element("e".to_string(), None, None),
+            second: Element("f".to_string(), None, None),
+            role: Element(":".to_string(), None, None),
+        },
+    ]);
+    assert_eq!(read(s)?, expected);
+    Ok(())
+}
+
+#[test]
+fn read_errors_include_location() {
+    let Location(LigatureError(message), offset) = read("{\n  a b }").unwrap_err();
+    assert_eq!(offset, 8);
+    assert!(message.contains("line 2, column 7"));
+    assert_eq!(read("{ a b c").unwrap_err().1, 7);
+    assert!(read("{ a b c } d").is_err());
+}
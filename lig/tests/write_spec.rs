@@ -2,24 +2,70 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use lig::write::write;
-use ligature::{Element, LigatureError, Role, Value};
+use lig::{read::read, write::write};
+use ligature::{Element, Entry, LangTag, LigatureError, Namespace};
+use std::collections::BTreeSet;
 
 #[test]
 fn write_set_of_statements() -> Result<(), LigatureError> {
-    let statements = vec![
-        Role {
-            first: Element("e".to_string()),
-            second: Element("a".to_string()),
-            role: Value::Integer(234),
+    let statements = BTreeSet::from([
+        Entry::Role {
+            first: Element("e".to_string(), None, None),
+            second: Element("234".to_string(), None, None),
+            role: Element("a".to_string(), None, None),
         },
-        Role {
-            first: Element("e".to_string()),
-            second: Element("a2".to_string()),
-            role: Value::String("test".to_string()),
+        Entry::Role {
+            first: Element("e".to_string(), None, None),
+            second: Element("test value".to_string(), None, None),
+            role: Element("a2".to_string(), None, None),
         },
-    ];
-    let expected = "<e> <a> 234\n<e> <a2> \"test\"\n";
-    //assert_eq!(write(statements.iter()), expected.to_string());
+    ]);
+    let expected = "{\n  e a 234,\n  e a2 \"test value\"\n}\n";
+    assert_eq!(write(&statements)?, expected.to_string());
     Ok(())
 }
+
+#[test]
+fn round_trip_every_entry_kind() -> Result<(), LigatureError> {
+    let statements = BTreeSet::from([
+        Entry::Role {
+            first: Element("e".to_string(), None, None),
+            second: Element("line\nbreak\t\"quoted\" \\ \u{1}".to_string(), None, None),
+            role: Element(":".to_string(), None, None),
+        },
+        Entry::Extends {
+            element: Element("e".to_string(), None, None),
+            concept: Element("¬:".to_string(), None, None),
+        },
+        Entry::NotExtends {
+            element: Element("e".to_string(), None, None),
+            concept: Element("Robot".to_string(), None, None),
+        },
+    ]);
+    assert_eq!(read(&write(&statements)?)?, statements);
+    assert_eq!(read(&write(&BTreeSet::new())?)?, BTreeSet::new());
+    Ok(())
+}
+
+#[test]
+fn elements_lig_cannot_represent_are_an_error() {
+    let namespaced = Element(
+        "alice".to_owned(),
+        Some(Namespace("http://example.com/".to_owned())),
+        None,
+    );
+    let tagged = Element("chat".to_owned(), None, Some(LangTag("fr".to_owned())));
+    for element in [namespaced, tagged] {
+        let statements = BTreeSet::from([Entry::Role {
+            first: Element::new("e"),
+            second: element.clone(),
+            role: Element::new("a"),
+        }]);
+        assert!(write(&statements).is_err());
+        let statements = BTreeSet::from([Entry::Extends {
+            element,
+            concept: Element::new("C"),
+        }]);
+        assert!(write(&statements).is_err());
+    }
+}
@@ -2,4 +2,79 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-//! This module is the main module for the ligature-repl project.
+//! A command line interface for running Wander scripts, exporting collections and backing up stores.
+
+use ligature::binary;
+use ligature::projection::{write_dot, write_gexf, write_graphml};
+use ligature::{Element, Ligature};
+use ligature_graph::LigatureGraph;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage:
+  ligature-cli run <script>
+  ligature-cli export <dot|graphml|gexf> <script> <collection>
+  ligature-cli dump <script> <file>
+  ligature-cli restore <file> <script>";
+
+fn run_script(path: &str, state: &mut LigatureGraph) -> Result<String, String> {
+    let script =
+        std::fs::read_to_string(path).map_err(|err| format!("Could not read {path}: {err}"))?;
+    let mut commands = wander::prelude::common();
+    commands.extend(tiny_dl::commands());
+    wander::run(&script, &commands, state)
+        .map(|result| result.to_string())
+        .map_err(|err| err.0)
+}
+
+fn export(format: &str, path: &str, collection: &str) -> Result<String, String> {
+    let write = match format {
+        "dot" => write_dot,
+        "graphml" => write_graphml,
+        "gexf" => write_gexf,
+        _ => return Err(format!("Unknown format `{format}`.")),
+    };
+    let mut state = LigatureGraph::new();
+    run_script(path, &mut state)?;
+    let entries = state
+        .entries(&Element(collection.to_owned(), None, None))
+        .map_err(|err| err.0)?;
+    Ok(write(&entries))
+}
+
+fn dump(script: &str, path: &str) -> Result<String, String> {
+    let mut state = LigatureGraph::new();
+    run_script(script, &mut state)?;
+    let file =
+        std::fs::File::create(path).map_err(|err| format!("Could not create {path}: {err}"))?;
+    binary::dump(&state, std::io::BufWriter::new(file)).map_err(|err| err.0)?;
+    Ok(String::new())
+}
+
+fn restore(path: &str, script: &str) -> Result<String, String> {
+    let file = std::fs::File::open(path).map_err(|err| format!("Could not read {path}: {err}"))?;
+    let mut state = LigatureGraph::new();
+    binary::load(&mut state, std::io::BufReader::new(file)).map_err(|err| err.0)?;
+    run_script(script, &mut state).map(|result| result + "\n")
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    let result = match &args[..] {
+        ["run", path] => run_script(path, &mut LigatureGraph::new()).map(|result| result + "\n"),
+        ["export", format, path, collection] => export(format, path, collection),
+        ["dump", script, path] => dump(script, path),
+        ["restore", path, script] => restore(path, script),
+        _ => Err(USAGE.to_owned()),
+    };
+    match result {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
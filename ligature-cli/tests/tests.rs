@@ -1,3 +1,93 @@
 // This Source Code Form is subject to the terms of the Mozilla Public
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::process::Command;
+
+/// A path in the temp directory that no other test run uses.
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("{}_{name}", std::process::id()))
+}
+
+fn script(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = temp_path(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn export_collection_as_dot() {
+    let path = script("ligature_cli_export.wander", "store people {alice knows bob}");
+    let output = Command::new(env!("CARGO_BIN_EXE_ligature-cli"))
+        .args(["export", "dot"])
+        .arg(&path)
+        .arg("people")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "digraph {\n  \"alice\";\n  \"bob\";\n  \"alice\" -> \"bob\" [label=\"knows\"];\n}\n"
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn unknown_format() {
+    let path = script("ligature_cli_unknown.wander", "ignore");
+    let output = Command::new(env!("CARGO_BIN_EXE_ligature-cli"))
+        .args(["export", "svg"])
+        .arg(&path)
+        .arg("people")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn run_with_tiny_dl() {
+    let path = script(
+        "ligature_cli_tiny_dl.wander",
+        "subsumes {Woman : Person} Person Woman",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_ligature-cli"))
+        .arg("run")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "\"true\"\n");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn dump_and_restore_a_store() {
+    let store = script(
+        "ligature_cli_dump.wander",
+        "store people {alice knows bob, alice : Person}",
+    );
+    let dump = temp_path("ligature_cli_dump.ligb");
+    let output = Command::new(env!("CARGO_BIN_EXE_ligature-cli"))
+        .arg("dump")
+        .arg(&store)
+        .arg(&dump)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let read = script("ligature_cli_restore.wander", "read people");
+    let output = Command::new(env!("CARGO_BIN_EXE_ligature-cli"))
+        .arg("restore")
+        .arg(&dump)
+        .arg(&read)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "{\"alice\" \"knows\" \"bob\", \"alice\" : \"Person\"}\n"
+    );
+    for path in [store, dump, read] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -4,8 +4,11 @@
 
 //! This module is an implementation of the an in-memory, non-transactional knowledge graph.
 
-use ligature::{Element, Entry, Ligature, LigatureError};
-use std::collections::BTreeSet;
+use ligature::json::element_from_json;
+use ligature::{Element, Entry, LangTag, Ligature, LigatureError, Namespace};
+use hashbag::HashBag;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
 use trips::mem::TripsMem;
 use trips::{Query, Slot, Trip, Trips};
 
@@ -33,47 +36,31 @@ impl Ligature for LigatureGraph {
     fn collections(&self) -> Result<Vec<Element>, LigatureError> {
         self.store
             .collections()
-            .map(|r| r.into_iter().map(|e| Element(e)).collect())
-            .map_err(|e| todo!())
+            .map_err(|e| LigatureError(e.0))?
+            .into_iter()
+            .map(|collection| decode(&collection))
+            .collect()
     }
 
     fn add_collection(&mut self, collection: Element) -> Result<(), LigatureError> {
-        self.store.add_collection(collection.0).map_err(|e| todo!())
+        self.store
+            .add_collection(encode(&collection))
+            .map_err(|e| todo!())
     }
 
     fn remove_collection(&mut self, collection: Element) -> Result<(), LigatureError> {
         self.store
-            .remove_collection(collection.0)
+            .remove_collection(encode(&collection))
             .map_err(|e| todo!())
     }
 
     fn entries(&self, collection: &Element) -> Result<BTreeSet<ligature::Entry>, LigatureError> {
         self.store
-            .triples(collection.clone().0)
-            .map(|set| {
-                set.into_iter()
-                    .map(|entry: Trip| {
-                        if entry.1 == ":".to_owned() {
-                            Entry::Extends {
-                                element: Element(entry.0),
-                                concept: Element(entry.2),
-                            }
-                        } else if entry.1 == "¬:".to_owned() {
-                            Entry::NotExtends {
-                                element: Element(entry.0),
-                                concept: Element(entry.2),
-                            }
-                        } else {
-                            Entry::Role {
-                                first: Element(entry.0),
-                                second: Element(entry.2),
-                                role: Element(entry.1),
-                            }
-                        }
-                    })
-                    .collect()
-            })
-            .map_err(|e| LigatureError(e.0))
+            .triples(encode(collection))
+            .map_err(|e| LigatureError(e.0))?
+            .into_iter()
+            .map(|trip| trip_to_entry(&trip))
+            .collect()
     }
 
     fn add_entries(
@@ -81,22 +68,9 @@ impl Ligature for LigatureGraph {
         collection: Element,
         entries: &mut BTreeSet<ligature::Entry>,
     ) -> Result<(), LigatureError> {
-        let mut triples: BTreeSet<Trip> =
-            BTreeSet::from_iter(entries.iter().map(|entry| match entry {
-                Entry::Extends { element, concept } => {
-                    Trip(element.clone().0, ":".to_owned(), concept.clone().0)
-                }
-                Entry::Role {
-                    first,
-                    second,
-                    role,
-                } => Trip(first.clone().0, role.clone().0, second.clone().0),
-                Entry::NotExtends { element, concept } => {
-                    Trip(element.clone().0, "¬:".to_owned(), concept.clone().0)
-                }
-            }));
+        let mut triples: BTreeSet<Trip> = entries.iter().map(entry_to_trip).collect();
         self.store
-            .add_triples(collection.0, &mut triples)
+            .add_triples(encode(&collection), &mut triples)
             .map_err(|e| todo!())
     }
 
@@ -105,22 +79,9 @@ impl Ligature for LigatureGraph {
         collection: Element,
         entries: &mut BTreeSet<ligature::Entry>,
     ) -> Result<(), LigatureError> {
-        let mut triples: BTreeSet<Trip> =
-            BTreeSet::from_iter(entries.iter().map(|entry| match entry {
-                Entry::Extends { element, concept } => {
-                    Trip(element.clone().0, ":".to_owned(), concept.clone().0)
-                }
-                Entry::Role {
-                    first,
-                    second,
-                    role,
-                } => Trip(first.clone().0, role.clone().0, second.clone().0),
-                Entry::NotExtends { element, concept } => {
-                    Trip(element.clone().0, "¬:".to_owned(), concept.clone().0)
-                }
-            }));
+        let mut triples: BTreeSet<Trip> = entries.iter().map(entry_to_trip).collect();
         self.store
-            .remove_triples(collection.0, &mut triples)
+            .remove_triples(encode(&collection), &mut triples)
             .map_err(|e| todo!())
     }
 
@@ -129,83 +90,127 @@ impl Ligature for LigatureGraph {
         collection: Element,
         pattern: Entry,
     ) -> Result<BTreeSet<Entry>, LigatureError> {
-        let query_pattern: Query =
-            match pattern {
-                Entry::Extends { element, concept } => Query(
-                    check_value(element.clone().0),
-                    Slot::Value(":".to_owned()),
-                    check_value(concept.clone().0),
-                ),
-                Entry::Role {
-                    first,
-                    second,
-                    role,
-                } => Query(
-                    check_value(first.clone().0),
-                    check_value(role.clone().0),
-                    check_value(second.clone().0),
-                ),
-                Entry::NotExtends { element, concept } => Query(
-                    check_value(element.clone().0),
-                    Slot::Value("¬:".to_owned()),
-                    check_value(concept.clone().0),
-                ),
-            };
         self.store
-            .filter(collection.0, query_pattern)
-            .map(|trips| {
-                trips.iter().map(|trip| {
-                    match trip.1.as_str() {
-                        ":" => Entry::Extends { element: Element(trip.0.clone()), concept: Element(trip.2.clone()) },
-                        "¬:" => Entry::NotExtends { element: Element(trip.0.clone()), concept: Element(trip.2.clone()) },
-                        _ => Entry::Role { first: Element(trip.0.clone()), second: Element(trip.2.clone()), role: Element(trip.1.clone()) }
-                    }
-                }).collect()
-            })
-            .map_err(|e| todo!())
+            .filter(encode(&collection), entry_to_query(&pattern))
+            .map_err(|e| LigatureError(e.0))?
+            .iter()
+            .map(trip_to_entry)
+            .collect()
+    }
+
+    fn query(
+        &self,
+        collection: Element,
+        pattern: BTreeSet<ligature::Entry>,
+    ) -> Result<HashBag<BTreeMap<String, Element>>, LigatureError> {
+        let query_pattern: BTreeSet<Query> = pattern.iter().map(entry_to_query).collect();
+        let results = self
+            .store
+            .query(encode(&collection), query_pattern)
+            .map_err(|e| LigatureError(e.0))?;
+        let mut bag = HashBag::new();
+        for (bindings, count) in results.set_iter() {
+            let mut result = BTreeMap::new();
+            for (name, value) in bindings {
+                result.insert(name.clone(), decode(value)?);
+            }
+            bag.insert_many(result, count);
+        }
+        Ok(bag)
     }
-    
-    // fn query(
-    //     &self,
-    //     collection: Element,
-    //     pattern: BTreeSet<ligature::Entry>,
-    // ) -> Result<HashBag<std::collections::BTreeMap<String, String>>, LigatureError> {
-    //     let query_pattern: BTreeSet<Query> =
-    //         BTreeSet::from_iter(pattern.iter().map(|entry| match entry {
-    //             Entry::Extends { element, concept } => Query(
-    //                 Slot::Value(element.clone().0),
-    //                 Slot::Value(":".to_owned()),
-    //                 Slot::Value(concept.clone().0),
-    //             ),
-    //             Entry::Role {
-    //                 first,
-    //                 second,
-    //                 role,
-    //             } => Query(
-    //                 Slot::Value(first.clone().0),
-    //                 Slot::Value(role.clone().0),
-    //                 Slot::Value(second.clone().0),
-    //             ),
-    //             Entry::NotExtends { element, concept } => Query(
-    //                 Slot::Value(element.clone().0),
-    //                 Slot::Value("¬:".to_owned()),
-    //                 Slot::Value(concept.clone().0),
-    //             ),
-    //         }));
-    //     self.store
-    //         .query(collection.0, query_pattern)
-    //         .map_err(|e| todo!())
-    // }
 }
 
-fn check_value(value: String) -> Slot {
-    if value == "?" {
-        Slot::Any
-    } else if value.starts_with("?") {
-        let mut chars = value.chars();
-        chars.next();
-        Slot::Variable(chars.as_str().to_owned())
+/// Trips only stores strings, so Elements with a Namespace or LangTag are stored as JSON objects.
+/// Other Elements are stored as their value, unless it could be mistaken for a JSON object
+/// or for the `:` and `¬:` that Extends and NotExtends are stored with.
+fn encode(element: &Element) -> String {
+    match element {
+        Element(value, None, None) if !value.starts_with('{') && value != ":" && value != "¬:" => {
+            value.clone()
+        }
+        Element(value, namespace, lang) => {
+            let mut object = Map::new();
+            object.insert("value".to_owned(), Value::String(value.clone()));
+            if let Some(Namespace(namespace)) = namespace {
+                object.insert("namespace".to_owned(), Value::String(namespace.clone()));
+            }
+            if let Some(LangTag(lang)) = lang {
+                object.insert("lang".to_owned(), Value::String(lang.clone()));
+            }
+            Value::Object(object).to_string()
+        }
+    }
+}
+
+fn decode(value: &str) -> Result<Element, LigatureError> {
+    if value.starts_with('{') {
+        let json = serde_json::from_str(value).map_err(|e| LigatureError(e.to_string()))?;
+        element_from_json(&json)
     } else {
-        Slot::Value(value.to_owned())
+        Ok(Element(value.to_owned(), None, None))
     }
-}
\ No newline at end of file
+}
+
+fn entry_to_trip(entry: &Entry) -> Trip {
+    match entry {
+        Entry::Extends { element, concept } => {
+            Trip(encode(element), ":".to_owned(), encode(concept))
+        }
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => Trip(encode(first), encode(role), encode(second)),
+        Entry::NotExtends { element, concept } => {
+            Trip(encode(element), "¬:".to_owned(), encode(concept))
+        }
+    }
+}
+
+fn trip_to_entry(trip: &Trip) -> Result<Entry, LigatureError> {
+    Ok(match trip.1.as_str() {
+        ":" => Entry::Extends {
+            element: decode(&trip.0)?,
+            concept: decode(&trip.2)?,
+        },
+        "¬:" => Entry::NotExtends {
+            element: decode(&trip.0)?,
+            concept: decode(&trip.2)?,
+        },
+        _ => Entry::Role {
+            first: decode(&trip.0)?,
+            second: decode(&trip.2)?,
+            role: decode(&trip.1)?,
+        },
+    })
+}
+
+fn entry_to_query(entry: &Entry) -> Query {
+    match entry {
+        Entry::Extends { element, concept } => Query(
+            check_value(element),
+            Slot::Value(":".to_owned()),
+            check_value(concept),
+        ),
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => Query(check_value(first), check_value(role), check_value(second)),
+        Entry::NotExtends { element, concept } => Query(
+            check_value(element),
+            Slot::Value("¬:".to_owned()),
+            check_value(concept),
+        ),
+    }
+}
+
+fn check_value(element: &Element) -> Slot {
+    match element {
+        Element(value, None, None) if value == "?" => Slot::Any,
+        Element(value, None, None) if value.starts_with('?') => {
+            Slot::Variable(value[1..].to_owned())
+        }
+        element => Slot::Value(encode(element)),
+    }
+}
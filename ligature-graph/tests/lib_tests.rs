@@ -2,10 +2,11 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use hashbag::HashBag;
-use ligature::{Element, Entry, Ligature};
+use ligature::shapes::{read_shapes, Validated};
+use ligature::{Element, Entry, LangTag, Ligature, Namespace};
 use ligature_graph::LigatureGraph;
-use std::collections::BTreeSet;
+use hashbag::HashBag;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[test]
 fn empty_graph() {
@@ -16,19 +17,26 @@ fn empty_graph() {
 #[test]
 fn search_empty_graph() {
     let mut g = LigatureGraph::new();
-    g.add_collection(Element("test".to_owned()));
+    assert_eq!(g.add_collection(Element("test".to_owned(), None, None)), Ok(()));
     assert_eq!(
-        g.query(Element("test".to_owned()), BTreeSet::new())
-            .unwrap(),
-        HashBag::new()
+        g.filter(
+            Element("test".to_owned(), None, None),
+            Entry::Role {
+                first: Element("?".to_owned(), None, None),
+                second: Element("?".to_owned(), None, None),
+                role: Element("?".to_owned(), None, None),
+            }
+        )
+        .unwrap(),
+        BTreeSet::new()
     );
 }
 
 fn statement() -> Entry {
     Entry::Role {
-        first: Element("a".to_owned()),
-        second: Element("b".to_owned()),
-        role: Element("c".to_owned()),
+        first: Element("a".to_owned(), None, None),
+        second: Element("b".to_owned(), None, None),
+        role: Element("c".to_owned(), None, None),
     }
 }
 
@@ -36,19 +44,19 @@ fn statements() -> BTreeSet<Entry> {
     let mut statements = BTreeSet::new();
     for statement in vec![
         Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("b".to_owned()),
-            role: Element("c".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("b".to_owned(), None, None),
+            role: Element("c".to_owned(), None, None),
         },
         Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("b".to_owned()),
-            role: Element("d".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("b".to_owned(), None, None),
+            role: Element("d".to_owned(), None, None),
         },
         Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("e".to_owned()),
-            role: Element("f".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("e".to_owned(), None, None),
+            role: Element("f".to_owned(), None, None),
         },
     ] {
         statements.insert(statement);
@@ -60,14 +68,14 @@ fn statements_res() -> BTreeSet<Entry> {
     let mut statements = BTreeSet::new();
     for statement in vec![
         Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("b".to_owned()),
-            role: Element("c".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("b".to_owned(), None, None),
+            role: Element("c".to_owned(), None, None),
         },
         Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("b".to_owned()),
-            role: Element("d".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("b".to_owned(), None, None),
+            role: Element("d".to_owned(), None, None),
         },
     ] {
         statements.insert(statement);
@@ -80,12 +88,27 @@ fn single_element_graph() {
     let mut entries: BTreeSet<Entry> = BTreeSet::new();
     entries.insert(statement());
     let mut g = LigatureGraph::new();
-    g.add_collection(Element("test".to_owned()));
-    g.add_entries(Element("test".to_owned()), &mut entries);
+    assert_eq!(g.add_collection(Element("test".to_owned(), None, None)), Ok(()));
+    assert_eq!(
+        g.add_entries(Element("test".to_owned(), None, None), &mut entries),
+        Ok(())
+    );
     let mut res1 = BTreeSet::new();
     res1.insert(statement());
-    assert_eq!(g.entries(Element("test".to_owned())).unwrap(), res1);
-    //assert_eq!(g.query(Element("test".to_owned()), BTreeSet::new()).unwrap(), res1);
+    assert_eq!(g.entries(&Element("test".to_owned(), None, None)).unwrap(), res1);
+    let pattern = BTreeSet::from([Entry::Role {
+        first: Element("?first".to_owned(), None, None),
+        second: Element("?second".to_owned(), None, None),
+        role: Element("c".to_owned(), None, None),
+    }]);
+    let bindings = BTreeMap::from([
+        ("first".to_owned(), Element("a".to_owned(), None, None)),
+        ("second".to_owned(), Element("b".to_owned(), None, None)),
+    ]);
+    assert_eq!(
+        g.query(Element("test".to_owned(), None, None), pattern).unwrap(),
+        HashBag::from_iter([bindings])
+    );
     // assert_eq!(
     //     g.find(Some(Identifier::new("a").unwrap()), None, None),
     //     res1
@@ -96,6 +119,61 @@ fn single_element_graph() {
     // );
 }
 
+#[test]
+fn namespaces_and_lang_tags_are_kept() {
+    let namespaced = |value: &str| {
+        Element(
+            value.to_owned(),
+            Some(Namespace("https://example.com/".to_owned())),
+            None,
+        )
+    };
+    let tagged =
+        |value: &str, tag: &str| Element(value.to_owned(), None, Some(LangTag(tag.to_owned())));
+    let collection = namespaced("people");
+    let mut entries = BTreeSet::from([
+        Entry::role(
+            namespaced("alice"),
+            namespaced("name"),
+            tagged("Alice", "en"),
+        ),
+        Entry::role(namespaced("alice"), "name", "Alice"),
+        Entry::role("alice", ":", "Person"),
+        Entry::extends(namespaced("alice"), namespaced("Person")),
+        Entry::role("{\"value\":\"alice\"}", "knows", "alice"),
+    ]);
+    let mut g = LigatureGraph::new();
+    assert_eq!(g.add_collection(collection.clone()), Ok(()));
+    assert_eq!(g.add_entries(collection.clone(), &mut entries), Ok(()));
+    assert_eq!(g.collections().unwrap(), vec![collection.clone()]);
+    assert_eq!(g.entries(&collection).unwrap(), entries);
+    let pattern = BTreeSet::from([Entry::role("?person", namespaced("name"), "?name")]);
+    let bindings = BTreeMap::from([
+        ("person".to_owned(), namespaced("alice")),
+        ("name".to_owned(), tagged("Alice", "en")),
+    ]);
+    assert_eq!(
+        g.query(collection.clone(), pattern).unwrap(),
+        HashBag::from_iter([bindings])
+    );
+    assert_eq!(
+        g.filter(
+            collection.clone(),
+            Entry::role("?", "?", tagged("Alice", "en"))
+        )
+        .unwrap(),
+        BTreeSet::from([Entry::role(
+            namespaced("alice"),
+            namespaced("name"),
+            tagged("Alice", "en")
+        )])
+    );
+    assert_eq!(
+        g.filter(collection, Entry::extends("alice", "?")).unwrap(),
+        BTreeSet::new()
+    );
+}
+
 // #[test]
 // fn multi_statement_graph() {
 //     let g = Graph::new(statements());
@@ -127,3 +205,37 @@ fn single_element_graph() {
 //     let g3 = g1.add_all(g2);
 //     assert_eq!(g3, Graph::new(statements()));
 // }
+
+#[test]
+fn invalid_writes_are_rejected() {
+    let people = Element::new("people");
+    let shapes = BTreeSet::from([
+        Entry::role("personName", "concept", "Person"),
+        Entry::role("personName", "role", "name"),
+        Entry::role("personName", "minCount", "1"),
+    ]);
+    let mut ligature = Validated::new(LigatureGraph::new());
+    ligature.set_shapes(people.clone(), read_shapes(&shapes).unwrap());
+    ligature.add_collection(people.clone()).unwrap();
+    assert!(ligature
+        .add_entries(
+            people.clone(),
+            &mut BTreeSet::from([Entry::extends("alice", "Person")])
+        )
+        .is_err());
+    assert_eq!(ligature.entries(&people), Ok(BTreeSet::new()));
+    let valid = BTreeSet::from([
+        Entry::extends("alice", "Person"),
+        Entry::role("alice", "name", "Alice"),
+    ]);
+    ligature
+        .add_entries(people.clone(), &mut valid.clone())
+        .unwrap();
+    assert!(ligature
+        .remove_entries(
+            people.clone(),
+            &mut BTreeSet::from([Entry::role("alice", "name", "Alice")])
+        )
+        .is_err());
+    assert_eq!(ligature.entries(&people), Ok(valid));
+}
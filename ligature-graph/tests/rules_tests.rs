@@ -0,0 +1,125 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::rules::{infer_into, instantiate, Bindings, Rule};
+use ligature::{Element, Entry, Ligature};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+
+fn transitivity() -> Rule {
+    Rule::new(
+        BTreeSet::from([Entry::extends("?a", "?b"), Entry::extends("?b", "?c")]),
+        BTreeSet::from([Entry::extends("?a", "?c")]),
+    )
+    .unwrap()
+}
+
+fn infer(entries: &BTreeSet<Entry>, rules: &[Rule]) -> BTreeSet<Entry> {
+    infer_into(
+        &mut LigatureGraph::new(),
+        Element::new("test"),
+        entries,
+        rules,
+    )
+    .unwrap()
+}
+
+#[test]
+fn instantiate_conclusions() {
+    let bindings = Bindings::from([("a".to_owned(), Element::new("rex"))]);
+    assert_eq!(
+        instantiate(&Entry::extends("?a", "Dog"), &bindings),
+        Ok(Entry::extends("rex", "Dog"))
+    );
+    assert!(instantiate(&Entry::extends("?a", "?b"), &bindings).is_err());
+}
+
+#[test]
+fn infer_transitive_concepts() {
+    let entries = BTreeSet::from([
+        Entry::extends("rex", "Dog"),
+        Entry::extends("Dog", "Mammal"),
+        Entry::extends("Mammal", "Animal"),
+    ]);
+    let expected = BTreeSet::from([
+        Entry::extends("rex", "Mammal"),
+        Entry::extends("rex", "Animal"),
+        Entry::extends("Dog", "Animal"),
+    ]);
+    assert_eq!(infer(&entries, &[transitivity()]), expected);
+}
+
+#[test]
+fn infer_with_several_rules() {
+    let symmetric = Rule::new(
+        BTreeSet::from([Entry::role("?a", "sibling", "?b")]),
+        BTreeSet::from([Entry::role("?b", "sibling", "?a")]),
+    )
+    .unwrap();
+    let person = Rule::new(
+        BTreeSet::from([Entry::role("?a", "sibling", "?")]),
+        BTreeSet::from([Entry::extends("?a", "Person")]),
+    )
+    .unwrap();
+    let entries = BTreeSet::from([Entry::role("alice", "sibling", "bob")]);
+    let expected = BTreeSet::from([
+        Entry::role("bob", "sibling", "alice"),
+        Entry::extends("alice", "Person"),
+        Entry::extends("bob", "Person"),
+    ]);
+    assert_eq!(infer(&entries, &[symmetric, person]), expected);
+}
+
+#[test]
+fn infer_nothing_new() {
+    let entries = BTreeSet::from([Entry::extends("rex", "Dog")]);
+    assert_eq!(infer(&entries, &[transitivity()]), BTreeSet::new());
+}
+
+#[test]
+fn infer_only_from_added_entries() {
+    let mut ligature = LigatureGraph::new();
+    let collection = Element::new("animals");
+    let first = BTreeSet::from([
+        Entry::extends("rex", "Dog"),
+        Entry::extends("Dog", "Mammal"),
+    ]);
+    let res = infer_into(&mut ligature, collection.clone(), &first, &[transitivity()]);
+    assert_eq!(res, Ok(BTreeSet::from([Entry::extends("rex", "Mammal")])));
+    let res = infer_into(&mut ligature, collection.clone(), &first, &[transitivity()]);
+    assert_eq!(res, Ok(BTreeSet::new()));
+    let second = BTreeSet::from([Entry::extends("Mammal", "Animal")]);
+    let res = infer_into(
+        &mut ligature,
+        collection.clone(),
+        &second,
+        &[transitivity()],
+    );
+    assert_eq!(
+        res,
+        Ok(BTreeSet::from([
+            Entry::extends("rex", "Animal"),
+            Entry::extends("Dog", "Animal"),
+        ]))
+    );
+    assert_eq!(
+        ligature.entries(&collection).map(|entries| entries.len()),
+        Ok(6)
+    );
+}
+
+#[test]
+fn invalid_rules() {
+    assert!(Rule::new(BTreeSet::new(), BTreeSet::from([Entry::extends("a", "b")])).is_err());
+    assert!(Rule::new(
+        BTreeSet::from([Entry::extends("?a", "?b")]),
+        BTreeSet::from([Entry::extends("?a", "?c")])
+    )
+    .is_err());
+    assert!(Rule::new(
+        BTreeSet::from([Entry::extends("?a", "?b")]),
+        BTreeSet::from([Entry::extends("?a", "?")])
+    )
+    .is_err());
+}
@@ -7,10 +7,12 @@
 mod utils;
 use std::collections::{self, BTreeSet, HashMap};
 
-use ligature::{Entry, Ligature};
+use ligature::projection::project;
+use ligature::shapes;
+use ligature::{Element, Entry, Ligature};
 use ligature_graph::LigatureGraph;
 use serde::Serialize;
-use wander::{WanderError, WanderValue};
+use wander::{Command, WanderValue};
 use wasm_bindgen::prelude::*;
 
 #[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -32,58 +34,22 @@ pub struct EdgeResult {
 }
 
 fn graph_to_graphology(graph: BTreeSet<Entry>) -> GraphologyResult {
-    let mut nodes = BTreeSet::new();
-    let mut edges = BTreeSet::new();
-    for entry in graph {
-        match entry {
-            Entry::Extends { element, concept } => {
-                nodes.insert(NodeResult {
-                    key: element.clone().0,
-                });
-                nodes.insert(NodeResult {
-                    key: concept.clone().0,
-                });
-                edges.insert(EdgeResult {
-                    key: ":".to_owned(),
-                    source: element.0,
-                    target: concept.0,
-                });
-            }
-            Entry::NotExtends { element, concept } => {
-                nodes.insert(NodeResult {
-                    key: element.clone().0,
-                });
-                nodes.insert(NodeResult {
-                    key: concept.clone().0,
-                });
-                edges.insert(EdgeResult {
-                    key: "¬:".to_owned(),
-                    source: element.0,
-                    target: concept.0,
-                });
-            }
-            Entry::Role {
-                first,
-                second,
-                role,
-            } => {
-                nodes.insert(NodeResult {
-                    key: first.clone().0,
-                });
-                nodes.insert(NodeResult {
-                    key: second.clone().0,
-                });
-                edges.insert(EdgeResult {
-                    key: role.0,
-                    source: first.0,
-                    target: second.0,
-                });
-            }
-        }
-    }
+    let projection = project(&graph);
     GraphologyResult {
-        nodes: nodes,
-        edges: edges,
+        nodes: projection
+            .nodes
+            .into_iter()
+            .map(|node| NodeResult { key: node.key })
+            .collect(),
+        edges: projection
+            .edges
+            .into_iter()
+            .map(|edge| EdgeResult {
+                key: edge.key,
+                source: edge.source,
+                target: edge.target,
+            })
+            .collect(),
     }
 }
 
@@ -105,12 +71,55 @@ pub fn ligature_to_graphology(ligature: &dyn Ligature) -> JsValue {
     serde_wasm_bindgen::to_value(&res).unwrap()
 }
 
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(message: &str);
+}
+
+fn bindings() -> HashMap<String, Command> {
+    let mut bindings = wander::prelude::common();
+    bindings.insert(
+        "log".to_owned(),
+        Command {
+            doc: "Write each argument to the browser's console.".to_owned(),
+            parameters: None,
+            fun: Box::new(|arguments, _, _| {
+                for argument in &arguments {
+                    log(&argument.to_string());
+                }
+                Ok(WanderValue::Network(BTreeSet::new()))
+            }),
+        },
+    );
+    bindings
+}
+
 #[wasm_bindgen]
 pub fn run(script: String) -> JsValue {
     let mut state = LigatureGraph::new();
-    let mut bindings = wander::prelude::common();
-    match wander::run(&script, &bindings, &mut state) {
+    match wander::run(&script, &bindings(), &mut state) {
         Ok(_) => ligature_to_graphology(&state),
         Err(err) => serde_wasm_bindgen::to_value(&err).unwrap(),
     }
 }
+
+/// Run a script, then check a collection against the shapes stored in another collection.
+/// Returns the list of violations, or an error message.
+#[wasm_bindgen]
+pub fn validate(script: String, collection: String, shapes: String) -> JsValue {
+    let mut state = LigatureGraph::new();
+    if let Err(err) = wander::run(&script, &bindings(), &mut state) {
+        return serde_wasm_bindgen::to_value(&err).unwrap();
+    }
+    let result = state
+        .entries(&Element(collection, None, None))
+        .and_then(|entries| {
+            let shapes = state.entries(&Element(shapes, None, None))?;
+            Ok(shapes::validate(&entries, &shapes::read_shapes(&shapes)?))
+        });
+    match result {
+        Ok(violations) => serde_wasm_bindgen::to_value(&violations).unwrap(),
+        Err(err) => serde_wasm_bindgen::to_value(&err.0).unwrap(),
+    }
+}
@@ -3,26 +3,72 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 //! A server for Ligature using ZeroMQ servers.
+//!
+//! Requests are Wander scripts, networks are returned in the binary format and other results as text.
+//! A request in the binary format is a store dump, its Collections are added to the store
+//! and a dump of the whole store is returned.
 
+use std::cell::Cell;
 use std::fmt::{Debug, Display};
+use std::rc::Rc;
 
-use wander::run;
+use ligature::binary;
+use ligature::Element;
+use wander::{run, Command, WanderValue};
 use zmq::{Context, Message, SocketType::REP};
 
+const ADDRESS: &str = "tcp://127.0.0.1:4200";
+
 fn main() {
     let ctx = Context::new();
     let responder = ctx.socket(REP).unwrap();
-    responder.bind("tcp://127.0.0.1:4200").unwrap();
+    responder.bind(ADDRESS).unwrap();
     let mut msg = Message::new();
-    let commands = wander::prelude::common();
+    let mut commands = wander::prelude::common();
+    commands.insert(
+        "server-address".to_owned(),
+        Command {
+            doc: "The address this server is listening on.".to_owned(),
+            parameters: Some(vec![]),
+            fun: Box::new(|_, _, _| {
+                Ok(WanderValue::Element(Element(ADDRESS.to_owned(), None, None)))
+            }),
+        },
+    );
+    let requests = Rc::new(Cell::new(0u64));
+    let counter = requests.clone();
+    commands.insert(
+        "request-count".to_owned(),
+        Command {
+            doc: "The number of requests this server has received.".to_owned(),
+            parameters: Some(vec![]),
+            fun: Box::new(move |_, _, _| {
+                Ok(WanderValue::Element(Element(
+                    counter.get().to_string(),
+                    None,
+                    None,
+                )))
+            }),
+        },
+    );
     let mut state = ligature_graph::LigatureGraph::new();
     loop {
         responder.recv(&mut msg, 0).unwrap();
-        let query = msg.as_str().unwrap();
-        println!("Received {}", query);
-        match run(query, &commands, &mut state) {
-            Ok(res) => responder.send(&res.to_string(), 0).unwrap(),
-            Err(err) => responder.send(&err.0, 0).unwrap(),
-        }
+        requests.set(requests.get() + 1);
+        let response = if msg.starts_with(binary::MAGIC) {
+            println!("Received a dump");
+            binary::load(&mut state, &msg[..])
+                .and_then(|_| binary::dump(&state, vec![]))
+                .unwrap_or_else(|err| err.0.into_bytes())
+        } else {
+            let query = msg.as_str().unwrap();
+            println!("Received {}", query);
+            match run(query, &commands, &mut state) {
+                Ok(WanderValue::Network(network)) => binary::write_network(&network),
+                Ok(res) => res.to_string().into_bytes(),
+                Err(err) => err.0.into_bytes(),
+            }
+        };
+        responder.send(response, 0).unwrap();
     }
 }
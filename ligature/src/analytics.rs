@@ -0,0 +1,256 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module runs graph algorithms over the nodes and edges of a [Projection].
+//!
+//! Every Entry is an edge, including `Extends` and `NotExtends` Entries.
+//! Each algorithm has a matching function writing its results as Entries,
+//! for example `alice pageRank 0.250000`, so they can be stored next to the network they describe.
+
+use crate::projection::Projection;
+use crate::{Element, Entry};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The number of edges entering and leaving a node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Degree {
+    /// Edges ending at the node.
+    pub incoming: usize,
+    /// Edges starting at the node.
+    pub outgoing: usize,
+}
+
+/// The nodes of a projection numbered by key, with each edge as a pair of node numbers.
+struct Graph<'a> {
+    keys: Vec<&'a str>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(projection: &'a Projection) -> Self {
+        let keys: Vec<&str> = projection
+            .nodes
+            .iter()
+            .map(|node| node.key.as_str())
+            .collect();
+        let index: BTreeMap<&str, usize> =
+            keys.iter().enumerate().map(|(i, key)| (*key, i)).collect();
+        let edges = projection
+            .edges
+            .iter()
+            .map(|edge| (index[edge.source.as_str()], index[edge.target.as_str()]))
+            .collect();
+        Self { keys, edges }
+    }
+
+    /// The nodes each node shares an edge with, ignoring direction.
+    fn neighbours(&self) -> Vec<Vec<usize>> {
+        let mut result = vec![vec![]; self.keys.len()];
+        for (source, target) in &self.edges {
+            if source != target {
+                result[*source].push(*target);
+                result[*target].push(*source);
+            }
+        }
+        result
+    }
+
+    fn by_key<T>(&self, values: Vec<T>) -> BTreeMap<String, T> {
+        self.keys
+            .iter()
+            .map(|key| (*key).to_owned())
+            .zip(values)
+            .collect()
+    }
+}
+
+/// Count the edges entering and leaving each node.
+pub fn degrees(projection: &Projection) -> BTreeMap<String, Degree> {
+    let graph = Graph::new(projection);
+    let mut result = vec![Degree::default(); graph.keys.len()];
+    for (source, target) in &graph.edges {
+        result[*source].outgoing += 1;
+        result[*target].incoming += 1;
+    }
+    graph.by_key(result)
+}
+
+/// Group nodes that are connected, ignoring the direction of edges.
+/// Components are ordered by their first key.
+pub fn connected_components(projection: &Projection) -> Vec<BTreeSet<String>> {
+    let graph = Graph::new(projection);
+    let neighbours = graph.neighbours();
+    let mut seen = vec![false; graph.keys.len()];
+    let mut result = vec![];
+    for start in 0..graph.keys.len() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut component = BTreeSet::new();
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            component.insert(graph.keys[current].to_owned());
+            for next in &neighbours[current] {
+                if !seen[*next] {
+                    seen[*next] = true;
+                    stack.push(*next);
+                }
+            }
+        }
+        result.push(component);
+    }
+    result
+}
+
+/// Rank nodes by the edges pointing to them, weighted by the rank of their sources.
+/// `damping` is the chance of following an edge rather than jumping to a random node, usually `0.85`.
+/// The ranks add up to one.
+pub fn page_rank(projection: &Projection, damping: f64) -> BTreeMap<String, f64> {
+    let graph = Graph::new(projection);
+    let count = graph.keys.len();
+    if count == 0 {
+        return BTreeMap::new();
+    }
+    let mut outgoing = vec![0usize; count];
+    for (source, _) in &graph.edges {
+        outgoing[*source] += 1;
+    }
+    let mut ranks = vec![1.0 / count as f64; count];
+    for _ in 0..100 {
+        let dangling: f64 = (0..count)
+            .filter(|node| outgoing[*node] == 0)
+            .map(|node| ranks[node])
+            .sum();
+        let base = (1.0 - damping + damping * dangling) / count as f64;
+        let mut next = vec![base; count];
+        for (source, target) in &graph.edges {
+            next[*target] += damping * ranks[*source] / outgoing[*source] as f64;
+        }
+        let change: f64 = ranks.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if change < 1e-10 {
+            break;
+        }
+    }
+    graph.by_key(ranks)
+}
+
+/// Group nodes into communities, ignoring the direction of edges.
+/// Each node in turn moves to the neighbouring community that most improves the modularity of the grouping,
+/// until no move helps. This is the first phase of the Louvain method.
+/// Communities are numbered from zero in the order of their first key.
+pub fn communities(projection: &Projection) -> BTreeMap<String, usize> {
+    let graph = Graph::new(projection);
+    let neighbours = graph.neighbours();
+    let degrees: Vec<f64> = neighbours.iter().map(|next| next.len() as f64).collect();
+    let total: f64 = degrees.iter().sum();
+    let mut labels: Vec<usize> = (0..graph.keys.len()).collect();
+    let mut totals = degrees.clone();
+    for _ in 0..100 {
+        let mut changed = false;
+        for node in 0..graph.keys.len() {
+            let current = labels[node];
+            totals[current] -= degrees[node];
+            let mut weights: BTreeMap<usize, f64> = BTreeMap::from([(current, 0.0)]);
+            for next in &neighbours[node] {
+                *weights.entry(labels[*next]).or_default() += 1.0;
+            }
+            let gain = |label: usize| weights[&label] - totals[label] * degrees[node] / total;
+            // Ties keep the node where it is, then go to the smallest label.
+            let mut best = current;
+            for label in weights.keys() {
+                if gain(*label) > gain(best) {
+                    best = *label;
+                }
+            }
+            totals[best] += degrees[node];
+            if best != current {
+                labels[node] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut numbers: BTreeMap<usize, usize> = BTreeMap::new();
+    let result = labels
+        .iter()
+        .map(|label| {
+            let next = numbers.len();
+            *numbers.entry(*label).or_insert(next)
+        })
+        .collect();
+    graph.by_key(result)
+}
+
+/// The Element of each node, by key.
+fn elements(projection: &Projection) -> BTreeMap<&str, &Element> {
+    projection
+        .nodes
+        .iter()
+        .map(|node| (node.key.as_str(), &node.element))
+        .collect()
+}
+
+/// Write each node's degrees as `node inDegree n` and `node outDegree n` Entries.
+pub fn degree_entries(projection: &Projection) -> BTreeSet<Entry> {
+    let elements = elements(projection);
+    degrees(projection)
+        .iter()
+        .flat_map(|(key, degree)| {
+            let element = elements[key.as_str()].clone();
+            [
+                Entry::role(element.clone(), "inDegree", degree.incoming.to_string()),
+                Entry::role(element, "outDegree", degree.outgoing.to_string()),
+            ]
+        })
+        .collect()
+}
+
+/// Write each node's component as a `node component n` Entry.
+pub fn component_entries(projection: &Projection) -> BTreeSet<Entry> {
+    let elements = elements(projection);
+    connected_components(projection)
+        .iter()
+        .enumerate()
+        .flat_map(|(i, component)| {
+            let elements = &elements;
+            component.iter().map(move |key| {
+                Entry::role(elements[key.as_str()].clone(), "component", i.to_string())
+            })
+        })
+        .collect()
+}
+
+/// Write each node's rank as a `node pageRank r` Entry, with six decimal places.
+pub fn page_rank_entries(projection: &Projection, damping: f64) -> BTreeSet<Entry> {
+    let elements = elements(projection);
+    page_rank(projection, damping)
+        .iter()
+        .map(|(key, rank)| {
+            Entry::role(
+                elements[key.as_str()].clone(),
+                "pageRank",
+                format!("{rank:.6}"),
+            )
+        })
+        .collect()
+}
+
+/// Write each node's community as a `node community n` Entry.
+pub fn community_entries(projection: &Projection) -> BTreeSet<Entry> {
+    let elements = elements(projection);
+    communities(projection)
+        .iter()
+        .map(|(key, community)| {
+            Entry::role(
+                elements[key.as_str()].clone(),
+                "community",
+                community.to_string(),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,476 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module implements a compact, versioned binary encoding for networks and Collections.
+//!
+//! A stream starts with the four bytes `LIGB` followed by a single version byte.
+//! The rest of the stream is a sequence of records, each starting with a tag byte.
+//!
+//! | Tag    | Record                                                     |
+//! | ------ | ---------------------------------------------------------- |
+//! | `0x00` | `Entry::Role`, followed by first, role and second Elements |
+//! | `0x01` | `Entry::Extends`, followed by element and concept Elements |
+//! | `0x02` | `Entry::NotExtends`, followed by element and concept       |
+//! | `0x03` | Start of a Collection, followed by the Collection's name   |
+//! | `0xFF` | End of stream, followed by a CRC-32 of all previous bytes  |
+//!
+//! Elements are dictionary encoded.
+//! Each Element is written as an unsigned LEB128 varint id, ids are assigned in order of first use.
+//! When the id is equal to the number of Elements seen so far, the Element's definition follows:
+//! a flags byte (`0x01` when a Namespace is present and `0x02` when a LangTag is present)
+//! and then the value, Namespace and LangTag as varint length prefixed UTF-8 strings.
+//!
+//! The checksum is written as four little-endian bytes.
+//! When a whole network or store is decoded nothing may follow the checksum.
+
+use crate::{
+    restore, snapshot, Collections, Element, Entry, LangTag, Ligature, LigatureError, Namespace,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Write};
+
+/// The bytes every binary stream starts with.
+pub const MAGIC: &[u8; 4] = b"LIGB";
+
+/// The current version of the binary format.
+pub const VERSION: u8 = 1;
+
+const ROLE: u8 = 0x00;
+const EXTENDS: u8 = 0x01;
+const NOT_EXTENDS: u8 = 0x02;
+const COLLECTION: u8 = 0x03;
+const END: u8 = 0xFF;
+
+const HAS_NAMESPACE: u8 = 0x01;
+const HAS_LANG_TAG: u8 = 0x02;
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+fn update_crc(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for byte in bytes {
+        crc = CRC_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+fn io_error(err: std::io::Error) -> LigatureError {
+    LigatureError(format!("Error in binary stream: {err}"))
+}
+
+/// A single record read from a binary stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    /// The following Entries belong to this Collection.
+    Collection(Element),
+    /// An Entry.
+    Entry(Entry),
+}
+
+/// Writes networks and Collections to a binary stream one record at a time.
+pub struct Encoder<W: Write> {
+    writer: W,
+    crc: u32,
+    dictionary: HashMap<Element, u64>,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Create an Encoder and write the stream's header.
+    pub fn new(writer: W) -> Result<Self, LigatureError> {
+        let mut encoder = Self {
+            writer,
+            crc: 0,
+            dictionary: HashMap::new(),
+        };
+        encoder.write(MAGIC)?;
+        encoder.write(&[VERSION])?;
+        Ok(encoder)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), LigatureError> {
+        self.crc = update_crc(self.crc, bytes);
+        self.writer.write_all(bytes).map_err(io_error)
+    }
+
+    fn varint(&mut self, mut value: u64) -> Result<(), LigatureError> {
+        let mut bytes = vec![];
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            } else {
+                bytes.push(byte | 0x80);
+            }
+        }
+        self.write(&bytes)
+    }
+
+    fn string(&mut self, value: &str) -> Result<(), LigatureError> {
+        self.varint(value.len() as u64)?;
+        self.write(value.as_bytes())
+    }
+
+    fn element(&mut self, element: &Element) -> Result<(), LigatureError> {
+        if let Some(id) = self.dictionary.get(element) {
+            return self.varint(*id);
+        }
+        let id = self.dictionary.len() as u64;
+        self.dictionary.insert(element.clone(), id);
+        self.varint(id)?;
+        let Element(value, namespace, lang) = element;
+        let mut flags = 0;
+        if namespace.is_some() {
+            flags |= HAS_NAMESPACE;
+        }
+        if lang.is_some() {
+            flags |= HAS_LANG_TAG;
+        }
+        self.write(&[flags])?;
+        self.string(value)?;
+        if let Some(Namespace(namespace)) = namespace {
+            self.string(namespace)?;
+        }
+        if let Some(LangTag(lang)) = lang {
+            self.string(lang)?;
+        }
+        Ok(())
+    }
+
+    /// Start a Collection, all following Entries are added to it.
+    pub fn collection(&mut self, collection: &Element) -> Result<(), LigatureError> {
+        self.write(&[COLLECTION])?;
+        self.element(collection)
+    }
+
+    /// Write a single Entry.
+    pub fn entry(&mut self, entry: &Entry) -> Result<(), LigatureError> {
+        match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => {
+                self.write(&[ROLE])?;
+                self.element(first)?;
+                self.element(role)?;
+                self.element(second)
+            }
+            Entry::Extends { element, concept } => {
+                self.write(&[EXTENDS])?;
+                self.element(element)?;
+                self.element(concept)
+            }
+            Entry::NotExtends { element, concept } => {
+                self.write(&[NOT_EXTENDS])?;
+                self.element(element)?;
+                self.element(concept)
+            }
+        }
+    }
+
+    /// End the stream by writing its checksum and return the underlying writer.
+    pub fn finish(mut self) -> Result<W, LigatureError> {
+        self.write(&[END])?;
+        let crc = self.crc.to_le_bytes();
+        self.writer.write_all(&crc).map_err(io_error)?;
+        self.writer.flush().map_err(io_error)?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads records from a binary stream one at a time.
+pub struct Decoder<R: Read> {
+    reader: R,
+    crc: u32,
+    dictionary: Vec<Element>,
+    complete: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Create a Decoder and check the stream's header.
+    pub fn new(reader: R) -> Result<Self, LigatureError> {
+        let mut decoder = Self {
+            reader,
+            crc: 0,
+            dictionary: vec![],
+            complete: false,
+        };
+        let mut magic = [0u8; 4];
+        decoder.read(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(LigatureError("Not a Ligature binary stream.".to_owned()));
+        }
+        let mut version = [0u8; 1];
+        decoder.read(&mut version)?;
+        if version[0] != VERSION {
+            return Err(LigatureError(format!(
+                "Unsupported binary format version {}.",
+                version[0]
+            )));
+        }
+        Ok(decoder)
+    }
+
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), LigatureError> {
+        self.reader.read_exact(bytes).map_err(io_error)?;
+        self.crc = update_crc(self.crc, bytes);
+        Ok(())
+    }
+
+    fn byte(&mut self) -> Result<u8, LigatureError> {
+        let mut byte = [0u8; 1];
+        self.read(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, LigatureError> {
+        let mut result = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift == 63 && byte > 1 {
+                return Err(LigatureError("Invalid varint in binary stream.".to_owned()));
+            }
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, LigatureError> {
+        let length = self.varint()? as usize;
+        let mut bytes = vec![];
+        (&mut self.reader)
+            .take(length as u64)
+            .read_to_end(&mut bytes)
+            .map_err(io_error)?;
+        if bytes.len() != length {
+            return Err(LigatureError("Unexpected end of binary stream.".to_owned()));
+        }
+        self.crc = update_crc(self.crc, &bytes);
+        String::from_utf8(bytes)
+            .map_err(|_| LigatureError("Invalid UTF-8 in binary stream.".to_owned()))
+    }
+
+    fn element(&mut self) -> Result<Element, LigatureError> {
+        let id = self.varint()? as usize;
+        if id < self.dictionary.len() {
+            return Ok(self.dictionary[id].clone());
+        } else if id > self.dictionary.len() {
+            return Err(LigatureError(format!(
+                "Unknown Element id {id} in binary stream."
+            )));
+        }
+        let flags = self.byte()?;
+        let value = self.string()?;
+        let namespace = if flags & HAS_NAMESPACE != 0 {
+            Some(Namespace(self.string()?))
+        } else {
+            None
+        };
+        let lang = if flags & HAS_LANG_TAG != 0 {
+            Some(LangTag(self.string()?))
+        } else {
+            None
+        };
+        let element = Element(value, namespace, lang);
+        self.dictionary.push(element.clone());
+        Ok(element)
+    }
+
+    /// Read the next record, returns None once the end of the stream has been reached
+    /// and its checksum has been verified.
+    pub fn record(&mut self) -> Result<Option<Record>, LigatureError> {
+        if self.complete {
+            return Ok(None);
+        }
+        match self.byte()? {
+            ROLE => {
+                let first = self.element()?;
+                let role = self.element()?;
+                let second = self.element()?;
+                Ok(Some(Record::Entry(Entry::Role {
+                    first,
+                    second,
+                    role,
+                })))
+            }
+            EXTENDS => {
+                let element = self.element()?;
+                let concept = self.element()?;
+                Ok(Some(Record::Entry(Entry::Extends { element, concept })))
+            }
+            NOT_EXTENDS => {
+                let element = self.element()?;
+                let concept = self.element()?;
+                Ok(Some(Record::Entry(Entry::NotExtends { element, concept })))
+            }
+            COLLECTION => Ok(Some(Record::Collection(self.element()?))),
+            END => {
+                let expected = self.crc;
+                let mut crc = [0u8; 4];
+                self.reader.read_exact(&mut crc).map_err(io_error)?;
+                if u32::from_le_bytes(crc) != expected {
+                    return Err(LigatureError("Binary stream checksum mismatch.".to_owned()));
+                }
+                self.complete = true;
+                Ok(None)
+            }
+            tag => Err(LigatureError(format!(
+                "Unknown record tag {tag:#04x} in binary stream."
+            ))),
+        }
+    }
+
+    fn end(mut self) -> Result<(), LigatureError> {
+        let mut byte = [0u8; 1];
+        if self.reader.read(&mut byte).map_err(io_error)? == 0 {
+            Ok(())
+        } else {
+            Err(LigatureError(
+                "Unexpected bytes after the end of binary stream.".to_owned(),
+            ))
+        }
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = Result<Record, LigatureError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => None,
+            Err(err) => {
+                self.complete = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Encode a network to the given writer.
+pub fn encode_network<W: Write>(entries: &BTreeSet<Entry>, writer: W) -> Result<W, LigatureError> {
+    let mut encoder = Encoder::new(writer)?;
+    for entry in entries {
+        encoder.entry(entry)?;
+    }
+    encoder.finish()
+}
+
+/// Decode a network from the given reader.
+pub fn decode_network<R: Read>(reader: R) -> Result<BTreeSet<Entry>, LigatureError> {
+    let mut result = BTreeSet::new();
+    let mut decoder = Decoder::new(reader)?;
+    for record in decoder.by_ref() {
+        match record? {
+            Record::Entry(entry) => {
+                result.insert(entry);
+            }
+            Record::Collection(_) => {
+                return Err(LigatureError(
+                    "Expected a network but found a Collection.".to_owned(),
+                ))
+            }
+        }
+    }
+    decoder.end()?;
+    Ok(result)
+}
+
+/// Encode a set of Collections to the given writer.
+pub fn encode_collections<W: Write>(
+    collections: &Collections,
+    writer: W,
+) -> Result<W, LigatureError> {
+    let mut encoder = Encoder::new(writer)?;
+    for (collection, entries) in collections {
+        encoder.collection(collection)?;
+        for entry in entries {
+            encoder.entry(entry)?;
+        }
+    }
+    encoder.finish()
+}
+
+/// Decode a set of Collections from the given reader.
+pub fn decode_collections<R: Read>(reader: R) -> Result<Collections, LigatureError> {
+    let mut result: Collections = BTreeMap::new();
+    let mut current = None;
+    let mut decoder = Decoder::new(reader)?;
+    for record in decoder.by_ref() {
+        match record? {
+            Record::Collection(collection) => {
+                result.entry(collection.clone()).or_default();
+                current = Some(collection);
+            }
+            Record::Entry(entry) => match &current {
+                Some(collection) => {
+                    result.entry(collection.clone()).or_default().insert(entry);
+                }
+                None => {
+                    return Err(LigatureError(
+                        "Found an Entry before any Collection.".to_owned(),
+                    ))
+                }
+            },
+        }
+    }
+    decoder.end()?;
+    Ok(result)
+}
+
+/// Encode a network to a Vec of bytes.
+pub fn write_network(entries: &BTreeSet<Entry>) -> Vec<u8> {
+    encode_network(entries, vec![]).expect("Writing to a Vec can't fail.")
+}
+
+/// Decode a network from a slice of bytes.
+pub fn read_network(bytes: &[u8]) -> Result<BTreeSet<Entry>, LigatureError> {
+    decode_network(bytes)
+}
+
+/// Encode a set of Collections to a Vec of bytes.
+pub fn write_collections(collections: &Collections) -> Vec<u8> {
+    encode_collections(collections, vec![]).expect("Writing to a Vec can't fail.")
+}
+
+/// Decode a set of Collections from a slice of bytes.
+pub fn read_collections(bytes: &[u8]) -> Result<Collections, LigatureError> {
+    decode_collections(bytes)
+}
+
+/// Write every Collection in a Ligature instance to the given writer, for backups.
+pub fn dump<W: Write>(ligature: &dyn Ligature, writer: W) -> Result<W, LigatureError> {
+    encode_collections(&snapshot(ligature)?, writer)
+}
+
+/// Read Collections written by `dump` and add them to a Ligature instance.
+pub fn load<R: Read>(ligature: &mut dyn Ligature, reader: R) -> Result<(), LigatureError> {
+    restore(ligature, decode_collections(reader)?)
+}
@@ -0,0 +1,99 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module follows `Extends` Entries through the concept hierarchy and checks them against `NotExtends` Entries.
+//!
+//! If `a : B` and `B : C` then `a` also extends `C`, so `a ¬: C` is a contradiction.
+
+use crate::{Element, Entry};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+fn hierarchy(entries: &BTreeSet<Entry>) -> BTreeMap<&Element, BTreeSet<&Element>> {
+    let mut result: BTreeMap<&Element, BTreeSet<&Element>> = BTreeMap::new();
+    for entry in entries {
+        if let Entry::Extends { element, concept } = entry {
+            result.entry(element).or_default().insert(concept);
+        }
+    }
+    result
+}
+
+/// Find the `Extends` Entries leading from an Element to a Concept, if there are any.
+fn path<'a>(
+    hierarchy: &BTreeMap<&'a Element, BTreeSet<&'a Element>>,
+    element: &'a Element,
+    concept: &Element,
+) -> Option<Vec<Entry>> {
+    let mut parents: BTreeMap<&Element, &Element> = BTreeMap::new();
+    let mut queue = VecDeque::from([element]);
+    while let Some(current) = queue.pop_front() {
+        for next in hierarchy.get(current).into_iter().flatten() {
+            if *next == concept {
+                let mut result = vec![Entry::Extends {
+                    element: current.clone(),
+                    concept: (*next).clone(),
+                }];
+                let mut child = current;
+                while child != element {
+                    let parent = parents[child];
+                    result.push(Entry::Extends {
+                        element: parent.clone(),
+                        concept: child.clone(),
+                    });
+                    child = parent;
+                }
+                return Some(result);
+            }
+            if *next != element && !parents.contains_key(next) {
+                parents.insert(next, current);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Find every Concept each Element extends, directly or through other Concepts.
+pub fn closure(entries: &BTreeSet<Entry>) -> BTreeSet<Entry> {
+    let hierarchy = hierarchy(entries);
+    let mut result = BTreeSet::new();
+    for element in hierarchy.keys() {
+        let mut seen = BTreeSet::new();
+        let mut queue = VecDeque::from([*element]);
+        while let Some(current) = queue.pop_front() {
+            for next in hierarchy.get(current).into_iter().flatten() {
+                if seen.insert(*next) {
+                    result.insert(Entry::Extends {
+                        element: (*element).clone(),
+                        concept: (*next).clone(),
+                    });
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Find the `NotExtends` Entries that contradict the concept hierarchy.
+/// Each contradiction is returned along with the `Extends` Entries that lead to it,
+/// so an empty result means the Entries are consistent.
+pub fn contradictions(entries: &BTreeSet<Entry>) -> BTreeSet<Entry> {
+    let hierarchy = hierarchy(entries);
+    let mut result = BTreeSet::new();
+    for entry in entries {
+        if let Entry::NotExtends { element, concept } = entry {
+            if let Some(path) = path(&hierarchy, element, concept) {
+                result.insert(entry.clone());
+                result.extend(path);
+            }
+        }
+    }
+    result
+}
+
+/// Check that no `NotExtends` Entry contradicts the concept hierarchy.
+pub fn is_consistent(entries: &BTreeSet<Entry>) -> bool {
+    contradictions(entries).is_empty()
+}
@@ -0,0 +1,310 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module supports importing and exporting tables as CSV or TSV.
+//!
+//! When importing, each row is mapped to the Element found in the key column
+//! and every other column is mapped to a role, using the column's header as the role
+//! unless the [TableMapping] says otherwise.
+//! Empty cells are skipped.
+//!
+//! When exporting, a Collection is pivoted back into a table with one row per Element.
+//! If an Element has more than one value for a column, the extra values are written
+//! on additional rows that repeat the key and leave the other columns empty,
+//! so an exported table can be imported again without losing Entries.
+
+use crate::{Element, Entry, Ligature, LigatureError};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Describes how rows and columns of a table map to Entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableMapping {
+    /// The character separating fields, `,` for CSV and a tab for TSV.
+    pub delimiter: char,
+    /// The header of the column holding each row's Element.
+    pub key: String,
+    /// Maps column headers to roles.
+    /// When empty, every column except the key and concept columns is imported using its header as the role.
+    /// Otherwise only the listed columns are imported.
+    pub columns: BTreeMap<String, Element>,
+    /// A Concept that every row's Element extends.
+    pub concept: Option<Element>,
+    /// The header of a column whose values are Concepts that the row's Element extends.
+    pub concept_column: Option<String>,
+}
+
+impl TableMapping {
+    /// Create a mapping for CSV with the given key column.
+    pub fn csv(key: &str) -> Self {
+        Self {
+            delimiter: ',',
+            key: key.to_owned(),
+            columns: BTreeMap::new(),
+            concept: None,
+            concept_column: None,
+        }
+    }
+
+    /// Create a mapping for TSV with the given key column.
+    pub fn tsv(key: &str) -> Self {
+        Self {
+            delimiter: '\t',
+            ..Self::csv(key)
+        }
+    }
+}
+
+/// Read the rows of a table.
+/// Fields can be quoted with `"` and a quote inside of a quoted field is written as `""`.
+pub fn read_table(input: &str, delimiter: char) -> Result<Vec<Vec<String>>, LigatureError> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c)
+                }
+            }
+        } else if c == '"' && field.is_empty() {
+            quoted = true;
+        } else if c == delimiter {
+            row.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            line += 1;
+            row.push(std::mem::take(&mut field));
+            rows.push(std::mem::take(&mut row));
+        } else {
+            field.push(c);
+        }
+    }
+    if quoted {
+        return Err(LigatureError(format!(
+            "Unterminated quoted field on line {line}."
+        )));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn write_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter)
+        || field.contains('"')
+        || field.contains('\n')
+        || field.contains('\r')
+    {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Write the rows of a table, quoting fields when needed.
+pub fn write_table(rows: &[Vec<String>], delimiter: char) -> String {
+    let mut result = String::new();
+    for row in rows {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|field| write_field(field, delimiter))
+            .collect();
+        result += &fields.join(&delimiter.to_string());
+        result.push('\n');
+    }
+    result
+}
+
+/// Import a table as a set of Entries.
+pub fn import(input: &str, mapping: &TableMapping) -> Result<BTreeSet<Entry>, LigatureError> {
+    let mut rows = read_table(input, mapping.delimiter)?.into_iter();
+    let header = match rows.next() {
+        Some(header) => header,
+        None => return Ok(BTreeSet::new()),
+    };
+    let find = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .ok_or(LigatureError(format!("Column `{name}` not found.")))
+    };
+    let key = find(&mapping.key)?;
+    let concept_column = match &mapping.concept_column {
+        Some(name) => Some(find(name)?),
+        None => None,
+    };
+    let roles: Vec<(usize, Element)> = if mapping.columns.is_empty() {
+        header
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != key && Some(*i) != concept_column)
+            .map(|(i, name)| (i, Element::new(name)))
+            .collect()
+    } else {
+        let mut roles = vec![];
+        for (name, role) in &mapping.columns {
+            roles.push((find(name)?, role.clone()));
+        }
+        roles
+    };
+    let mut result = BTreeSet::new();
+    for (i, row) in rows.enumerate() {
+        let first = match row.get(key) {
+            Some(value) if !value.is_empty() => Element::new(value),
+            _ if row.iter().all(|field| field.is_empty()) => continue,
+            _ => {
+                return Err(LigatureError(format!(
+                    "Row {} is missing a value for `{}`.",
+                    i + 2,
+                    mapping.key
+                )))
+            }
+        };
+        if let Some(concept) = &mapping.concept {
+            result.insert(Entry::Extends {
+                element: first.clone(),
+                concept: concept.clone(),
+            });
+        }
+        if let Some(Some(concept)) = concept_column.map(|column| row.get(column)) {
+            if !concept.is_empty() {
+                result.insert(Entry::Extends {
+                    element: first.clone(),
+                    concept: Element::new(concept),
+                });
+            }
+        }
+        for (column, role) in &roles {
+            if let Some(value) = row.get(*column) {
+                if !value.is_empty() {
+                    result.insert(Entry::Role {
+                        first: first.clone(),
+                        second: Element::new(value),
+                        role: role.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Export a set of Entries as a table.
+/// Only roles listed in the mapping are exported, or every role when the mapping has no columns.
+/// If the mapping has a concept column, the Concepts each Element extends are written to it.
+/// An Element that only extends Concepts still gets a row.
+pub fn export(entries: &BTreeSet<Entry>, mapping: &TableMapping) -> String {
+    let columns: Vec<(String, Element)> = if mapping.columns.is_empty() {
+        let roles: BTreeSet<&Element> = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Role { role, .. } => Some(role),
+                _ => None,
+            })
+            .collect();
+        roles
+            .into_iter()
+            .map(|role| (role.0.clone(), role.clone()))
+            .collect()
+    } else {
+        mapping
+            .columns
+            .iter()
+            .map(|(name, role)| (name.clone(), role.clone()))
+            .collect()
+    };
+    let mut cells: BTreeMap<&Element, Vec<Vec<&str>>> = BTreeMap::new();
+    let width = if mapping.concept_column.is_some() {
+        columns.len() + 1
+    } else {
+        columns.len()
+    };
+    for entry in entries {
+        match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => {
+                if let Some(column) = columns.iter().position(|(_, column)| column == role) {
+                    let row = cells.entry(first).or_insert_with(|| vec![vec![]; width]);
+                    row[column].push(&second.0);
+                }
+            }
+            Entry::Extends { element, concept } => {
+                let row = cells.entry(element).or_insert_with(|| vec![vec![]; width]);
+                if mapping.concept_column.is_some() && Some(concept) != mapping.concept.as_ref() {
+                    row[columns.len()].push(&concept.0);
+                }
+            }
+            Entry::NotExtends { .. } => (),
+        }
+    }
+    let mut header = vec![mapping.key.clone()];
+    header.extend(columns.iter().map(|(name, _)| name.clone()));
+    if let Some(concept_column) = &mapping.concept_column {
+        header.push(concept_column.clone());
+    }
+    let mut rows = vec![header];
+    for (first, values) in cells {
+        let height = values
+            .iter()
+            .map(|values| values.len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for i in 0..height {
+            let mut row = vec![first.0.clone()];
+            for values in &values {
+                row.push(
+                    values
+                        .get(i)
+                        .map(|value| value.to_string())
+                        .unwrap_or_default(),
+                );
+            }
+            rows.push(row);
+        }
+    }
+    write_table(&rows, mapping.delimiter)
+}
+
+/// Import a table into a Collection, creating the Collection if it doesn't exist.
+pub fn import_into(
+    ligature: &mut dyn Ligature,
+    collection: Element,
+    input: &str,
+    mapping: &TableMapping,
+) -> Result<(), LigatureError> {
+    let mut entries = import(input, mapping)?;
+    if !ligature.collections()?.contains(&collection) {
+        ligature.add_collection(collection.clone())?;
+    }
+    ligature.add_entries(collection, &mut entries)
+}
+
+/// Export a Collection as a table.
+pub fn export_from(
+    ligature: &dyn Ligature,
+    collection: &Element,
+    mapping: &TableMapping,
+) -> Result<String, LigatureError> {
+    Ok(export(&ligature.entries(collection)?, mapping))
+}
@@ -0,0 +1,610 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module defines Ligature's JSON and JSON-LD representations of networks and Collections.
+//!
+//! Elements are written as JSON strings, unless they have a Namespace or LangTag,
+//! in which case they are written as an object.
+//!
+//! ```json
+//! "alice"
+//! { "value": "Alice", "lang": "en" }
+//! { "value": "alice", "namespace": "https://example.com/" }
+//! ```
+//!
+//! Each Entry is written as an object whose keys identify the kind of Entry.
+//!
+//! ```json
+//! { "first": "alice", "role": "knows", "second": "bob" }
+//! { "element": "alice", "extends": "Person" }
+//! { "element": "alice", "notExtends": "Robot" }
+//! ```
+//!
+//! A network is an array of Entries and a set of Collections is an array of objects
+//! with a `collection` key holding the Collection's name and an `entries` key holding its network.
+//!
+//! ```json
+//! [ { "collection": "people", "entries": [ { "element": "alice", "extends": "Person" } ] } ]
+//! ```
+//!
+//! The JSON-LD representation groups Entries by their first Element into node objects.
+//! `Entry::Extends` is written using `@type`, `Entry::NotExtends` using the `notExtends` term
+//! (see [crate::rdf::NOT_EXTENDS]) and roles using the role's name as the key.
+//! Each Collection is written as a named graph.
+//! Elements without a Namespace are relative to [VOCAB], which the context sets as both
+//! `@vocab` and `@base`, and literals are written as value objects
+//! (see [crate::rdf::LITERAL_NAMESPACE]).
+
+use crate::{
+    rdf::{blank_node_prefix, LIGATURE_NAMESPACE, LITERAL_NAMESPACE, NOT_EXTENDS},
+    Collections, Element, Entry, LangTag, LigatureError, Namespace,
+};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// The IRI that Elements without a Namespace are relative to in JSON-LD documents.
+pub const VOCAB: &str = "https://ligature.dev/element/";
+
+/// Convert an Element to JSON.
+pub fn element_to_json(element: &Element) -> Value {
+    match element {
+        Element(value, None, None) => Value::String(value.clone()),
+        Element(value, namespace, lang) => {
+            let mut result = Map::new();
+            result.insert("value".to_owned(), Value::String(value.clone()));
+            if let Some(Namespace(namespace)) = namespace {
+                result.insert("namespace".to_owned(), Value::String(namespace.clone()));
+            }
+            if let Some(LangTag(lang)) = lang {
+                result.insert("lang".to_owned(), Value::String(lang.clone()));
+            }
+            Value::Object(result)
+        }
+    }
+}
+
+/// Read an Element from JSON.
+pub fn element_from_json(value: &Value) -> Result<Element, LigatureError> {
+    match value {
+        Value::String(value) => Ok(Element(value.clone(), None, None)),
+        Value::Object(object) => {
+            let value = match object.get("value") {
+                Some(Value::String(value)) => value.clone(),
+                _ => return Err(LigatureError("Element is missing a `value`.".to_owned())),
+            };
+            let namespace = optional_string(object, "namespace")?.map(Namespace);
+            let lang = optional_string(object, "lang")?.map(LangTag);
+            Ok(Element(value, namespace, lang))
+        }
+        value => Err(LigatureError(format!(
+            "Expected an Element but found {value}."
+        ))),
+    }
+}
+
+fn optional_string(
+    object: &Map<String, Value>,
+    key: &str,
+) -> Result<Option<String>, LigatureError> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(value) => Err(LigatureError(format!(
+            "Expected a string for `{key}` but found {value}."
+        ))),
+    }
+}
+
+/// Convert an Entry to JSON.
+pub fn entry_to_json(entry: &Entry) -> Value {
+    match entry {
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => json!({
+            "first": element_to_json(first),
+            "role": element_to_json(role),
+            "second": element_to_json(second),
+        }),
+        Entry::Extends { element, concept } => json!({
+            "element": element_to_json(element),
+            "extends": element_to_json(concept),
+        }),
+        Entry::NotExtends { element, concept } => json!({
+            "element": element_to_json(element),
+            "notExtends": element_to_json(concept),
+        }),
+    }
+}
+
+/// Read an Entry from JSON.
+pub fn entry_from_json(value: &Value) -> Result<Entry, LigatureError> {
+    let object = match value {
+        Value::Object(object) => object,
+        value => {
+            return Err(LigatureError(format!(
+                "Expected an Entry but found {value}."
+            )))
+        }
+    };
+    let field = |key: &str| match object.get(key) {
+        Some(value) => element_from_json(value),
+        None => Err(LigatureError(format!("Entry is missing `{key}`."))),
+    };
+    if object.contains_key("extends") {
+        Ok(Entry::Extends {
+            element: field("element")?,
+            concept: field("extends")?,
+        })
+    } else if object.contains_key("notExtends") {
+        Ok(Entry::NotExtends {
+            element: field("element")?,
+            concept: field("notExtends")?,
+        })
+    } else {
+        Ok(Entry::Role {
+            first: field("first")?,
+            second: field("second")?,
+            role: field("role")?,
+        })
+    }
+}
+
+/// Convert a network to JSON.
+pub fn network_to_json(entries: &BTreeSet<Entry>) -> Value {
+    Value::Array(entries.iter().map(entry_to_json).collect())
+}
+
+/// Read a network from JSON.
+pub fn network_from_json(value: &Value) -> Result<BTreeSet<Entry>, LigatureError> {
+    match value {
+        Value::Array(entries) => entries.iter().map(entry_from_json).collect(),
+        value => Err(LigatureError(format!(
+            "Expected a network but found {value}."
+        ))),
+    }
+}
+
+/// Convert a set of Collections to JSON.
+pub fn collections_to_json(collections: &Collections) -> Value {
+    Value::Array(
+        collections
+            .iter()
+            .map(|(collection, entries)| {
+                json!({
+                    "collection": element_to_json(collection),
+                    "entries": network_to_json(entries),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Read a set of Collections from JSON.
+pub fn collections_from_json(value: &Value) -> Result<Collections, LigatureError> {
+    let collections = match value {
+        Value::Array(collections) => collections,
+        value => {
+            return Err(LigatureError(format!(
+                "Expected Collections but found {value}."
+            )))
+        }
+    };
+    let mut result = BTreeMap::new();
+    for collection in collections {
+        match (collection.get("collection"), collection.get("entries")) {
+            (Some(name), Some(entries)) => {
+                result
+                    .entry(element_from_json(name)?)
+                    .or_insert_with(BTreeSet::new)
+                    .append(&mut network_from_json(entries)?);
+            }
+            _ => {
+                return Err(LigatureError(
+                    "A Collection requires `collection` and `entries`.".to_owned(),
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn parse(input: &str) -> Result<Value, LigatureError> {
+    serde_json::from_str(input).map_err(|err| LigatureError(format!("Error reading JSON: {err}")))
+}
+
+/// Write a network as a JSON string.
+pub fn write_network(entries: &BTreeSet<Entry>) -> String {
+    network_to_json(entries).to_string()
+}
+
+/// Read a network from a JSON string.
+pub fn read_network(input: &str) -> Result<BTreeSet<Entry>, LigatureError> {
+    network_from_json(&parse(input)?)
+}
+
+/// Write a set of Collections as a JSON string.
+pub fn write_collections(collections: &Collections) -> String {
+    collections_to_json(collections).to_string()
+}
+
+/// Read a set of Collections from a JSON string.
+pub fn read_collections(input: &str) -> Result<Collections, LigatureError> {
+    collections_from_json(&parse(input)?)
+}
+
+/// The `@context` used by Ligature's JSON-LD documents.
+pub fn jsonld_context() -> Value {
+    json!({
+        "@vocab": VOCAB,
+        "@base": VOCAB,
+        "lig": LIGATURE_NAMESPACE,
+        "notExtends": { "@id": "lig:notExtends", "@type": "@id" },
+    })
+}
+
+fn element_to_id(element: &Element) -> String {
+    match element {
+        Element(value, Some(Namespace(namespace)), _) => namespace.clone() + value,
+        Element(value, None, _) => value.clone(),
+    }
+}
+
+fn element_to_jsonld(element: &Element) -> Value {
+    match element {
+        Element(value, None, Some(LangTag(lang))) => json!({ "@value": value, "@language": lang }),
+        Element(value, Some(Namespace(namespace)), None) if namespace == LITERAL_NAMESPACE => {
+            json!({ "@value": value })
+        }
+        element => json!({ "@id": element_to_id(element) }),
+    }
+}
+
+fn push(node: &mut Map<String, Value>, key: &str, value: Value) {
+    match node.entry(key.to_owned()).or_insert_with(|| json!([])) {
+        Value::Array(values) => values.push(value),
+        _ => unreachable!(),
+    }
+}
+
+fn node<'a>(
+    nodes: &'a mut BTreeMap<String, Map<String, Value>>,
+    element: &Element,
+) -> &'a mut Map<String, Value> {
+    let id = element_to_id(element);
+    nodes.entry(id.clone()).or_insert_with(|| {
+        let mut node = Map::new();
+        node.insert("@id".to_owned(), Value::String(id));
+        node
+    })
+}
+
+fn network_to_jsonld_nodes(entries: &BTreeSet<Entry>) -> Vec<Value> {
+    let mut nodes: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for entry in entries {
+        match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => push(
+                node(&mut nodes, first),
+                &element_to_id(role),
+                element_to_jsonld(second),
+            ),
+            Entry::Extends { element, concept } => push(
+                node(&mut nodes, element),
+                "@type",
+                json!(element_to_id(concept)),
+            ),
+            Entry::NotExtends { element, concept } => push(
+                node(&mut nodes, element),
+                "notExtends",
+                json!(element_to_id(concept)),
+            ),
+        }
+    }
+    nodes.into_values().map(Value::Object).collect()
+}
+
+/// Convert a network to a JSON-LD document.
+pub fn network_to_jsonld(entries: &BTreeSet<Entry>) -> Value {
+    json!({
+        "@context": jsonld_context(),
+        "@graph": network_to_jsonld_nodes(entries),
+    })
+}
+
+/// Convert a set of Collections to a JSON-LD document with one named graph per Collection.
+pub fn collections_to_jsonld(collections: &Collections) -> Value {
+    let graphs: Vec<Value> = collections
+        .iter()
+        .map(|(collection, entries)| {
+            json!({
+                "@id": element_to_id(collection),
+                "@graph": network_to_jsonld_nodes(entries),
+            })
+        })
+        .collect();
+    json!({
+        "@context": jsonld_context(),
+        "@graph": graphs,
+    })
+}
+
+struct JsonLdReader {
+    terms: HashMap<String, String>,
+    /// Terms whose string values are IRIs rather than literals.
+    id_terms: HashSet<String>,
+    vocab: Option<String>,
+    base: Option<String>,
+    blank_node_prefix: String,
+    blank_nodes: usize,
+}
+
+fn literal(value: String) -> Element {
+    Element(value, Some(Namespace(LITERAL_NAMESPACE.to_owned())), None)
+}
+
+impl JsonLdReader {
+    fn new(document: &Value) -> Result<Self, LigatureError> {
+        let mut reader = Self {
+            terms: HashMap::new(),
+            id_terms: HashSet::new(),
+            vocab: None,
+            base: None,
+            blank_node_prefix: blank_node_prefix(&document.to_string()),
+            blank_nodes: 0,
+        };
+        let contexts = match document.get("@context") {
+            None => vec![],
+            Some(Value::Array(contexts)) => contexts.iter().collect(),
+            Some(context) => vec![context],
+        };
+        for context in contexts {
+            match context {
+                Value::Object(context) => {
+                    for (term, definition) in context {
+                        match (term.as_str(), definition) {
+                            ("@vocab", Value::String(vocab)) => reader.vocab = Some(vocab.clone()),
+                            ("@base", Value::String(base)) => reader.base = Some(base.clone()),
+                            (term, _) if term.starts_with('@') => (),
+                            (term, Value::String(id)) => {
+                                reader.terms.insert(term.to_owned(), id.clone());
+                            }
+                            (term, Value::Object(definition)) => {
+                                if let Some(Value::String(id)) = definition.get("@id") {
+                                    reader.terms.insert(term.to_owned(), id.clone());
+                                }
+                                if let Some(Value::String(kind)) = definition.get("@type") {
+                                    if kind == "@id" || kind == "@vocab" {
+                                        reader.id_terms.insert(term.to_owned());
+                                    }
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                _ => {
+                    return Err(LigatureError(
+                        "Only inline JSON-LD contexts are supported.".to_owned(),
+                    ))
+                }
+            }
+        }
+        Ok(reader)
+    }
+
+    /// Expand a term or compact IRI, resolving relative IRIs against `@vocab` for keys and types
+    /// or against `@base` for ids.
+    fn expand(&self, value: &str, vocab: bool) -> Result<String, LigatureError> {
+        let mut value = value;
+        let mut seen = HashSet::new();
+        while let Some(id) = self.terms.get(value) {
+            if !seen.insert(value) {
+                return Err(LigatureError(format!(
+                    "The JSON-LD term `{value}` is defined in terms of itself."
+                )));
+            }
+            value = id;
+        }
+        if let Some((prefix, local)) = value.split_once(':') {
+            return Ok(match self.terms.get(prefix) {
+                Some(namespace) if !local.starts_with("//") => namespace.clone() + local,
+                _ => value.to_owned(),
+            });
+        }
+        let base = if vocab { &self.vocab } else { &self.base };
+        Ok(match base {
+            Some(base) => base.clone() + value,
+            None => value.to_owned(),
+        })
+    }
+
+    fn element(&self, value: &str, vocab: bool) -> Result<Element, LigatureError> {
+        let iri = self.expand(value, vocab)?;
+        Ok(match iri.strip_prefix(VOCAB) {
+            Some(value) => Element::new(value),
+            None => Element::new(iri),
+        })
+    }
+
+    fn values(value: &Value) -> Vec<&Value> {
+        match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        }
+    }
+
+    fn node(
+        &mut self,
+        node: &Value,
+        result: &mut BTreeSet<Entry>,
+    ) -> Result<Element, LigatureError> {
+        let node = match node {
+            Value::Object(node) => node,
+            node => return Err(LigatureError(format!("Expected a node but found {node}."))),
+        };
+        let id = match node.get("@id") {
+            Some(Value::String(id)) => self.element(id, false)?,
+            Some(id) => return Err(LigatureError(format!("Expected an `@id` but found {id}."))),
+            None => {
+                self.blank_nodes += 1;
+                Element::new(format!("_:{}{}", self.blank_node_prefix, self.blank_nodes))
+            }
+        };
+        for (key, value) in node {
+            if key == "@id" || key == "@context" {
+                continue;
+            } else if key == "@type" {
+                for concept in Self::values(value) {
+                    match concept {
+                        Value::String(concept) => {
+                            result.insert(Entry::Extends {
+                                element: id.clone(),
+                                concept: self.element(concept, true)?,
+                            });
+                        }
+                        concept => {
+                            return Err(LigatureError(format!(
+                                "Expected a type but found {concept}."
+                            )))
+                        }
+                    }
+                }
+            } else if key.starts_with('@') {
+                return Err(LigatureError(format!(
+                    "Unsupported JSON-LD keyword `{key}`."
+                )));
+            } else if self.expand(key, true)? == NOT_EXTENDS {
+                for concept in Self::values(value) {
+                    let concept = self.object(concept, true, result)?;
+                    result.insert(Entry::NotExtends {
+                        element: id.clone(),
+                        concept,
+                    });
+                }
+            } else {
+                let role = self.element(key, true)?;
+                let id_term = self.id_terms.contains(key);
+                for second in Self::values(value) {
+                    let second = self.object(second, id_term, result)?;
+                    result.insert(Entry::Role {
+                        first: id.clone(),
+                        second,
+                        role: role.clone(),
+                    });
+                }
+            }
+        }
+        Ok(id)
+    }
+
+    /// Read the value of a key, where strings are IRIs if the key is an `id_term`
+    /// and literals otherwise.
+    fn object(
+        &mut self,
+        value: &Value,
+        id_term: bool,
+        result: &mut BTreeSet<Entry>,
+    ) -> Result<Element, LigatureError> {
+        match value {
+            Value::String(value) if id_term => self.element(value, false),
+            Value::String(value) => Ok(literal(value.clone())),
+            Value::Number(value) => Ok(literal(value.to_string())),
+            Value::Bool(value) => Ok(literal(value.to_string())),
+            Value::Object(object) if object.contains_key("@value") => {
+                let value = match object.get("@value") {
+                    Some(Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                    None => unreachable!(),
+                };
+                match optional_string(object, "@language")? {
+                    Some(lang) => Ok(Element(value, None, Some(LangTag(lang)))),
+                    None => Ok(literal(value)),
+                }
+            }
+            Value::Object(_) => self.node(value, result),
+            value => Err(LigatureError(format!("Unsupported JSON-LD value {value}."))),
+        }
+    }
+
+    fn graph(&mut self, graph: &Value) -> Result<BTreeSet<Entry>, LigatureError> {
+        let mut result = BTreeSet::new();
+        for node in Self::values(graph) {
+            self.node(node, &mut result)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Read a network from a JSON-LD document.
+/// Named graphs are not supported, use [collections_from_jsonld] to read them.
+pub fn network_from_jsonld(document: &Value) -> Result<BTreeSet<Entry>, LigatureError> {
+    let mut reader = JsonLdReader::new(document)?;
+    match document.get("@graph") {
+        Some(graph) => reader.graph(graph),
+        None => reader.graph(document),
+    }
+}
+
+/// Read a set of Collections from a JSON-LD document.
+/// Each named graph becomes a Collection and nodes outside of a named graph are added to `default_collection`.
+pub fn collections_from_jsonld(
+    document: &Value,
+    default_collection: &Element,
+) -> Result<Collections, LigatureError> {
+    let mut reader = JsonLdReader::new(document)?;
+    let mut result: Collections = BTreeMap::new();
+    let top = match document.get("@graph") {
+        Some(graph) => JsonLdReader::values(graph),
+        None => vec![document],
+    };
+    for node in top {
+        match node.get("@graph") {
+            Some(graph) => {
+                let name = match node.get("@id") {
+                    Some(Value::String(id)) => reader.element(id, false)?,
+                    _ => return Err(LigatureError("A named graph requires an `@id`.".to_owned())),
+                };
+                let mut entries = reader.graph(graph)?;
+                result.entry(name).or_default().append(&mut entries);
+            }
+            None => {
+                let mut entries = BTreeSet::new();
+                reader.node(node, &mut entries)?;
+                result
+                    .entry(default_collection.clone())
+                    .or_default()
+                    .append(&mut entries);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Write a network as a JSON-LD string.
+pub fn write_jsonld_network(entries: &BTreeSet<Entry>) -> String {
+    network_to_jsonld(entries).to_string()
+}
+
+/// Read a network from a JSON-LD string.
+pub fn read_jsonld_network(input: &str) -> Result<BTreeSet<Entry>, LigatureError> {
+    network_from_jsonld(&parse(input)?)
+}
+
+/// Write a set of Collections as a JSON-LD string.
+pub fn write_jsonld_collections(collections: &Collections) -> String {
+    collections_to_jsonld(collections).to_string()
+}
+
+/// Read a set of Collections from a JSON-LD string.
+pub fn read_jsonld_collections(
+    input: &str,
+    default_collection: &Element,
+) -> Result<Collections, LigatureError> {
+    collections_from_jsonld(&parse(input)?, default_collection)
+}
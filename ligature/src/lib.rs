@@ -12,6 +12,17 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::{BTreeMap, BTreeSet};
 
+pub mod analytics;
+pub mod binary;
+pub mod concepts;
+pub mod csv;
+pub mod json;
+pub mod paths;
+pub mod projection;
+pub mod rdf;
+pub mod rules;
+pub mod shapes;
+
 /// An Element that is identified by a unique String value.
 #[derive(Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Deserialize, Serialize, Hash)]
 pub struct LangTag(pub String);
@@ -24,6 +35,25 @@ pub struct Namespace(pub String);
 #[derive(Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Deserialize, Serialize, Hash)]
 pub struct Element(pub String, pub Option<Namespace>, pub Option<LangTag>);
 
+impl Element {
+    /// Create an Element without a Namespace or LangTag.
+    pub fn new(value: impl Into<String>) -> Self {
+        Element(value.into(), None, None)
+    }
+}
+
+impl From<&str> for Element {
+    fn from(value: &str) -> Self {
+        Element::new(value)
+    }
+}
+
+impl From<String> for Element {
+    fn from(value: String) -> Self {
+        Element::new(value)
+    }
+}
+
 impl fmt::Display for Element {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", json!(self.0))
@@ -58,9 +88,44 @@ pub enum Entry {
     },
 }
 
+impl Entry {
+    /// Create a Role Entry, in the order it is written: `first role second`.
+    pub fn role(
+        first: impl Into<Element>,
+        role: impl Into<Element>,
+        second: impl Into<Element>,
+    ) -> Self {
+        Entry::Role {
+            first: first.into(),
+            second: second.into(),
+            role: role.into(),
+        }
+    }
+
+    /// Create an Extends Entry.
+    pub fn extends(element: impl Into<Element>, concept: impl Into<Element>) -> Self {
+        Entry::Extends {
+            element: element.into(),
+            concept: concept.into(),
+        }
+    }
+
+    /// Create a NotExtends Entry.
+    pub fn not_extends(element: impl Into<Element>, concept: impl Into<Element>) -> Self {
+        Entry::NotExtends {
+            element: element.into(),
+            concept: concept.into(),
+        }
+    }
+}
+
 /// A simple error message.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LigatureError(pub String);
 
+/// The contents of several Collections, keyed by Collection name.
+pub type Collections = BTreeMap<Element, BTreeSet<Entry>>;
+
 /// A trait that defines all the actions a Ligature instance can perform.
 /// The API used for storing triples.
 pub trait Ligature {
@@ -100,10 +165,35 @@ pub trait Ligature {
         collection: Element,
         pattern: Entry,
     ) -> Result<BTreeSet<Entry>, LigatureError>;
-    // /// Run a query against the given Dataset.
-    // fn query(
-    //     &self,
-    //     collection: Element,
-    //     pattern: BTreeSet<Entry>,
-    // ) -> Result<HashBag<BTreeMap<String, String>>, LigatureError>;
+    /// Run a query against the given Dataset.
+    /// Elements starting with `?` in the pattern are Variables, and `?` on its own matches anything.
+    /// Each result maps Variable names, without the `?`, to the Elements they matched.
+    fn query(
+        &self,
+        collection: Element,
+        pattern: BTreeSet<Entry>,
+    ) -> Result<HashBag<BTreeMap<String, Element>>, LigatureError>;
+}
+
+/// Read every Collection in a Ligature instance.
+pub fn snapshot(ligature: &dyn Ligature) -> Result<Collections, LigatureError> {
+    let mut result = BTreeMap::new();
+    for collection in ligature.collections()? {
+        let entries = ligature.entries(&collection)?;
+        result.insert(collection, entries);
+    }
+    Ok(result)
+}
+
+/// Add the given Collections to a Ligature instance.
+/// Collections that don't exist are created and existing Collections are added to.
+pub fn restore(ligature: &mut dyn Ligature, collections: Collections) -> Result<(), LigatureError> {
+    let existing = ligature.collections()?;
+    for (collection, mut entries) in collections {
+        if !existing.contains(&collection) {
+            ligature.add_collection(collection.clone())?;
+        }
+        ligature.add_entries(collection, &mut entries)?;
+    }
+    Ok(())
 }
@@ -0,0 +1,313 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module follows roles through a network using path expressions.
+//!
+//! A path expression is made of role names combined with:
+//!
+//! - `a/b`: follow `a` and then `b`.
+//! - `a|b`: follow either `a` or `b`.
+//! - `^a`: follow `a` backwards.
+//! - `a*`: follow `a` zero or more times.
+//! - `a+`: follow `a` one or more times.
+//! - `(...)`: group an expression.
+//!
+//! So `^parentOf+` finds every ancestor of an Element.
+
+use crate::{Element, Entry, Ligature, LigatureError};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// A parsed path expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Path {
+    /// Follow a role.
+    Role(Element),
+    /// Follow a path backwards.
+    Inverse(Box<Path>),
+    /// Follow each path in turn.
+    Sequence(Vec<Path>),
+    /// Follow any of the paths.
+    Alternative(Vec<Path>),
+    /// Follow a path zero or more times.
+    ZeroOrMore(Box<Path>),
+    /// Follow a path one or more times.
+    OneOrMore(Box<Path>),
+}
+
+const SPECIAL: &[char] = &['/', '|', '^', '*', '+', '(', ')'];
+
+/// The deepest that groups can be nested in a path expression.
+const MAX_NESTING: usize = 64;
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].trim_start().chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let rest = &self.input[self.offset..];
+        let trimmed = rest.trim_start();
+        let c = trimmed.chars().next()?;
+        self.offset += rest.len() - trimmed.len() + c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> LigatureError {
+        LigatureError(format!("Could not read path `{}`: {message}", self.input))
+    }
+
+    fn alternative(&mut self) -> Result<Path, LigatureError> {
+        let mut paths = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.next();
+            paths.push(self.sequence()?);
+        }
+        Ok(if paths.len() == 1 {
+            paths.remove(0)
+        } else {
+            Path::Alternative(paths)
+        })
+    }
+
+    fn sequence(&mut self) -> Result<Path, LigatureError> {
+        let mut paths = vec![self.unary()?];
+        while self.peek() == Some('/') {
+            self.next();
+            paths.push(self.unary()?);
+        }
+        Ok(if paths.len() == 1 {
+            paths.remove(0)
+        } else {
+            Path::Sequence(paths)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Path, LigatureError> {
+        // Following a path backwards twice follows it forwards, so only an odd number of `^` inverts it.
+        let mut inverse = false;
+        while self.peek() == Some('^') {
+            self.next();
+            inverse = !inverse;
+        }
+        let mut path = self.primary()?;
+        loop {
+            match self.peek() {
+                Some('*') => path = Path::ZeroOrMore(Box::new(path)),
+                Some('+') => path = Path::OneOrMore(Box::new(path)),
+                _ if inverse => return Ok(Path::Inverse(Box::new(path))),
+                _ => return Ok(path),
+            }
+            self.next();
+        }
+    }
+
+    fn primary(&mut self) -> Result<Path, LigatureError> {
+        match self.peek() {
+            Some('(') => {
+                if self.depth == MAX_NESTING {
+                    return Err(self.error(&format!(
+                        "groups can't be nested more than {MAX_NESTING} deep."
+                    )));
+                }
+                self.next();
+                self.depth += 1;
+                let path = self.alternative()?;
+                self.depth -= 1;
+                match self.next() {
+                    Some(')') => Ok(path),
+                    _ => Err(self.error("expected `)`.")),
+                }
+            }
+            Some(c) if !SPECIAL.contains(&c) => {
+                let rest = self.input[self.offset..].trim_start();
+                let length = rest
+                    .find(|c: char| c.is_whitespace() || SPECIAL.contains(&c))
+                    .unwrap_or(rest.len());
+                self.offset = self.input.len() - rest.len() + length;
+                Ok(Path::Role(Element(rest[..length].to_owned(), None, None)))
+            }
+            Some(c) => Err(self.error(&format!("unexpected `{c}`."))),
+            None => Err(self.error("expected a role.")),
+        }
+    }
+}
+
+impl Path {
+    /// Read a path expression.
+    pub fn parse(input: &str) -> Result<Path, LigatureError> {
+        let mut parser = Parser {
+            input,
+            offset: 0,
+            depth: 0,
+        };
+        let path = parser.alternative()?;
+        match parser.peek() {
+            None => Ok(path),
+            Some(c) => Err(parser.error(&format!("unexpected `{c}`."))),
+        }
+    }
+}
+
+/// The roles of a network, indexed in both directions.
+struct Index<'a> {
+    forward: BTreeMap<(&'a Element, &'a Element), BTreeSet<&'a Element>>,
+    backward: BTreeMap<(&'a Element, &'a Element), BTreeSet<&'a Element>>,
+}
+
+impl<'a> Index<'a> {
+    fn new(entries: &'a BTreeSet<Entry>) -> Self {
+        let mut forward: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        let mut backward: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for entry in entries {
+            if let Entry::Role {
+                first,
+                second,
+                role,
+            } = entry
+            {
+                forward.entry((role, first)).or_default().insert(second);
+                backward.entry((role, second)).or_default().insert(first);
+            }
+        }
+        Self { forward, backward }
+    }
+
+    fn follow(
+        &self,
+        path: &Path,
+        from: &BTreeSet<&'a Element>,
+        inverse: bool,
+    ) -> BTreeSet<&'a Element> {
+        match path {
+            Path::Role(role) => {
+                let index = if inverse {
+                    &self.backward
+                } else {
+                    &self.forward
+                };
+                from.iter()
+                    .flat_map(|element| index.get(&(role, *element)).into_iter().flatten())
+                    .copied()
+                    .collect()
+            }
+            Path::Inverse(path) => self.follow(path, from, !inverse),
+            Path::Sequence(paths) => {
+                let mut current = from.clone();
+                let mut step = |path: &Path| current = self.follow(path, &current, inverse);
+                if inverse {
+                    paths.iter().rev().for_each(&mut step);
+                } else {
+                    paths.iter().for_each(&mut step);
+                }
+                current
+            }
+            Path::Alternative(paths) => paths
+                .iter()
+                .flat_map(|path| self.follow(path, from, inverse))
+                .collect(),
+            Path::ZeroOrMore(path) => self.repeat(path, from.clone(), inverse),
+            Path::OneOrMore(path) => self.repeat(path, self.follow(path, from, inverse), inverse),
+        }
+    }
+
+    /// Follow a path until no new Elements are found.
+    fn repeat(
+        &self,
+        path: &Path,
+        mut result: BTreeSet<&'a Element>,
+        inverse: bool,
+    ) -> BTreeSet<&'a Element> {
+        let mut frontier = result.clone();
+        while !frontier.is_empty() {
+            frontier = self
+                .follow(path, &frontier, inverse)
+                .into_iter()
+                .filter(|element| !result.contains(element))
+                .collect();
+            result.extend(frontier.iter().copied());
+        }
+        result
+    }
+}
+
+/// Find the Elements reached by following a path from an Element.
+pub fn follow(entries: &BTreeSet<Entry>, path: &Path, start: &Element) -> BTreeSet<Element> {
+    Index::new(entries)
+        .follow(path, &BTreeSet::from([start]), false)
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// Find the Elements reached by following a path from an Element in a collection.
+pub fn follow_in(
+    ligature: &dyn Ligature,
+    collection: &Element,
+    path: &Path,
+    start: &Element,
+) -> Result<BTreeSet<Element>, LigatureError> {
+    Ok(follow(&ligature.entries(collection)?, path, start))
+}
+
+/// The role Entries leaving each Element, along with the Element each one leads to.
+fn neighbours(entries: &BTreeSet<Entry>) -> BTreeMap<&Element, Vec<(&Element, &Entry)>> {
+    let mut result: BTreeMap<&Element, Vec<(&Element, &Entry)>> = BTreeMap::new();
+    for entry in entries {
+        if let Entry::Role { first, second, .. } = entry {
+            result.entry(first).or_default().push((second, entry));
+        }
+    }
+    result
+}
+
+/// Find every Element reached by following any roles from an Element.
+pub fn reachable(entries: &BTreeSet<Entry>, start: &Element) -> BTreeSet<Element> {
+    let neighbours = neighbours(entries);
+    let mut result = BTreeSet::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+        for (next, _) in neighbours.get(current).into_iter().flatten() {
+            if result.insert((*next).clone()) {
+                queue.push_back(next);
+            }
+        }
+    }
+    result
+}
+
+/// Find the fewest role Entries leading from one Element to another, if there are any.
+pub fn shortest_path(
+    entries: &BTreeSet<Entry>,
+    from: &Element,
+    to: &Element,
+) -> Option<Vec<Entry>> {
+    let neighbours = neighbours(entries);
+    let mut parents: BTreeMap<&Element, (&Element, &Entry)> = BTreeMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut result = vec![];
+            let mut child = current;
+            while let Some((parent, entry)) = parents.get(child) {
+                result.push((*entry).clone());
+                child = parent;
+            }
+            result.reverse();
+            return Some(result);
+        }
+        for (next, entry) in neighbours.get(current).into_iter().flatten() {
+            if *next != from && !parents.contains_key(next) {
+                parents.insert(next, (current, entry));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,257 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module projects a network onto nodes and edges so it can be visualized.
+//!
+//! Every Element taking part in an Entry becomes a node and every Entry becomes an edge.
+//! Elements used as a Concept in an `Extends` or `NotExtends` Entry are marked as concepts,
+//! which the GraphML, GEXF and DOT writers use to style them differently from other nodes.
+
+use crate::{Element, Entry, LangTag, Namespace};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A node in a [Projection].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Node {
+    /// A unique key for the Element, see [project].
+    pub key: String,
+    /// The Element this node represents.
+    pub element: Element,
+    /// Whether the Element is used as a Concept.
+    pub concept: bool,
+}
+
+/// The kind of Entry an [Edge] was projected from.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeKind {
+    /// Projected from a Role Entry.
+    Role,
+    /// Projected from an Extends Entry.
+    Extends,
+    /// Projected from a NotExtends Entry.
+    NotExtends,
+}
+
+/// An edge in a [Projection].
+/// The key is the role's name or `:` and `¬:` for `Extends` and `NotExtends`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    /// The edge's label.
+    pub key: String,
+    /// The key of the source node.
+    pub source: String,
+    /// The key of the target node.
+    pub target: String,
+    /// The kind of Entry this edge was projected from.
+    pub kind: EdgeKind,
+}
+
+/// The nodes and edges of a network.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct Projection {
+    /// The nodes of the network.
+    pub nodes: BTreeSet<Node>,
+    /// The edges of the network.
+    pub edges: BTreeSet<Edge>,
+}
+
+fn key(element: &Element) -> String {
+    match element {
+        Element(value, None, None) if !value.starts_with('{') => value.clone(),
+        Element(value, namespace, lang) => {
+            let mut fields = vec![format!("\"value\":{}", json!(value))];
+            if let Some(Namespace(namespace)) = namespace {
+                fields.push(format!("\"namespace\":{}", json!(namespace)));
+            }
+            if let Some(LangTag(lang)) = lang {
+                fields.push(format!("\"lang\":{}", json!(lang)));
+            }
+            format!("{{{}}}", fields.join(","))
+        }
+    }
+}
+
+/// Project a network onto nodes and edges.
+/// Each node's key is its Element's value, or a JSON object with the value, namespace and lang
+/// when the Element has a Namespace or LangTag or its value starts with `{`,
+/// so different Elements always get different keys.
+pub fn project(entries: &BTreeSet<Entry>) -> Projection {
+    let mut nodes: BTreeMap<String, (&Element, bool)> = BTreeMap::new();
+    let mut edges = BTreeSet::new();
+    for entry in entries {
+        let (source, target, label, kind) = match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => (first, second, key(role), EdgeKind::Role),
+            Entry::Extends { element, concept } => {
+                (element, concept, ":".to_owned(), EdgeKind::Extends)
+            }
+            Entry::NotExtends { element, concept } => {
+                (element, concept, "¬:".to_owned(), EdgeKind::NotExtends)
+            }
+        };
+        nodes.entry(key(source)).or_insert((source, false));
+        let (_, concept) = nodes.entry(key(target)).or_insert((target, false));
+        if kind != EdgeKind::Role {
+            *concept = true;
+        }
+        edges.insert(Edge {
+            key: label,
+            source: key(source),
+            target: key(target),
+            kind,
+        });
+    }
+    Projection {
+        nodes: nodes
+            .into_iter()
+            .map(|(key, (element, concept))| Node {
+                key,
+                element: element.clone(),
+                concept,
+            })
+            .collect(),
+        edges,
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut result = String::new();
+    for c in value.chars() {
+        match c {
+            '&' => result += "&amp;",
+            '<' => result += "&lt;",
+            '>' => result += "&gt;",
+            '"' => result += "&quot;",
+            '\'' => result += "&apos;",
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn kind_name(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Role => "role",
+        EdgeKind::Extends => "extends",
+        EdgeKind::NotExtends => "notExtends",
+    }
+}
+
+/// Write a network as GraphML.
+/// Nodes have a `concept` attribute and edges have `label` and `kind` attributes.
+pub fn write_graphml(entries: &BTreeSet<Entry>) -> String {
+    let projection = project(entries);
+    let mut result = String::new();
+    result += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+    result += "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n";
+    result += "  <key id=\"concept\" for=\"node\" attr.name=\"concept\" attr.type=\"boolean\"/>\n";
+    result += "  <key id=\"label\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>\n";
+    result += "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n";
+    result += "  <graph edgedefault=\"directed\">\n";
+    for node in &projection.nodes {
+        result += &format!(
+            "    <node id=\"{}\"><data key=\"concept\">{}</data></node>\n",
+            escape_xml(&node.key),
+            node.concept
+        );
+    }
+    for (i, edge) in projection.edges.iter().enumerate() {
+        result += &format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\"><data key=\"label\">{}</data><data key=\"kind\">{}</data></edge>\n",
+            i,
+            escape_xml(&edge.source),
+            escape_xml(&edge.target),
+            escape_xml(&edge.key),
+            kind_name(edge.kind)
+        );
+    }
+    result += "  </graph>\n";
+    result += "</graphml>\n";
+    result
+}
+
+/// Write a network as GEXF.
+/// Concepts are drawn as blue squares and other nodes as grey discs.
+pub fn write_gexf(entries: &BTreeSet<Entry>) -> String {
+    let projection = project(entries);
+    let mut result = String::new();
+    result += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+    result += "<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">\n";
+    result += "  <graph defaultedgetype=\"directed\">\n";
+    result += "    <attributes class=\"edge\">\n";
+    result += "      <attribute id=\"kind\" title=\"kind\" type=\"string\"/>\n";
+    result += "    </attributes>\n";
+    result += "    <nodes>\n";
+    for node in &projection.nodes {
+        let (color, shape) = if node.concept {
+            ("r=\"66\" g=\"133\" b=\"244\"", "square")
+        } else {
+            ("r=\"153\" g=\"153\" b=\"153\"", "disc")
+        };
+        result += &format!(
+            "      <node id=\"{0}\" label=\"{0}\"><viz:color {1}/><viz:shape value=\"{2}\"/></node>\n",
+            escape_xml(&node.key),
+            color,
+            shape
+        );
+    }
+    result += "    </nodes>\n";
+    result += "    <edges>\n";
+    for (i, edge) in projection.edges.iter().enumerate() {
+        result += &format!(
+            "      <edge id=\"e{}\" source=\"{}\" target=\"{}\" label=\"{}\"><attvalues><attvalue for=\"kind\" value=\"{}\"/></attvalues></edge>\n",
+            i,
+            escape_xml(&edge.source),
+            escape_xml(&edge.target),
+            escape_xml(&edge.key),
+            kind_name(edge.kind)
+        );
+    }
+    result += "    </edges>\n";
+    result += "  </graph>\n";
+    result += "</gexf>\n";
+    result
+}
+
+fn escape_dot(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Write a network as a Graphviz DOT digraph.
+/// Concepts are drawn as filled boxes, `Extends` edges are dashed and `NotExtends` edges are dotted and red.
+pub fn write_dot(entries: &BTreeSet<Entry>) -> String {
+    let projection = project(entries);
+    let mut result = String::from("digraph {\n");
+    for node in &projection.nodes {
+        if node.concept {
+            result += &format!(
+                "  {} [shape=box, style=filled, fillcolor=lightblue];\n",
+                escape_dot(&node.key)
+            );
+        } else {
+            result += &format!("  {};\n", escape_dot(&node.key));
+        }
+    }
+    for edge in &projection.edges {
+        let style = match edge.kind {
+            EdgeKind::Role => "",
+            EdgeKind::Extends => ", style=dashed",
+            EdgeKind::NotExtends => ", style=dotted, color=red",
+        };
+        result += &format!(
+            "  {} -> {} [label={}{}];\n",
+            escape_dot(&edge.source),
+            escape_dot(&edge.target),
+            escape_dot(&edge.key),
+            style
+        );
+    }
+    result += "}\n";
+    result
+}
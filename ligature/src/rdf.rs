@@ -0,0 +1,1038 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module supports exchanging Ligature data with RDF tools using
+//! N-Triples, N-Quads, Turtle, and TriG.
+//!
+//! Each Collection is mapped to a named graph with the Collection's name as the graph name
+//! and each Entry is mapped to a single triple.
+//!
+//! | Entry                | Triple                                 |
+//! | -------------------- | -------------------------------------- |
+//! | `Entry::Role`        | `first role second`                    |
+//! | `Entry::Extends`     | `element rdf:type concept`             |
+//! | `Entry::NotExtends`  | `element lig:notExtends concept`       |
+//!
+//! RDF has no way to state that a resource is not of a given type, so `Entry::NotExtends` uses
+//! the [NOT_EXTENDS] predicate.
+//!
+//! Elements are written as IRIs with their Namespace (if any) prepended to their value.
+//! Characters that IRIs can't contain are percent-encoded, and IRIs without a scheme are
+//! resolved against the base IRI given to the writer, following RFC 3986.
+//! Elements whose value starts with `_:` are written as blank nodes.
+//! Elements with a LangTag are written as language-tagged literals and Elements in the
+//! [LITERAL_NAMESPACE] are written as plain literals.
+//! When reading, all other literals become Elements in the [LITERAL_NAMESPACE] that hold the
+//! literal's lexical form, so datatypes are not preserved.
+
+use crate::{Collections, Element, Entry, LangTag, LigatureError, Namespace};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The IRI of `rdf:type`, used to represent `Entry::Extends`.
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// The namespace used for Ligature specific IRIs.
+pub const LIGATURE_NAMESPACE: &str = "https://ligature.dev/ns#";
+
+/// The IRI of `lig:notExtends`, used to represent `Entry::NotExtends`.
+pub const NOT_EXTENDS: &str = "https://ligature.dev/ns#notExtends";
+
+/// The Namespace of Elements that are literals rather than IRIs.
+pub const LITERAL_NAMESPACE: &str = "https://ligature.dev/ns#literal";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Term {
+    Iri(String),
+    Blank(String),
+    Literal(String, Option<String>),
+}
+
+fn is_blank_label(label: &str) -> bool {
+    !label.is_empty()
+        && !label.ends_with('.')
+        && label
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+fn is_absolute(iri: &str) -> bool {
+    match iri.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        None => false,
+    }
+}
+
+/// The parts of an IRI reference: its scheme, authority, path, query, and fragment.
+struct Reference<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Reference<'a> {
+    fn parse(iri: &'a str) -> Self {
+        let (rest, fragment) = match iri.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (iri, None),
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let (scheme, rest) = match rest.split_once(':') {
+            Some((scheme, rest)) if is_absolute(iri) && !scheme.contains('/') => {
+                (Some(scheme), rest)
+            }
+            _ => (None, rest),
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => match rest.find('/') {
+                Some(i) => (Some(&rest[..i]), &rest[i..]),
+                None => (Some(rest), ""),
+            },
+            None => (None, rest),
+        };
+        Self {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+}
+
+/// Remove the `.` and `..` segments of a path, as described in RFC 3986 section 5.2.4.
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output: Vec<&str> = vec![];
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = input[start..]
+                .find('/')
+                .map(|i| i + start)
+                .unwrap_or(input.len());
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
+
+/// Resolve an IRI reference against a base IRI, as described in RFC 3986 section 5.2.
+fn resolve(base: &str, reference: &str) -> String {
+    let base = Reference::parse(base);
+    let reference = Reference::parse(reference);
+    let (authority, path, query) = if reference.scheme.is_some() || reference.authority.is_some() {
+        (
+            reference.authority,
+            remove_dot_segments(reference.path),
+            reference.query,
+        )
+    } else if reference.path.is_empty() {
+        (
+            base.authority,
+            base.path.to_owned(),
+            reference.query.or(base.query),
+        )
+    } else if reference.path.starts_with('/') {
+        (
+            base.authority,
+            remove_dot_segments(reference.path),
+            reference.query,
+        )
+    } else {
+        let merged = if base.authority.is_some() && base.path.is_empty() {
+            format!("/{}", reference.path)
+        } else {
+            match base.path.rfind('/') {
+                Some(i) => format!("{}{}", &base.path[..=i], reference.path),
+                None => reference.path.to_owned(),
+            }
+        };
+        (
+            base.authority,
+            remove_dot_segments(&merged),
+            reference.query,
+        )
+    };
+    let mut result = String::new();
+    if let Some(scheme) = reference.scheme.or(base.scheme) {
+        result += &format!("{scheme}:");
+    }
+    if let Some(authority) = authority {
+        result += &format!("//{authority}");
+    }
+    result += &path;
+    if let Some(query) = query {
+        result += &format!("?{query}");
+    }
+    if let Some(fragment) = reference.fragment {
+        result += &format!("#{fragment}");
+    }
+    result
+}
+
+/// Percent-encode the characters an IRI can't contain and resolve it against `base` if it has no scheme.
+fn to_iri(value: &str, base: Option<&str>) -> Result<Term, LigatureError> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut iri = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        let escaped = chars.len() > i + 2
+            && chars[i + 1].is_ascii_hexdigit()
+            && chars[i + 2].is_ascii_hexdigit();
+        match c {
+            '\u{00}'..='\u{20}' | '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => {
+                iri += &format!("%{:02X}", *c as u32)
+            }
+            '%' if !escaped => iri += "%25",
+            c => iri.push(*c),
+        }
+    }
+    if is_absolute(&iri) {
+        Ok(Term::Iri(iri))
+    } else {
+        match base {
+            Some(base) => Ok(Term::Iri(resolve(base, &iri))),
+            None => Err(LigatureError(format!(
+                "`{value}` isn't an absolute IRI and no base IRI was given."
+            ))),
+        }
+    }
+}
+
+fn element_to_term(element: &Element, base: Option<&str>) -> Result<Term, LigatureError> {
+    match element {
+        Element(value, _, Some(LangTag(tag))) => {
+            Ok(Term::Literal(value.clone(), Some(tag.clone())))
+        }
+        Element(value, Some(Namespace(namespace)), None) if namespace == LITERAL_NAMESPACE => {
+            Ok(Term::Literal(value.clone(), None))
+        }
+        Element(value, Some(Namespace(namespace)), None) => {
+            to_iri(&(namespace.clone() + value), base)
+        }
+        Element(value, None, None) => match value.strip_prefix("_:") {
+            Some(label) if is_blank_label(label) => Ok(Term::Blank(label.to_owned())),
+            _ => to_iri(value, base),
+        },
+    }
+}
+
+fn term_to_element(term: Term) -> Element {
+    match term {
+        Term::Iri(iri) => Element(iri, None, None),
+        Term::Blank(label) => Element(format!("_:{label}"), None, None),
+        Term::Literal(value, None) => {
+            Element(value, Some(Namespace(LITERAL_NAMESPACE.to_owned())), None)
+        }
+        Term::Literal(value, lang) => Element(value, None, lang.map(LangTag)),
+    }
+}
+
+/// Convert an Element used as a subject or graph name, which can't be a literal.
+fn resource_to_term(
+    element: &Element,
+    base: Option<&str>,
+    position: &str,
+) -> Result<Term, LigatureError> {
+    match element_to_term(element, base)? {
+        Term::Literal(value, _) => Err(LigatureError(format!(
+            "`{value}` is a literal and can't be used as a {position}."
+        ))),
+        term => Ok(term),
+    }
+}
+
+fn entry_to_triple(entry: &Entry, base: Option<&str>) -> Result<(Term, Term, Term), LigatureError> {
+    Ok(match entry {
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => (
+            resource_to_term(first, base, "subject")?,
+            match element_to_term(role, base)? {
+                Term::Iri(iri) => Term::Iri(iri),
+                _ => {
+                    return Err(LigatureError(format!(
+                        "`{}` isn't an IRI and can't be used as a predicate.",
+                        role.0
+                    )))
+                }
+            },
+            element_to_term(second, base)?,
+        ),
+        Entry::Extends { element, concept } => (
+            resource_to_term(element, base, "subject")?,
+            Term::Iri(RDF_TYPE.to_owned()),
+            element_to_term(concept, base)?,
+        ),
+        Entry::NotExtends { element, concept } => (
+            resource_to_term(element, base, "subject")?,
+            Term::Iri(NOT_EXTENDS.to_owned()),
+            element_to_term(concept, base)?,
+        ),
+    })
+}
+
+fn triple_to_entry(subject: Term, predicate: Term, object: Term) -> Entry {
+    match predicate {
+        Term::Iri(ref iri) if iri == RDF_TYPE => Entry::Extends {
+            element: term_to_element(subject),
+            concept: term_to_element(object),
+        },
+        Term::Iri(ref iri) if iri == NOT_EXTENDS => Entry::NotExtends {
+            element: term_to_element(subject),
+            concept: term_to_element(object),
+        },
+        predicate => Entry::Role {
+            first: term_to_element(subject),
+            second: term_to_element(object),
+            role: term_to_element(predicate),
+        },
+    }
+}
+
+fn write_iri(iri: &str) -> String {
+    format!("<{iri}>")
+}
+
+fn write_literal(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+fn write_term(term: &Term) -> String {
+    match term {
+        Term::Iri(iri) => write_iri(iri),
+        Term::Blank(label) => format!("_:{label}"),
+        Term::Literal(value, None) => write_literal(value),
+        Term::Literal(value, Some(tag)) => format!("{}@{}", write_literal(value), tag),
+    }
+}
+
+fn write_turtle_predicate(term: &Term) -> String {
+    match term {
+        Term::Iri(iri) if iri == RDF_TYPE => "a".to_owned(),
+        Term::Iri(iri) if iri == NOT_EXTENDS => "lig:notExtends".to_owned(),
+        term => write_term(term),
+    }
+}
+
+/// Write a set of Entries as N-Triples.
+/// Elements that aren't absolute IRIs are resolved against `base`, and are an error without one.
+pub fn write_ntriples(
+    entries: &BTreeSet<Entry>,
+    base: Option<&str>,
+) -> Result<String, LigatureError> {
+    let mut result = String::new();
+    for entry in entries {
+        let (subject, predicate, object) = entry_to_triple(entry, base)?;
+        result += &format!(
+            "{} {} {} .\n",
+            write_term(&subject),
+            write_term(&predicate),
+            write_term(&object)
+        );
+    }
+    Ok(result)
+}
+
+/// Write a set of Collections as N-Quads, using each Collection's name as the graph name.
+/// Elements that aren't absolute IRIs are resolved against `base`, and are an error without one.
+pub fn write_nquads(
+    collections: &Collections,
+    base: Option<&str>,
+) -> Result<String, LigatureError> {
+    let mut result = String::new();
+    for (collection, entries) in collections {
+        let graph = write_term(&resource_to_term(collection, base, "graph name")?);
+        for entry in entries {
+            let (subject, predicate, object) = entry_to_triple(entry, base)?;
+            result += &format!(
+                "{} {} {} {} .\n",
+                write_term(&subject),
+                write_term(&predicate),
+                write_term(&object),
+                graph
+            );
+        }
+    }
+    Ok(result)
+}
+
+fn write_turtle_prefixes() -> String {
+    format!("@prefix lig: {} .\n", write_iri(LIGATURE_NAMESPACE))
+}
+
+fn write_turtle_triples(
+    entries: &BTreeSet<Entry>,
+    indent: &str,
+    base: Option<&str>,
+) -> Result<String, LigatureError> {
+    let mut subjects: BTreeMap<Term, BTreeMap<Term, Vec<Term>>> = BTreeMap::new();
+    for entry in entries {
+        let (subject, predicate, object) = entry_to_triple(entry, base)?;
+        subjects
+            .entry(subject)
+            .or_default()
+            .entry(predicate)
+            .or_default()
+            .push(object);
+    }
+    let mut result = String::new();
+    for (subject, predicates) in subjects {
+        result += &format!("{}{}", indent, write_term(&subject));
+        let predicates: Vec<String> = predicates
+            .iter()
+            .map(|(predicate, objects)| {
+                let objects: Vec<String> = objects.iter().map(write_term).collect();
+                format!(
+                    "{} {}",
+                    write_turtle_predicate(predicate),
+                    objects.join(", ")
+                )
+            })
+            .collect();
+        result += &format!(" {} .\n", predicates.join(&format!(" ;\n{}    ", indent)));
+    }
+    Ok(result)
+}
+
+/// Write a set of Entries as Turtle.
+/// Elements that aren't absolute IRIs are resolved against `base`, and are an error without one.
+pub fn write_turtle(
+    entries: &BTreeSet<Entry>,
+    base: Option<&str>,
+) -> Result<String, LigatureError> {
+    Ok(write_turtle_prefixes() + "\n" + &write_turtle_triples(entries, "", base)?)
+}
+
+/// Write a set of Collections as TriG, using each Collection's name as the graph name.
+/// Elements that aren't absolute IRIs are resolved against `base`, and are an error without one.
+pub fn write_trig(collections: &Collections, base: Option<&str>) -> Result<String, LigatureError> {
+    let mut result = write_turtle_prefixes();
+    for (collection, entries) in collections {
+        result += &format!(
+            "\n{} {{\n{}}}\n",
+            write_term(&resource_to_term(collection, base, "graph name")?),
+            write_turtle_triples(entries, "  ", base)?
+        );
+    }
+    Ok(result)
+}
+
+/// Read a set of Entries from N-Triples.
+pub fn read_ntriples(input: &str) -> Result<BTreeSet<Entry>, LigatureError> {
+    let default = Element(String::new(), None, None);
+    let mut parser = Parser::new(input, "N-Triples");
+    parser.quads(false, &default)?;
+    Ok(parser.result.remove(&default).unwrap_or_default())
+}
+
+/// Read a set of Collections from N-Quads.
+/// Triples in the default graph are added to `default_collection`.
+pub fn read_nquads(
+    input: &str,
+    default_collection: &Element,
+) -> Result<Collections, LigatureError> {
+    let mut parser = Parser::new(input, "N-Quads");
+    parser.quads(true, default_collection)?;
+    Ok(parser.result)
+}
+
+/// Read a set of Entries from Turtle.
+pub fn read_turtle(input: &str) -> Result<BTreeSet<Entry>, LigatureError> {
+    let default = Element(String::new(), None, None);
+    let mut parser = Parser::new(input, "Turtle");
+    parser.turtle(false, &default)?;
+    Ok(parser.result.remove(&default).unwrap_or_default())
+}
+
+/// Read a set of Collections from TriG.
+/// Triples in the default graph are added to `default_collection`.
+pub fn read_trig(input: &str, default_collection: &Element) -> Result<Collections, LigatureError> {
+    let mut parser = Parser::new(input, "TriG");
+    parser.turtle(true, default_collection)?;
+    Ok(parser.result)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    offset: usize,
+    format: &'static str,
+    prefixes: HashMap<String, String>,
+    base: Option<String>,
+    blank_node_prefix: String,
+    blank_nodes: usize,
+    result: Collections,
+}
+
+/// Pick a prefix for generated blank node labels that no label in the input starts with,
+/// so they can't collide with the input's own labels.
+pub(crate) fn blank_node_prefix(input: &str) -> String {
+    let labels: Vec<&str> = input.split("_:").skip(1).collect();
+    let mut prefix = "b".to_owned();
+    while labels.iter().any(|label| label.starts_with(&prefix)) {
+        prefix.push('b');
+    }
+    prefix
+}
+
+impl Parser {
+    fn new(input: &str, format: &'static str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            offset: 0,
+            format,
+            prefixes: HashMap::new(),
+            base: None,
+            blank_node_prefix: blank_node_prefix(input),
+            blank_nodes: 0,
+            result: BTreeMap::new(),
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, LigatureError> {
+        let mut line = 1;
+        let mut column = 1;
+        for c in &self.chars[..self.offset.min(self.chars.len())] {
+            if *c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        Err(LigatureError(format!(
+            "Error reading {} at line {}, column {}: {}",
+            self.format, line, column, message
+        )))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.offset).copied()
+    }
+
+    fn peek_at(&self, distance: usize) -> Option<char> {
+        self.chars.get(self.offset + distance).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.offset += 1;
+        }
+        c
+    }
+
+    fn is_complete(&mut self) -> bool {
+        self.skip_ws();
+        self.peek().is_none()
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.offset += 1;
+            } else if c == '#' {
+                while let Some(c) = self.next() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), LigatureError> {
+        self.skip_ws();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.offset += 1;
+                Ok(())
+            }
+            Some(c) => self.error(&format!("expected `{expected}` but found `{c}`")),
+            None => self.error(&format!("expected `{expected}` but reached end of input")),
+        }
+    }
+
+    /// Check for a keyword, ignoring case, that is followed by whitespace.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let length = keyword.chars().count();
+        let matches = keyword
+            .chars()
+            .enumerate()
+            .all(|(i, k)| self.peek_at(i).map(|c| c.eq_ignore_ascii_case(&k)) == Some(true));
+        if matches && self.peek_at(length).map(|c| c.is_whitespace()) == Some(true) {
+            self.offset += length;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn insert(&mut self, graph: &Element, subject: Term, predicate: Term, object: Term) {
+        self.result
+            .entry(graph.clone())
+            .or_default()
+            .insert(triple_to_entry(subject, predicate, object));
+    }
+
+    fn unicode_escape(&mut self, digits: usize) -> Result<char, LigatureError> {
+        let mut value = String::new();
+        for _ in 0..digits {
+            match self.next() {
+                Some(c) if c.is_ascii_hexdigit() => value.push(c),
+                _ => return self.error("invalid unicode escape"),
+            }
+        }
+        match u32::from_str_radix(&value, 16)
+            .ok()
+            .and_then(char::from_u32)
+        {
+            Some(c) => Ok(c),
+            None => self.error("invalid unicode escape"),
+        }
+    }
+
+    fn iri(&mut self) -> Result<String, LigatureError> {
+        self.expect('<')?;
+        let mut iri = String::new();
+        loop {
+            match self.next() {
+                Some('>') => break,
+                Some('\\') => match self.next() {
+                    Some('u') => iri.push(self.unicode_escape(4)?),
+                    Some('U') => iri.push(self.unicode_escape(8)?),
+                    _ => return self.error("invalid escape in IRI"),
+                },
+                Some(c) if c == '\n' || c == '\r' => return self.error("unterminated IRI"),
+                Some(c) => iri.push(c),
+                None => return self.error("unterminated IRI"),
+            }
+        }
+        match &self.base {
+            Some(base) if !is_absolute(&iri) => Ok(resolve(base, &iri)),
+            _ => Ok(iri),
+        }
+    }
+
+    fn blank_node(&mut self) -> Result<Term, LigatureError> {
+        self.offset += 2;
+        let mut label = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                label.push(c);
+                self.offset += 1;
+            } else {
+                break;
+            }
+        }
+        while label.ends_with('.') {
+            label.pop();
+            self.offset -= 1;
+        }
+        if label.is_empty() {
+            self.error("expected a blank node label")
+        } else {
+            Ok(Term::Blank(label))
+        }
+    }
+
+    fn fresh_blank_node(&mut self) -> Term {
+        self.blank_nodes += 1;
+        Term::Blank(format!("{}{}", self.blank_node_prefix, self.blank_nodes))
+    }
+
+    fn string(&mut self) -> Result<String, LigatureError> {
+        let quote = match self.next() {
+            Some(c) if c == '"' || c == '\'' => c,
+            _ => return self.error("expected a string"),
+        };
+        let long = self.peek() == Some(quote) && self.peek_at(1) == Some(quote);
+        if long {
+            self.offset += 2;
+        }
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some(c) if c == quote => {
+                    if !long {
+                        break;
+                    } else if self.peek() == Some(quote) && self.peek_at(1) == Some(quote) {
+                        self.offset += 2;
+                        break;
+                    } else {
+                        value.push(c);
+                    }
+                }
+                Some('\\') => match self.next() {
+                    Some('t') => value.push('\t'),
+                    Some('b') => value.push('\u{08}'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('f') => value.push('\u{0C}'),
+                    Some('"') => value.push('"'),
+                    Some('\'') => value.push('\''),
+                    Some('\\') => value.push('\\'),
+                    Some('u') => value.push(self.unicode_escape(4)?),
+                    Some('U') => value.push(self.unicode_escape(8)?),
+                    _ => return self.error("invalid escape in string"),
+                },
+                Some(c) if !long && (c == '\n' || c == '\r') => {
+                    return self.error("unterminated string")
+                }
+                Some(c) => value.push(c),
+                None => return self.error("unterminated string"),
+            }
+        }
+        Ok(value)
+    }
+
+    fn literal(&mut self, turtle: bool) -> Result<Term, LigatureError> {
+        let value = self.string()?;
+        match (self.peek(), self.peek_at(1)) {
+            (Some('@'), _) => {
+                self.offset += 1;
+                let mut tag = String::new();
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_alphanumeric() || c == '-' {
+                        tag.push(c);
+                        self.offset += 1;
+                    } else {
+                        break;
+                    }
+                }
+                if tag.is_empty() {
+                    self.error("expected a language tag")
+                } else {
+                    Ok(Term::Literal(value, Some(tag)))
+                }
+            }
+            (Some('^'), Some('^')) => {
+                self.offset += 2;
+                self.iri_or_prefixed_name(turtle)?;
+                Ok(Term::Literal(value, None))
+            }
+            _ => Ok(Term::Literal(value, None)),
+        }
+    }
+
+    fn iri_or_prefixed_name(&mut self, turtle: bool) -> Result<String, LigatureError> {
+        match self.peek() {
+            Some('<') => self.iri(),
+            Some(_) if turtle => self.prefixed_name(),
+            Some(c) => self.error(&format!("expected an IRI but found `{c}`")),
+            None => self.error("expected an IRI but reached end of input"),
+        }
+    }
+
+    fn name_chars(&mut self, allow_colon: bool) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric()
+                || c == '_'
+                || c == '-'
+                || c == '.'
+                || c == '%'
+                || (allow_colon && c == ':')
+            {
+                name.push(c);
+                self.offset += 1;
+            } else {
+                break;
+            }
+        }
+        while name.ends_with('.') {
+            name.pop();
+            self.offset -= 1;
+        }
+        name
+    }
+
+    fn prefixed_name(&mut self) -> Result<String, LigatureError> {
+        let prefix = self.name_chars(false);
+        if self.peek() != Some(':') {
+            return self.error(&format!("expected a prefixed name but found `{prefix}`"));
+        }
+        self.offset += 1;
+        let local = self.name_chars(true);
+        match self.prefixes.get(&prefix) {
+            Some(namespace) => Ok(namespace.clone() + &local),
+            None => self.error(&format!("undefined prefix `{prefix}:`")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Term, LigatureError> {
+        let mut value = String::new();
+        while let Some(c) = self.peek() {
+            let next_is_digit = self.peek_at(1).map(|c| c.is_ascii_digit()) == Some(true);
+            if c.is_ascii_digit()
+                || c == '+'
+                || c == '-'
+                || c == 'e'
+                || c == 'E'
+                || (c == '.' && next_is_digit)
+            {
+                value.push(c);
+                self.offset += 1;
+            } else {
+                break;
+            }
+        }
+        if value.chars().any(|c| c.is_ascii_digit()) {
+            Ok(Term::Literal(value, None))
+        } else {
+            match self.peek() {
+                Some(c) if value.is_empty() => self.error(&format!("unexpected `{c}`")),
+                _ => self.error(&format!("`{value}` isn't a number")),
+            }
+        }
+    }
+
+    fn term(&mut self, turtle: bool) -> Result<Term, LigatureError> {
+        self.skip_ws();
+        match self.peek() {
+            Some('<') => self.iri().map(Term::Iri),
+            Some('_') if self.peek_at(1) == Some(':') => self.blank_node(),
+            Some('"') => self.literal(turtle),
+            Some('\'') if turtle => self.literal(turtle),
+            Some(c) if turtle && (c.is_ascii_digit() || c == '+' || c == '-' || c == '.') => {
+                self.number()
+            }
+            Some('t') if turtle && self.keyword_term("true") => {
+                self.offset += 4;
+                Ok(Term::Literal("true".to_owned(), None))
+            }
+            Some('f') if turtle && self.keyword_term("false") => {
+                self.offset += 5;
+                Ok(Term::Literal("false".to_owned(), None))
+            }
+            Some('(') if turtle => self.error("RDF collections are not supported"),
+            Some(_) if turtle => self.prefixed_name().map(Term::Iri),
+            Some(c) => self.error(&format!("unexpected `{c}`")),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    /// Check if a keyword is next without consuming it.
+    fn keyword_term(&self, keyword: &str) -> bool {
+        let length = keyword.chars().count();
+        keyword
+            .chars()
+            .enumerate()
+            .all(|(i, k)| self.peek_at(i) == Some(k))
+            && !self
+                .peek_at(length)
+                .map(|c| c.is_alphanumeric() || c == ':' || c == '_' || c == '-')
+                .unwrap_or(false)
+    }
+
+    fn quads(&mut self, allow_graph: bool, default: &Element) -> Result<(), LigatureError> {
+        while !self.is_complete() {
+            let subject = match self.term(false)? {
+                Term::Literal(_, _) => return self.error("a subject can't be a literal"),
+                subject => subject,
+            };
+            let predicate = match self.term(false)? {
+                Term::Iri(iri) => Term::Iri(iri),
+                _ => return self.error("a predicate must be an IRI"),
+            };
+            let object = self.term(false)?;
+            self.skip_ws();
+            let graph = if allow_graph && self.peek() != Some('.') {
+                match self.term(false)? {
+                    Term::Literal(_, _) => return self.error("a graph name can't be a literal"),
+                    graph => term_to_element(graph),
+                }
+            } else {
+                default.clone()
+            };
+            self.expect('.')?;
+            self.insert(&graph, subject, predicate, object);
+        }
+        Ok(())
+    }
+
+    fn turtle(&mut self, trig: bool, default: &Element) -> Result<(), LigatureError> {
+        while !self.is_complete() {
+            if self.keyword("@prefix") {
+                self.prefix()?;
+                self.expect('.')?;
+            } else if self.keyword("@base") {
+                self.skip_ws();
+                self.base = Some(self.iri()?);
+                self.expect('.')?;
+            } else if self.keyword("prefix") {
+                self.prefix()?;
+            } else if self.keyword("base") {
+                self.skip_ws();
+                self.base = Some(self.iri()?);
+            } else if trig && self.keyword("graph") {
+                let graph = self.graph_name()?;
+                self.graph(&graph)?;
+            } else if trig && self.peek() == Some('{') {
+                self.graph(default)?;
+            } else {
+                let subject = self.subject(default)?;
+                self.skip_ws();
+                if trig && self.peek() == Some('{') {
+                    self.graph(&term_to_element(subject))?;
+                } else {
+                    self.predicate_object_list(default, &subject)?;
+                    self.expect('.')?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn prefix(&mut self) -> Result<(), LigatureError> {
+        self.skip_ws();
+        let prefix = self.name_chars(false);
+        self.expect(':')?;
+        self.skip_ws();
+        let namespace = self.iri()?;
+        self.prefixes.insert(prefix, namespace);
+        Ok(())
+    }
+
+    fn graph_name(&mut self) -> Result<Element, LigatureError> {
+        match self.term(true)? {
+            Term::Literal(_, _) => self.error("a graph name can't be a literal"),
+            term => Ok(term_to_element(term)),
+        }
+    }
+
+    fn graph(&mut self, graph: &Element) -> Result<(), LigatureError> {
+        self.expect('{')?;
+        self.result.entry(graph.clone()).or_default();
+        loop {
+            self.skip_ws();
+            if self.peek() == Some('}') {
+                self.offset += 1;
+                return Ok(());
+            }
+            let subject = self.subject(graph)?;
+            self.predicate_object_list(graph, &subject)?;
+            self.skip_ws();
+            match self.peek() {
+                Some('.') => self.offset += 1,
+                Some('}') => (),
+                Some(c) => return self.error(&format!("expected `.` or `}}` but found `{c}`")),
+                None => return self.error("unterminated graph"),
+            }
+        }
+    }
+
+    fn subject(&mut self, graph: &Element) -> Result<Term, LigatureError> {
+        self.skip_ws();
+        if self.peek() == Some('[') {
+            return self.blank_node_property_list(graph);
+        }
+        match self.term(true)? {
+            Term::Literal(_, _) => self.error("a subject can't be a literal"),
+            subject => Ok(subject),
+        }
+    }
+
+    fn object(&mut self, graph: &Element) -> Result<Term, LigatureError> {
+        self.skip_ws();
+        if self.peek() == Some('[') {
+            self.blank_node_property_list(graph)
+        } else {
+            self.term(true)
+        }
+    }
+
+    fn blank_node_property_list(&mut self, graph: &Element) -> Result<Term, LigatureError> {
+        self.expect('[')?;
+        let node = self.fresh_blank_node();
+        self.skip_ws();
+        if self.peek() != Some(']') {
+            self.predicate_object_list(graph, &node)?;
+        }
+        self.expect(']')?;
+        Ok(node)
+    }
+
+    fn verb(&mut self) -> Result<Term, LigatureError> {
+        self.skip_ws();
+        if self.peek() == Some('a')
+            && self
+                .peek_at(1)
+                .map(|c| c.is_whitespace() || c == '<' || c == '[' || c == '"')
+                .unwrap_or(false)
+        {
+            self.offset += 1;
+            return Ok(Term::Iri(RDF_TYPE.to_owned()));
+        }
+        match self.term(true)? {
+            Term::Iri(iri) => Ok(Term::Iri(iri)),
+            _ => self.error("a predicate must be an IRI"),
+        }
+    }
+
+    fn predicate_object_list(
+        &mut self,
+        graph: &Element,
+        subject: &Term,
+    ) -> Result<(), LigatureError> {
+        loop {
+            let predicate = self.verb()?;
+            loop {
+                let object = self.object(graph)?;
+                self.insert(graph, subject.clone(), predicate.clone(), object);
+                self.skip_ws();
+                if self.peek() == Some(',') {
+                    self.offset += 1;
+                } else {
+                    break;
+                }
+            }
+            self.skip_ws();
+            if self.peek() != Some(';') {
+                return Ok(());
+            }
+            while self.peek() == Some(';') {
+                self.offset += 1;
+                self.skip_ws();
+            }
+            match self.peek() {
+                Some('.') | Some(']') | Some('}') | None => return Ok(()),
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module is a forward-chaining rule engine.
+//!
+//! A [Rule] has a set of premises and a set of conclusions, both written as Entries.
+//! Elements starting with `?` are variables, and `?` on its own matches any Element in a premise.
+//! Whenever every premise matches, with each variable matching the same Element everywhere,
+//! the conclusions are inferred with the variables filled in.
+//!
+//! Rules are applied to a Collection until nothing new is inferred, using [Ligature::query] to find matches.
+//! Each round only looks for matches that use at least one Entry added in the round before it,
+//! starting with the Entries that were added to the Collection.
+
+use crate::{Element, Entry, Ligature, LigatureError};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Elements bound to variables, keyed by the variable's name without the `?`.
+pub type Bindings = BTreeMap<String, Element>;
+
+/// A rule inferring its conclusions wherever its premises match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    premises: BTreeSet<Entry>,
+    conclusions: BTreeSet<Entry>,
+}
+
+fn variable(element: &Element) -> Option<&str> {
+    match element.0.strip_prefix('?') {
+        Some(name) if !name.is_empty() => Some(name),
+        _ => None,
+    }
+}
+
+fn elements(entry: &Entry) -> Vec<&Element> {
+    match entry {
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => vec![first, role, second],
+        Entry::Extends { element, concept } | Entry::NotExtends { element, concept } => {
+            vec![element, concept]
+        }
+    }
+}
+
+impl Rule {
+    /// Create a rule.
+    /// Fails if the rule has no premises or a conclusion uses a variable that isn't in a premise.
+    pub fn new(
+        premises: BTreeSet<Entry>,
+        conclusions: BTreeSet<Entry>,
+    ) -> Result<Self, LigatureError> {
+        if premises.is_empty() {
+            return Err(LigatureError("A rule needs at least one premise.".to_owned()));
+        }
+        let bound: BTreeSet<&str> = premises
+            .iter()
+            .flat_map(elements)
+            .filter_map(variable)
+            .collect();
+        for element in conclusions.iter().flat_map(elements) {
+            if element.0 == "?" {
+                return Err(LigatureError(
+                    "`?` can only be used in a rule's premises.".to_owned(),
+                ));
+            }
+            if let Some(name) = variable(element) {
+                if !bound.contains(name) {
+                    return Err(LigatureError(format!(
+                        "`?{name}` is used in a conclusion but not in a premise."
+                    )));
+                }
+            }
+        }
+        Ok(Self {
+            premises,
+            conclusions,
+        })
+    }
+
+    /// The Entries that must match for the rule to apply.
+    pub fn premises(&self) -> &BTreeSet<Entry> {
+        &self.premises
+    }
+
+    /// The Entries inferred when the rule applies.
+    pub fn conclusions(&self) -> &BTreeSet<Entry> {
+        &self.conclusions
+    }
+}
+
+fn match_element(pattern: &Element, element: &Element, bindings: &mut Bindings) -> bool {
+    if pattern.0 == "?" {
+        return true;
+    }
+    match variable(pattern) {
+        Some(name) => match bindings.get(name) {
+            Some(bound) => bound == element,
+            None => {
+                bindings.insert(name.to_owned(), element.clone());
+                true
+            }
+        },
+        None => pattern == element,
+    }
+}
+
+/// Match a single pattern against an Entry.
+/// Role patterns only match Roles, and Extends and NotExtends patterns only match their own kind of Entry.
+fn match_entry(pattern: &Entry, entry: &Entry) -> Option<Bindings> {
+    let mut bindings = Bindings::new();
+    let matched = match (pattern, entry) {
+        (
+            Entry::Role {
+                first,
+                second,
+                role,
+            },
+            Entry::Role {
+                first: f,
+                second: s,
+                role: r,
+            },
+        ) => {
+            match_element(first, f, &mut bindings)
+                && match_element(role, r, &mut bindings)
+                && match_element(second, s, &mut bindings)
+        }
+        (
+            Entry::Extends { element, concept },
+            Entry::Extends {
+                element: e,
+                concept: c,
+            },
+        )
+        | (
+            Entry::NotExtends { element, concept },
+            Entry::NotExtends {
+                element: e,
+                concept: c,
+            },
+        ) => match_element(element, e, &mut bindings) && match_element(concept, c, &mut bindings),
+        _ => false,
+    };
+    if matched {
+        Some(bindings)
+    } else {
+        None
+    }
+}
+
+/// Replace the variables in a pattern with the Elements they are bound to.
+/// Variables that aren't bound are left in place.
+fn bind(pattern: &Entry, bindings: &Bindings) -> Entry {
+    let bind = |element: &Element| match variable(element).and_then(|name| bindings.get(name)) {
+        Some(bound) => bound.clone(),
+        None => element.clone(),
+    };
+    match pattern {
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => Entry::Role {
+            first: bind(first),
+            second: bind(second),
+            role: bind(role),
+        },
+        Entry::Extends { element, concept } => Entry::Extends {
+            element: bind(element),
+            concept: bind(concept),
+        },
+        Entry::NotExtends { element, concept } => Entry::NotExtends {
+            element: bind(element),
+            concept: bind(concept),
+        },
+    }
+}
+
+/// Replace the variables in a pattern with the Elements they are bound to.
+/// Fails if a variable isn't bound.
+pub fn instantiate(pattern: &Entry, bindings: &Bindings) -> Result<Entry, LigatureError> {
+    let entry = bind(pattern, bindings);
+    match elements(&entry).into_iter().find_map(variable) {
+        Some(name) => Err(LigatureError(format!("`?{name}` isn't bound."))),
+        None => Ok(entry),
+    }
+}
+
+/// Find the matches of a rule in a Collection that use at least one Entry from `delta`.
+/// Each premise is matched against the Entries in `delta`,
+/// and the rest of the premises are queried with the variables it bound filled in.
+fn new_matches(
+    ligature: &dyn Ligature,
+    collection: &Element,
+    rule: &Rule,
+    delta: &BTreeSet<Entry>,
+) -> Result<Vec<Bindings>, LigatureError> {
+    let mut results = vec![];
+    for premise in &rule.premises {
+        for entry in delta {
+            let bindings = match match_entry(premise, entry) {
+                Some(bindings) => bindings,
+                None => continue,
+            };
+            let rest = rule
+                .premises
+                .iter()
+                .filter(|other| *other != premise)
+                .map(|other| bind(other, &bindings))
+                .collect();
+            for (found, _) in ligature.query(collection.clone(), rest)?.set_iter() {
+                let mut bindings = bindings.clone();
+                bindings.extend(found.clone());
+                results.push(bindings);
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Add Entries to a Collection, creating it if it doesn't exist,
+/// and apply rules until nothing new is inferred, adding what is inferred to the Collection.
+/// Only matches that use at least one Entry that wasn't already in the Collection are looked for,
+/// so rules are applied to the Collection incrementally as Entries are added to it.
+/// Returns the inferred Entries that weren't already in the Collection.
+pub fn infer_into(
+    ligature: &mut dyn Ligature,
+    collection: Element,
+    entries: &BTreeSet<Entry>,
+    rules: &[Rule],
+) -> Result<BTreeSet<Entry>, LigatureError> {
+    let mut known = if ligature.collections()?.contains(&collection) {
+        ligature.entries(&collection)?
+    } else {
+        ligature.add_collection(collection.clone())?;
+        BTreeSet::new()
+    };
+    let mut delta: BTreeSet<Entry> = entries.difference(&known).cloned().collect();
+    let mut inferred = BTreeSet::new();
+    while !delta.is_empty() {
+        ligature.add_entries(collection.clone(), &mut delta.clone())?;
+        known.extend(delta.iter().cloned());
+        let mut next = BTreeSet::new();
+        for rule in rules {
+            for bindings in new_matches(ligature, &collection, rule, &delta)? {
+                for conclusion in &rule.conclusions {
+                    let entry = instantiate(conclusion, &bindings)?;
+                    if !known.contains(&entry) {
+                        next.insert(entry);
+                    }
+                }
+            }
+        }
+        inferred.extend(next.iter().cloned());
+        delta = next;
+    }
+    Ok(inferred)
+}
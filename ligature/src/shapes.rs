@@ -0,0 +1,309 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module validates networks against shapes.
+//!
+//! Shapes are written as a network where each constraint is an Element with these roles:
+//!
+//! - `concept` (required): the constraint applies to every Element extending this Concept,
+//!   directly or through the concept hierarchy. Elements that other Elements extend are
+//!   treated as Concepts rather than instances, so they aren't checked.
+//! - `role` (required): the role being constrained.
+//! - `minCount` and `maxCount`: how many values the role must have.
+//! - `valueConcept`: a Concept every value of the role must extend.
+//!
+//! For example, every Person having exactly one name and only working at Companies is written as
+//! `{personName concept Person, personName role name, personName minCount 1, personName maxCount 1,
+//! personWorksAt concept Person, personWorksAt role worksAt, personWorksAt valueConcept Company}`.
+
+use crate::concepts::closure;
+use crate::{Element, Entry, Ligature, LigatureError};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A constraint on the values of a role.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    /// The Element naming this constraint in the shapes network.
+    pub name: Element,
+    /// The Concept whose Elements are constrained.
+    pub concept: Element,
+    /// The role being constrained.
+    pub role: Element,
+    /// The fewest values allowed.
+    pub min_count: Option<usize>,
+    /// The most values allowed.
+    pub max_count: Option<usize>,
+    /// A Concept every value must extend.
+    pub value_concept: Option<Element>,
+}
+
+/// A failed constraint.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Violation {
+    /// The Element that failed the constraint.
+    pub focus: Element,
+    /// The name of the constraint.
+    pub constraint: Element,
+    /// A description of the problem.
+    pub message: String,
+}
+
+fn count(name: &Element, value: &Element) -> Result<usize, LigatureError> {
+    value.0.parse().map_err(|_| {
+        LigatureError(format!(
+            "`{}` must be given a number of values, not `{}`.",
+            name.0, value.0
+        ))
+    })
+}
+
+/// Read the constraints from a shapes network.
+pub fn read_shapes(shapes: &BTreeSet<Entry>) -> Result<Vec<Constraint>, LigatureError> {
+    let mut roles: BTreeMap<&Element, BTreeMap<&str, &Element>> = BTreeMap::new();
+    for entry in shapes {
+        if let Entry::Role {
+            first,
+            second,
+            role,
+        } = entry
+        {
+            let values = roles.entry(first).or_default();
+            if values.insert(role.0.as_str(), second).is_some() {
+                return Err(LigatureError(format!(
+                    "`{}` has more than one `{}`.",
+                    first.0, role.0
+                )));
+            }
+        }
+    }
+    let mut result = vec![];
+    for (name, values) in roles {
+        let required = |key: &str| {
+            values
+                .get(key)
+                .map(|value| (*value).clone())
+                .ok_or(LigatureError(format!("`{}` is missing a `{key}`.", name.0)))
+        };
+        result.push(Constraint {
+            name: name.clone(),
+            concept: required("concept")?,
+            role: required("role")?,
+            min_count: values
+                .get("minCount")
+                .map(|value| count(name, value))
+                .transpose()?,
+            max_count: values
+                .get("maxCount")
+                .map(|value| count(name, value))
+                .transpose()?,
+            value_concept: values.get("valueConcept").map(|value| (*value).clone()),
+        });
+    }
+    Ok(result)
+}
+
+/// Check a network against a set of constraints.
+pub fn validate(entries: &BTreeSet<Entry>, constraints: &[Constraint]) -> Vec<Violation> {
+    let mut concepts: BTreeMap<&Element, BTreeSet<&Element>> = BTreeMap::new();
+    let closure = closure(entries);
+    for entry in &closure {
+        if let Entry::Extends { element, concept } = entry {
+            concepts.entry(concept).or_default().insert(element);
+        }
+    }
+    let instances = |concept: &Element| {
+        concepts
+            .get(concept)
+            .into_iter()
+            .flatten()
+            .filter(|element| !concepts.contains_key(**element))
+            .copied()
+            .collect::<Vec<&Element>>()
+    };
+    let mut values: BTreeMap<(&Element, &Element), BTreeSet<&Element>> = BTreeMap::new();
+    for entry in entries {
+        if let Entry::Role {
+            first,
+            second,
+            role,
+        } = entry
+        {
+            values.entry((first, role)).or_default().insert(second);
+        }
+    }
+    let extends = |element: &Element, concept: &Element| {
+        concepts
+            .get(concept)
+            .is_some_and(|elements| elements.contains(element))
+    };
+    let mut result = vec![];
+    for constraint in constraints {
+        for focus in instances(&constraint.concept) {
+            let found = values
+                .get(&(focus, &constraint.role))
+                .cloned()
+                .unwrap_or_default();
+            let violation = |message: String| Violation {
+                focus: focus.clone(),
+                constraint: constraint.name.clone(),
+                message,
+            };
+            if let Some(min) = constraint.min_count {
+                if found.len() < min {
+                    result.push(violation(format!(
+                        "`{}` has {} values for `{}` but needs at least {min}.",
+                        focus.0,
+                        found.len(),
+                        constraint.role.0
+                    )));
+                }
+            }
+            if let Some(max) = constraint.max_count {
+                if found.len() > max {
+                    result.push(violation(format!(
+                        "`{}` has {} values for `{}` but can have at most {max}.",
+                        focus.0,
+                        found.len(),
+                        constraint.role.0
+                    )));
+                }
+            }
+            if let Some(value_concept) = &constraint.value_concept {
+                for value in found {
+                    if !extends(value, value_concept) {
+                        result.push(violation(format!(
+                            "`{}` is a value of `{}` for `{}` but doesn't extend `{}`.",
+                            value.0, constraint.role.0, focus.0, value_concept.0
+                        )));
+                    }
+                }
+            }
+        }
+    }
+    result.sort();
+    result
+}
+
+/// Write violations as a network, with an Entry linking each failing Element to the constraint it fails.
+pub fn report(violations: &[Violation]) -> BTreeSet<Entry> {
+    violations
+        .iter()
+        .map(|violation| Entry::Role {
+            first: violation.focus.clone(),
+            second: violation.constraint.clone(),
+            role: Element::new("violates"),
+        })
+        .collect()
+}
+
+/// Join the messages of violations into a single error.
+pub fn to_error(violations: &[Violation]) -> LigatureError {
+    let messages: Vec<&str> = violations
+        .iter()
+        .map(|violation| violation.message.as_str())
+        .collect();
+    LigatureError(messages.join("\n"))
+}
+
+/// A Ligature instance that checks collections against shapes before changing them.
+/// A write that would leave a collection with violations fails and leaves the collection unchanged.
+pub struct Validated<L: Ligature> {
+    inner: L,
+    shapes: BTreeMap<Element, Vec<Constraint>>,
+}
+
+impl<L: Ligature> Validated<L> {
+    /// Wrap a Ligature instance without any shapes.
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            shapes: BTreeMap::new(),
+        }
+    }
+
+    /// Set the constraints that a collection is checked against.
+    pub fn set_shapes(&mut self, collection: Element, constraints: Vec<Constraint>) {
+        self.shapes.insert(collection, constraints);
+    }
+
+    /// Get the wrapped Ligature instance.
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+
+    fn check(&self, collection: &Element, entries: &BTreeSet<Entry>) -> Result<(), LigatureError> {
+        match self.shapes.get(collection) {
+            Some(constraints) => {
+                let violations = validate(entries, constraints);
+                if violations.is_empty() {
+                    Ok(())
+                } else {
+                    Err(to_error(&violations))
+                }
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<L: Ligature> Ligature for Validated<L> {
+    fn collections(&self) -> Result<Vec<Element>, LigatureError> {
+        self.inner.collections()
+    }
+
+    fn add_collection(&mut self, collection: Element) -> Result<(), LigatureError> {
+        self.inner.add_collection(collection)
+    }
+
+    fn remove_collection(&mut self, collection: Element) -> Result<(), LigatureError> {
+        self.inner.remove_collection(collection)
+    }
+
+    fn entries(&self, collection: &Element) -> Result<BTreeSet<Entry>, LigatureError> {
+        self.inner.entries(collection)
+    }
+
+    fn add_entries(
+        &mut self,
+        collection: Element,
+        entries: &mut BTreeSet<Entry>,
+    ) -> Result<(), LigatureError> {
+        if self.shapes.contains_key(&collection) {
+            let mut result = self.inner.entries(&collection)?;
+            result.extend(entries.iter().cloned());
+            self.check(&collection, &result)?;
+        }
+        self.inner.add_entries(collection, entries)
+    }
+
+    fn remove_entries(
+        &mut self,
+        collection: Element,
+        entries: &mut BTreeSet<Entry>,
+    ) -> Result<(), LigatureError> {
+        if self.shapes.contains_key(&collection) {
+            let mut result = self.inner.entries(&collection)?;
+            result.retain(|entry| !entries.contains(entry));
+            self.check(&collection, &result)?;
+        }
+        self.inner.remove_entries(collection, entries)
+    }
+
+    fn filter(
+        &self,
+        collection: Element,
+        pattern: Entry,
+    ) -> Result<BTreeSet<Entry>, LigatureError> {
+        self.inner.filter(collection, pattern)
+    }
+
+    fn query(
+        &self,
+        collection: Element,
+        pattern: BTreeSet<Entry>,
+    ) -> Result<hashbag::HashBag<BTreeMap<String, Element>>, LigatureError> {
+        self.inner.query(collection, pattern)
+    }
+}
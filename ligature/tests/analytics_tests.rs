@@ -0,0 +1,113 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::analytics::{
+    communities, component_entries, connected_components, degree_entries, degrees, page_rank,
+    Degree,
+};
+use ligature::projection::{project, Projection};
+use ligature::{Element, Entry, LangTag};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn graph(edges: &[(&str, &str)]) -> Projection {
+    project(
+        &edges
+            .iter()
+            .map(|(first, second)| Entry::role(*first, "link", *second))
+            .collect(),
+    )
+}
+
+fn keys(values: &[&str]) -> BTreeSet<String> {
+    values.iter().map(|value| (*value).to_owned()).collect()
+}
+
+#[test]
+fn degrees_of_nodes() {
+    let result = degrees(&graph(&[("a", "b"), ("a", "c"), ("b", "c")]));
+    assert_eq!(
+        result["a"],
+        Degree {
+            incoming: 0,
+            outgoing: 2
+        }
+    );
+    assert_eq!(
+        result["c"],
+        Degree {
+            incoming: 2,
+            outgoing: 0
+        }
+    );
+}
+
+#[test]
+fn components_ignore_direction() {
+    let projection = graph(&[("a", "b"), ("c", "b"), ("d", "e"), ("f", "f")]);
+    assert_eq!(
+        connected_components(&projection),
+        vec![keys(&["a", "b", "c"]), keys(&["d", "e"]), keys(&["f"])]
+    );
+    assert!(component_entries(&projection).contains(&Entry::role("e", "component", "1")));
+}
+
+#[test]
+fn entries_keep_lang_tags() {
+    let bonjour = Element("Bonjour".to_owned(), None, Some(LangTag("fr".to_owned())));
+    let projection = project(&BTreeSet::from([Entry::role(
+        "hello",
+        "label",
+        bonjour.clone(),
+    )]));
+    assert!(degree_entries(&projection).contains(&Entry::role(bonjour, "inDegree", "1")));
+}
+
+#[test]
+fn page_rank_of_a_cycle_is_even() {
+    let ranks = page_rank(&graph(&[("a", "b"), ("b", "c"), ("c", "a")]), 0.85);
+    for rank in ranks.values() {
+        assert!((rank - 1.0 / 3.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn page_rank_favours_linked_nodes() {
+    let ranks = page_rank(&graph(&[("b", "a"), ("c", "a"), ("d", "a")]), 0.85);
+    assert!(ranks["a"] > ranks["b"]);
+    assert_eq!(ranks["b"], ranks["c"]);
+    assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-6);
+    assert_eq!(page_rank(&Projection::default(), 0.85), BTreeMap::new());
+}
+
+#[test]
+fn communities_of_two_cliques() {
+    let result = communities(&graph(&[
+        ("a", "b"),
+        ("a", "c"),
+        ("a", "d"),
+        ("b", "c"),
+        ("b", "d"),
+        ("c", "d"),
+        ("d", "e"),
+        ("e", "f"),
+        ("e", "g"),
+        ("e", "h"),
+        ("f", "g"),
+        ("f", "h"),
+        ("g", "h"),
+    ]));
+    assert_eq!(
+        result,
+        BTreeMap::from([
+            ("a".to_owned(), 0),
+            ("b".to_owned(), 0),
+            ("c".to_owned(), 0),
+            ("d".to_owned(), 0),
+            ("e".to_owned(), 1),
+            ("f".to_owned(), 1),
+            ("g".to_owned(), 1),
+            ("h".to_owned(), 1),
+        ])
+    );
+}
@@ -0,0 +1,135 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::binary::{
+    read_collections, read_network, write_collections, write_network, Decoder, Encoder, Record,
+    MAGIC, VERSION,
+};
+use ligature::{Collections, Element, Entry, LangTag, Namespace};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn entries() -> BTreeSet<Entry> {
+    BTreeSet::from([
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("bob"),
+            role: Element::new("knows"),
+        },
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element("Alice".to_owned(), None, Some(LangTag("en".to_owned()))),
+            role: Element(
+                "name".to_owned(),
+                Some(Namespace("https://example.com/".to_owned())),
+                None,
+            ),
+        },
+        Entry::Extends {
+            element: Element::new("alice"),
+            concept: Element::new("Person"),
+        },
+        Entry::NotExtends {
+            element: Element::new("alice"),
+            concept: Element::new("Robot"),
+        },
+    ])
+}
+
+fn collections() -> Collections {
+    BTreeMap::from([
+        (Element::new("people"), entries()),
+        (Element::new("empty"), BTreeSet::new()),
+    ])
+}
+
+#[test]
+fn round_trip_network() {
+    let bytes = write_network(&entries());
+    assert_eq!(&bytes[..4], MAGIC);
+    assert_eq!(bytes[4], VERSION);
+    assert_eq!(read_network(&bytes).unwrap(), entries());
+    assert_eq!(
+        read_network(&write_network(&BTreeSet::new())).unwrap(),
+        BTreeSet::new()
+    );
+}
+
+#[test]
+fn round_trip_collections() {
+    assert_eq!(
+        read_collections(&write_collections(&collections())).unwrap(),
+        collections()
+    );
+}
+
+#[test]
+fn repeated_elements_are_written_once() {
+    let network: BTreeSet<Entry> = (0..100)
+        .map(|i| Entry::Role {
+            first: Element::new("a-long-element-name"),
+            second: Element::new(i.to_string()),
+            role: Element::new("a-long-role-name"),
+        })
+        .collect();
+    let bytes = write_network(&network);
+    let occurrences = bytes
+        .windows("a-long-role-name".len())
+        .filter(|window| *window == "a-long-role-name".as_bytes())
+        .count();
+    assert_eq!(occurrences, 1);
+    assert_eq!(read_network(&bytes).unwrap(), network);
+}
+
+#[test]
+fn streaming_records() {
+    let mut encoder = Encoder::new(vec![]).unwrap();
+    encoder.collection(&Element::new("people")).unwrap();
+    for entry in entries() {
+        encoder.entry(&entry).unwrap();
+    }
+    let bytes = encoder.finish().unwrap();
+    let records: Vec<Record> = Decoder::new(&bytes[..])
+        .unwrap()
+        .map(|record| record.unwrap())
+        .collect();
+    let mut expected = vec![Record::Collection(Element::new("people"))];
+    expected.extend(entries().into_iter().map(Record::Entry));
+    assert_eq!(records, expected);
+}
+
+#[test]
+fn detect_invalid_streams() {
+    let bytes = write_collections(&collections());
+    let mut corrupted = bytes.clone();
+    corrupted[10] ^= 0xFF;
+    assert!(read_collections(&corrupted).is_err());
+    assert!(read_collections(&bytes[..bytes.len() - 1]).is_err());
+    assert!(read_collections(b"NOPE\x01").is_err());
+    assert!(read_network(&bytes).is_err());
+    assert!(read_collections(&write_network(&entries())).is_err());
+}
+
+#[test]
+fn reject_trailing_bytes() {
+    let mut bytes = write_collections(&collections());
+    bytes.push(0);
+    assert!(read_collections(&bytes).is_err());
+    let mut bytes = write_network(&entries());
+    bytes.extend(write_network(&entries()));
+    assert!(read_network(&bytes).is_err());
+}
+
+#[test]
+fn reject_varints_larger_than_64_bits() {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    bytes.push(0x03);
+    bytes.extend([0xFF; 9]);
+    bytes.push(0x02);
+    let mut decoder = Decoder::new(&bytes[..]).unwrap();
+    assert_eq!(
+        decoder.record().unwrap_err().0,
+        "Invalid varint in binary stream."
+    );
+}
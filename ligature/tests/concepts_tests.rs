@@ -0,0 +1,83 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::concepts::{closure, contradictions, is_consistent};
+use ligature::Entry;
+use std::collections::BTreeSet;
+
+#[test]
+fn closure_follows_the_hierarchy() {
+    let entries = BTreeSet::from([
+        Entry::extends("rex", "Dog"),
+        Entry::extends("Dog", "Mammal"),
+        Entry::extends("Mammal", "Animal"),
+    ]);
+    let expected = BTreeSet::from([
+        Entry::extends("rex", "Dog"),
+        Entry::extends("rex", "Mammal"),
+        Entry::extends("rex", "Animal"),
+        Entry::extends("Dog", "Mammal"),
+        Entry::extends("Dog", "Animal"),
+        Entry::extends("Mammal", "Animal"),
+    ]);
+    assert_eq!(closure(&entries), expected);
+}
+
+#[test]
+fn closure_with_cycles() {
+    let entries = BTreeSet::from([Entry::extends("A", "B"), Entry::extends("B", "A")]);
+    let expected = BTreeSet::from([
+        Entry::extends("A", "A"),
+        Entry::extends("A", "B"),
+        Entry::extends("B", "A"),
+        Entry::extends("B", "B"),
+    ]);
+    assert_eq!(closure(&entries), expected);
+}
+
+#[test]
+fn direct_contradiction() {
+    let entries = BTreeSet::from([
+        Entry::extends("a", "B"),
+        Entry::not_extends("a", "B"),
+        Entry::extends("c", "B"),
+    ]);
+    let expected = BTreeSet::from([Entry::extends("a", "B"), Entry::not_extends("a", "B")]);
+    assert_eq!(contradictions(&entries), expected);
+    assert!(!is_consistent(&entries));
+}
+
+#[test]
+fn inherited_contradiction() {
+    let entries = BTreeSet::from([
+        Entry::extends("a", "B"),
+        Entry::extends("B", "C"),
+        Entry::extends("B", "D"),
+        Entry::not_extends("a", "C"),
+    ]);
+    let expected = BTreeSet::from([
+        Entry::extends("a", "B"),
+        Entry::extends("B", "C"),
+        Entry::not_extends("a", "C"),
+    ]);
+    assert_eq!(contradictions(&entries), expected);
+}
+
+#[test]
+fn self_contradiction() {
+    let entries = BTreeSet::from([Entry::extends("a", "a"), Entry::not_extends("a", "a")]);
+    assert_eq!(contradictions(&entries), entries);
+}
+
+#[test]
+fn consistent_entries() {
+    let entries = BTreeSet::from([
+        Entry::extends("a", "B"),
+        Entry::extends("B", "C"),
+        Entry::not_extends("a", "D"),
+        Entry::not_extends("C", "B"),
+    ]);
+    assert_eq!(contradictions(&entries), BTreeSet::new());
+    assert!(is_consistent(&entries));
+}
@@ -0,0 +1,96 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::csv::{export, import, read_table, write_table, TableMapping};
+use ligature::{Element, Entry};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[test]
+fn read_and_write_quoted_fields() {
+    let input = "id,note\r\nalice,\"Hello, \"\"world\"\"\"\nbob,\"two\nlines\"\n";
+    let rows = read_table(input, ',').unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec!["id".to_owned(), "note".to_owned()],
+            vec!["alice".to_owned(), "Hello, \"world\"".to_owned()],
+            vec!["bob".to_owned(), "two\nlines".to_owned()],
+        ]
+    );
+    assert_eq!(read_table(&write_table(&rows, ','), ',').unwrap(), rows);
+    assert!(read_table("id\n\"open", ',').is_err());
+}
+
+#[test]
+fn import_rows_and_columns() {
+    let input = "id,name,age\nalice,Alice,34\nbob,,27\n";
+    let mut mapping = TableMapping::csv("id");
+    mapping.concept = Some(Element::new("Person"));
+    let expected = BTreeSet::from([
+        Entry::role("alice", "name", "Alice"),
+        Entry::role("alice", "age", "34"),
+        Entry::role("bob", "age", "27"),
+        Entry::Extends {
+            element: Element::new("alice"),
+            concept: Element::new("Person"),
+        },
+        Entry::Extends {
+            element: Element::new("bob"),
+            concept: Element::new("Person"),
+        },
+    ]);
+    assert_eq!(import(input, &mapping).unwrap(), expected);
+}
+
+#[test]
+fn import_with_column_mapping() {
+    let input = "id\tfull name\tkind\tignored\nalice\tAlice\tPerson\tx\n";
+    let mut mapping = TableMapping::tsv("id");
+    mapping.columns = BTreeMap::from([("full name".to_owned(), Element::new("name"))]);
+    mapping.concept_column = Some("kind".to_owned());
+    let expected = BTreeSet::from([
+        Entry::role("alice", "name", "Alice"),
+        Entry::Extends {
+            element: Element::new("alice"),
+            concept: Element::new("Person"),
+        },
+    ]);
+    assert_eq!(import(input, &mapping).unwrap(), expected);
+    assert!(import(input, &TableMapping::tsv("missing")).is_err());
+}
+
+#[test]
+fn export_elements_without_roles() {
+    let entries = BTreeSet::from([
+        Entry::role("alice", "name", "Alice"),
+        Entry::extends("alice", "Person"),
+        Entry::extends("bob", "Person"),
+    ]);
+    let mut mapping = TableMapping::csv("id");
+    mapping.concept = Some(Element::new("Person"));
+    assert_eq!(export(&entries, &mapping), "id,name\nalice,Alice\nbob,\n");
+    assert_eq!(
+        import(&export(&entries, &mapping), &mapping).unwrap(),
+        entries
+    );
+}
+
+#[test]
+fn export_pivots_collection() {
+    let entries = BTreeSet::from([
+        Entry::role("alice", "name", "Alice"),
+        Entry::role("alice", "knows", "bob"),
+        Entry::role("alice", "knows", "carol"),
+        Entry::role("bob", "name", "Bob"),
+    ]);
+    let mapping = TableMapping::csv("id");
+    assert_eq!(
+        export(&entries, &mapping),
+        "id,knows,name\nalice,bob,Alice\nalice,carol,\nbob,,Bob\n"
+    );
+    assert_eq!(
+        import(&export(&entries, &mapping), &mapping).unwrap(),
+        entries
+    );
+}
@@ -0,0 +1,200 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::json::{
+    read_collections, read_jsonld_collections, read_jsonld_network, read_network,
+    write_collections, write_jsonld_collections, write_jsonld_network, write_network, VOCAB,
+};
+use ligature::rdf::LITERAL_NAMESPACE;
+use ligature::{Collections, Element, Entry, LangTag, Namespace};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn entries() -> BTreeSet<Entry> {
+    BTreeSet::from([
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("bob"),
+            role: Element::new("knows"),
+        },
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element("Alice".to_owned(), None, Some(LangTag("en".to_owned()))),
+            role: Element::new("name"),
+        },
+        Entry::Extends {
+            element: Element::new("alice"),
+            concept: Element::new("Person"),
+        },
+        Entry::NotExtends {
+            element: Element::new("alice"),
+            concept: Element::new("Robot"),
+        },
+    ])
+}
+
+fn collections() -> Collections {
+    BTreeMap::from([
+        (Element::new("people"), entries()),
+        (Element::new("empty"), BTreeSet::new()),
+    ])
+}
+
+#[test]
+fn write_network_format() {
+    let network = BTreeSet::from([
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("bob"),
+            role: Element::new("knows"),
+        },
+        Entry::Extends {
+            element: Element(
+                "alice".to_owned(),
+                Some(Namespace("https://example.com/".to_owned())),
+                None,
+            ),
+            concept: Element::new("Person"),
+        },
+    ]);
+    let result: Value = serde_json::from_str(&write_network(&network)).unwrap();
+    let expected = json!([
+        { "first": "alice", "role": "knows", "second": "bob" },
+        {
+            "element": { "value": "alice", "namespace": "https://example.com/" },
+            "extends": "Person"
+        },
+    ]);
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn round_trip_network() {
+    assert_eq!(read_network(&write_network(&entries())).unwrap(), entries());
+}
+
+#[test]
+fn round_trip_collections() {
+    assert_eq!(
+        read_collections(&write_collections(&collections())).unwrap(),
+        collections()
+    );
+}
+
+#[test]
+fn write_jsonld_network_format() {
+    let result: Value = serde_json::from_str(&write_jsonld_network(&entries())).unwrap();
+    assert_eq!(
+        result["@graph"],
+        json!([{
+            "@id": "alice",
+            "@type": ["Person"],
+            "notExtends": ["Robot"],
+            "knows": [{ "@id": "bob" }],
+            "name": [{ "@value": "Alice", "@language": "en" }],
+        }])
+    );
+    assert_eq!(result["@context"]["@vocab"], json!(VOCAB));
+    assert_eq!(result["@context"]["@base"], json!(VOCAB));
+}
+
+#[test]
+fn round_trip_jsonld() {
+    assert_eq!(
+        read_jsonld_network(&write_jsonld_network(&entries())).unwrap(),
+        entries()
+    );
+    let default = Element::new("default");
+    assert_eq!(
+        read_jsonld_collections(&write_jsonld_collections(&collections()), &default).unwrap(),
+        collections()
+    );
+}
+
+#[test]
+fn read_jsonld_with_prefixes_and_nested_nodes() {
+    let input = r#"{
+        "@context": { "ex": "http://example.com/" },
+        "@id": "ex:alice",
+        "@type": "ex:Person",
+        "ex:knows": { "@id": "ex:bob", "@type": "ex:Person" }
+    }"#;
+    let expected = BTreeSet::from([
+        Entry::Extends {
+            element: Element::new("http://example.com/alice"),
+            concept: Element::new("http://example.com/Person"),
+        },
+        Entry::Extends {
+            element: Element::new("http://example.com/bob"),
+            concept: Element::new("http://example.com/Person"),
+        },
+        Entry::Role {
+            first: Element::new("http://example.com/alice"),
+            second: Element::new("http://example.com/bob"),
+            role: Element::new("http://example.com/knows"),
+        },
+    ]);
+    assert_eq!(read_jsonld_network(input).unwrap(), expected);
+}
+
+#[test]
+fn read_jsonld_with_vocab_and_literals() {
+    let input = r#"{
+        "@context": { "@vocab": "http://example.com/", "@base": "http://example.com/" },
+        "@id": "alice",
+        "name": "Alice",
+        "age": 42
+    }"#;
+    let literal = |value: &str| {
+        Element(
+            value.to_owned(),
+            Some(Namespace(LITERAL_NAMESPACE.to_owned())),
+            None,
+        )
+    };
+    let expected = BTreeSet::from([
+        Entry::role(
+            "http://example.com/alice",
+            "http://example.com/name",
+            literal("Alice"),
+        ),
+        Entry::role(
+            "http://example.com/alice",
+            "http://example.com/age",
+            literal("42"),
+        ),
+    ]);
+    assert_eq!(read_jsonld_network(input).unwrap(), expected);
+    assert_eq!(
+        read_jsonld_network(&write_jsonld_network(&expected)).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn generated_jsonld_blank_nodes_are_distinct_from_labels() {
+    let input = r#"{
+        "@id": "_:b1",
+        "knows": { "@type": "Person" }
+    }"#;
+    let entries = read_jsonld_network(input).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(!entries.contains(&Entry::extends("_:b1", "Person")));
+}
+
+#[test]
+fn read_jsonld_with_cyclic_terms() {
+    assert!(read_jsonld_network(r#"{ "@context": { "name": "name" }, "name": "Alice" }"#).is_err());
+    assert!(read_jsonld_network(
+        r#"{ "@context": { "a": "b", "b": "a" }, "@id": "x", "@type": "a" }"#
+    )
+    .is_err());
+}
+
+#[test]
+fn read_invalid_json() {
+    assert!(read_network("{").is_err());
+    assert!(read_network(r#"[{ "first": "a", "role": "b" }]"#).is_err());
+    assert!(read_collections(r#"[{ "entries": [] }]"#).is_err());
+}
@@ -0,0 +1,110 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::paths::{follow, reachable, shortest_path, Path};
+use ligature::{Element, Entry};
+use std::collections::BTreeSet;
+
+fn elements(values: &[&str]) -> BTreeSet<Element> {
+    values.iter().map(|value| Element::new(*value)).collect()
+}
+
+fn family() -> BTreeSet<Entry> {
+    BTreeSet::from([
+        Entry::role("ann", "parentOf", "bob"),
+        Entry::role("bob", "parentOf", "cat"),
+        Entry::role("cat", "parentOf", "dan"),
+        Entry::role("bob", "marriedTo", "eve"),
+        Entry::role("eve", "livesIn", "york"),
+    ])
+}
+
+fn run(path: &str, start: &str) -> BTreeSet<Element> {
+    follow(&family(), &Path::parse(path).unwrap(), &Element::new(start))
+}
+
+#[test]
+fn parse_paths() {
+    assert_eq!(
+        Path::parse("^a+/(b | c)*").unwrap(),
+        Path::Sequence(vec![
+            Path::Inverse(Box::new(Path::OneOrMore(Box::new(Path::Role(
+                Element::new("a")
+            ))))),
+            Path::ZeroOrMore(Box::new(Path::Alternative(vec![
+                Path::Role(Element::new("b")),
+                Path::Role(Element::new("c")),
+            ]))),
+        ])
+    );
+    assert!(Path::parse("(a").is_err());
+    assert!(Path::parse("a/").is_err());
+    assert!(Path::parse("a b").is_err());
+}
+
+#[test]
+fn parse_deeply_nested_paths() {
+    assert_eq!(
+        Path::parse(&format!("{}a", "^".repeat(100001))),
+        Ok(Path::Inverse(Box::new(Path::Role(Element::new("a")))))
+    );
+    assert_eq!(
+        Path::parse(&format!("{}a", "^".repeat(100000))),
+        Ok(Path::Role(Element::new("a")))
+    );
+    let nested = format!("{}a{}", "(".repeat(100000), ")".repeat(100000));
+    assert!(Path::parse(&nested).is_err());
+}
+
+#[test]
+fn follow_roles() {
+    assert_eq!(run("parentOf", "ann"), elements(&["bob"]));
+    assert_eq!(run("parentOf/parentOf", "ann"), elements(&["cat"]));
+    assert_eq!(run("parentOf|marriedTo", "bob"), elements(&["cat", "eve"]));
+    assert_eq!(run("marriedTo/livesIn", "bob"), elements(&["york"]));
+}
+
+#[test]
+fn follow_repeated_roles() {
+    assert_eq!(run("parentOf+", "ann"), elements(&["bob", "cat", "dan"]));
+    assert_eq!(
+        run("parentOf*", "ann"),
+        elements(&["ann", "bob", "cat", "dan"])
+    );
+    assert_eq!(run("^parentOf+", "dan"), elements(&["ann", "bob", "cat"]));
+}
+
+#[test]
+fn follow_inverse_sequences() {
+    assert_eq!(run("^(marriedTo/livesIn)", "york"), elements(&["bob"]));
+    assert_eq!(run("^parentOf/marriedTo", "cat"), elements(&["eve"]));
+}
+
+#[test]
+fn reachable_elements() {
+    assert_eq!(
+        reachable(&family(), &Element::new("bob")),
+        elements(&["cat", "dan", "eve", "york"])
+    );
+}
+
+#[test]
+fn shortest_paths() {
+    assert_eq!(
+        shortest_path(&family(), &Element::new("ann"), &Element::new("york")),
+        Some(vec![
+            Entry::role("ann", "parentOf", "bob"),
+            Entry::role("bob", "marriedTo", "eve"),
+            Entry::role("eve", "livesIn", "york"),
+        ])
+    );
+    assert_eq!(
+        shortest_path(&family(), &Element::new("ann"), &Element::new("ann")),
+        Some(vec![])
+    );
+    assert_eq!(
+        shortest_path(&family(), &Element::new("york"), &Element::new("ann")),
+        None
+    );
+}
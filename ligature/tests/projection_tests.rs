@@ -0,0 +1,148 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::projection::{project, write_dot, write_gexf, write_graphml, Edge, EdgeKind, Node};
+use ligature::{Element, Entry, LangTag, Namespace};
+use std::collections::BTreeSet;
+
+fn entries() -> BTreeSet<Entry> {
+    BTreeSet::from([
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("bob"),
+            role: Element::new("knows"),
+        },
+        Entry::Extends {
+            element: Element::new("alice"),
+            concept: Element::new("Person"),
+        },
+        Entry::NotExtends {
+            element: Element::new("bob"),
+            concept: Element::new("Robot"),
+        },
+    ])
+}
+
+fn node(element: Element, concept: bool) -> Node {
+    Node {
+        key: element.0.clone(),
+        element,
+        concept,
+    }
+}
+
+#[test]
+fn project_network() {
+    let projection = project(&entries());
+    assert_eq!(
+        projection.nodes,
+        BTreeSet::from([
+            node(Element::new("Person"), true),
+            node(Element::new("Robot"), true),
+            node(Element::new("alice"), false),
+            node(Element::new("bob"), false),
+        ])
+    );
+    assert!(projection.edges.contains(&Edge {
+        key: "¬:".to_owned(),
+        source: "bob".to_owned(),
+        target: "Robot".to_owned(),
+        kind: EdgeKind::NotExtends,
+    }));
+    assert_eq!(projection.edges.len(), 3);
+}
+
+#[test]
+fn project_tagged_and_namespaced_elements() {
+    let tagged =
+        |value: &str, tag: &str| Element(value.to_owned(), None, Some(LangTag(tag.to_owned())));
+    let entries = BTreeSet::from([
+        Entry::role("hello", "label", tagged("Bonjour", "fr")),
+        Entry::role("hi", "label", tagged("Bonjour", "en")),
+        Entry::role(
+            "hi",
+            "sameAs",
+            Element(
+                "hi".to_owned(),
+                Some(Namespace("https://example.com/".to_owned())),
+                None,
+            ),
+        ),
+    ]);
+    let keys: BTreeSet<String> = project(&entries)
+        .nodes
+        .into_iter()
+        .map(|node| node.key)
+        .collect();
+    assert_eq!(
+        keys,
+        BTreeSet::from([
+            "hello".to_owned(),
+            "hi".to_owned(),
+            r#"{"value":"Bonjour","lang":"en"}"#.to_owned(),
+            r#"{"value":"Bonjour","lang":"fr"}"#.to_owned(),
+            r#"{"value":"hi","namespace":"https://example.com/"}"#.to_owned(),
+        ])
+    );
+}
+
+#[test]
+fn different_elements_get_different_keys() {
+    let namespaced = |value: &str, namespace: &str| {
+        Element(
+            value.to_owned(),
+            Some(Namespace(namespace.to_owned())),
+            None,
+        )
+    };
+    let entries = BTreeSet::from([
+        Entry::role(namespaced("b", "a"), "knows", "ab"),
+        Entry::role(
+            namespaced("b", "a"),
+            "knows",
+            r#"{"value":"b","namespace":"a"}"#,
+        ),
+    ]);
+    let projection = project(&entries);
+    assert_eq!(projection.nodes.len(), 3);
+    assert_eq!(projection.edges.len(), 2);
+}
+
+#[test]
+fn write_dot_network() {
+    let expected = r#"digraph {
+  "Person" [shape=box, style=filled, fillcolor=lightblue];
+  "Robot" [shape=box, style=filled, fillcolor=lightblue];
+  "alice";
+  "bob";
+  "alice" -> "Person" [label=":", style=dashed];
+  "alice" -> "bob" [label="knows"];
+  "bob" -> "Robot" [label="¬:", style=dotted, color=red];
+}
+"#;
+    assert_eq!(write_dot(&entries()), expected);
+}
+
+#[test]
+fn write_graphml_and_gexf_network() {
+    let graphml = write_graphml(&entries());
+    assert!(graphml.contains("<node id=\"Person\"><data key=\"concept\">true</data></node>"));
+    assert!(graphml.contains(
+        "<edge id=\"e1\" source=\"alice\" target=\"bob\"><data key=\"label\">knows</data><data key=\"kind\">role</data></edge>"
+    ));
+    let gexf = write_gexf(&entries());
+    assert!(gexf.contains("<viz:shape value=\"square\"/>"));
+    assert!(gexf.contains("label=\":\"><attvalues><attvalue for=\"kind\" value=\"extends\"/>"));
+}
+
+#[test]
+fn escape_xml_and_dot() {
+    let entries = BTreeSet::from([Entry::Role {
+        first: Element::new("a<b>"),
+        second: Element::new("say \"hi\""),
+        role: Element::new("r&d"),
+    }]);
+    assert!(write_graphml(&entries).contains("source=\"a&lt;b&gt;\" target=\"say &quot;hi&quot;\""));
+    assert!(write_dot(&entries).contains("\"a<b>\" -> \"say \\\"hi\\\"\" [label=\"r&d\"];"));
+}
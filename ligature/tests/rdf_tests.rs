@@ -0,0 +1,313 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::rdf::{
+    read_nquads, read_ntriples, read_trig, read_turtle, write_nquads, write_ntriples, write_trig,
+    write_turtle, LITERAL_NAMESPACE, NOT_EXTENDS, RDF_TYPE,
+};
+use ligature::{Collections, Element, Entry, LangTag, Namespace};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn entries() -> BTreeSet<Entry> {
+    BTreeSet::from([
+        Entry::Role {
+            first: Element::new("http://example.com/alice"),
+            second: Element::new("http://example.com/bob"),
+            role: Element::new("http://example.com/knows"),
+        },
+        Entry::Extends {
+            element: Element::new("http://example.com/alice"),
+            concept: Element::new("http://example.com/Person"),
+        },
+        Entry::NotExtends {
+            element: Element::new("http://example.com/alice"),
+            concept: Element::new("http://example.com/Robot"),
+        },
+    ])
+}
+
+fn literal(value: &str) -> Element {
+    Element(
+        value.to_owned(),
+        Some(Namespace(LITERAL_NAMESPACE.to_owned())),
+        None,
+    )
+}
+
+fn collections() -> Collections {
+    BTreeMap::from([
+        (Element::new("http://example.com/people"), entries()),
+        (
+            Element::new("http://example.com/labels"),
+            BTreeSet::from([Entry::Role {
+                first: Element::new("_:b1"),
+                second: Element(
+                    "Bonjour \"le\" monde".to_owned(),
+                    None,
+                    Some(LangTag("fr".to_owned())),
+                ),
+                role: Element::new("http://example.com/label"),
+            }]),
+        ),
+    ])
+}
+
+#[test]
+fn write_ntriples_uses_mapping() {
+    let result = write_ntriples(&entries(), None).unwrap();
+    assert!(result.contains(&format!(
+        "<http://example.com/alice> <{}> <http://example.com/Person> .\n",
+        RDF_TYPE
+    )));
+    assert!(result.contains(&format!(
+        "<http://example.com/alice> <{}> <http://example.com/Robot> .\n",
+        NOT_EXTENDS
+    )));
+    assert!(result.contains(
+        "<http://example.com/alice> <http://example.com/knows> <http://example.com/bob> .\n"
+    ));
+}
+
+#[test]
+fn round_trip_ntriples() {
+    assert_eq!(
+        read_ntriples(&write_ntriples(&entries(), None).unwrap()).unwrap(),
+        entries()
+    );
+}
+
+#[test]
+fn round_trip_nquads() {
+    let default = Element::new("default");
+    assert_eq!(
+        read_nquads(&write_nquads(&collections(), None).unwrap(), &default).unwrap(),
+        collections()
+    );
+}
+
+#[test]
+fn round_trip_turtle() {
+    assert_eq!(
+        read_turtle(&write_turtle(&entries(), None).unwrap()).unwrap(),
+        entries()
+    );
+}
+
+#[test]
+fn round_trip_trig() {
+    let default = Element::new("default");
+    assert_eq!(
+        read_trig(&write_trig(&collections(), None).unwrap(), &default).unwrap(),
+        collections()
+    );
+}
+
+#[test]
+fn write_relative_elements_with_a_base() {
+    let entries = BTreeSet::from([Entry::extends("hello world", "Greeting")]);
+    assert!(write_ntriples(&entries, None).is_err());
+    let result = write_ntriples(&entries, Some("http://example.com/")).unwrap();
+    assert_eq!(
+        result,
+        format!(
+            "<http://example.com/hello%20world> <{RDF_TYPE}> <http://example.com/Greeting> .\n"
+        )
+    );
+    let expected = BTreeSet::from([Entry::extends(
+        "http://example.com/hello%20world",
+        "http://example.com/Greeting",
+    )]);
+    assert_eq!(read_ntriples(&result).unwrap(), expected);
+    let turtle = write_turtle(&entries, Some("http://example.com/")).unwrap();
+    assert_eq!(read_turtle(&turtle).unwrap(), expected);
+}
+
+#[test]
+fn resolve_relative_elements_like_rfc_3986() {
+    let base = "http://a/b/c/d;p?q";
+    let cases = [
+        ("g", "http://a/b/c/g"),
+        ("./g", "http://a/b/c/g"),
+        ("g/", "http://a/b/c/g/"),
+        ("/g", "http://a/g"),
+        ("//g", "http://g"),
+        ("?y", "http://a/b/c/d;p?y"),
+        ("#s", "http://a/b/c/d;p?q#s"),
+        ("..", "http://a/b/"),
+        ("../g", "http://a/b/g"),
+        ("../../../g", "http://a/g"),
+        ("g;x=1/../y", "http://a/b/c/y"),
+    ];
+    for (relative, expected) in cases {
+        let entries = BTreeSet::from([Entry::extends(relative, "http://example.com/C")]);
+        let result = write_ntriples(&entries, Some(base)).unwrap();
+        assert_eq!(
+            read_ntriples(&result).unwrap(),
+            BTreeSet::from([Entry::extends(expected, "http://example.com/C")]),
+            "{relative}"
+        );
+        let turtle = format!("@base <{base}> . <{relative}> a <http://example.com/C> .");
+        assert_eq!(
+            read_turtle(&turtle).unwrap(),
+            BTreeSet::from([Entry::extends(expected, "http://example.com/C")]),
+            "{relative}"
+        );
+    }
+}
+
+#[test]
+fn literals_and_blank_nodes_only_where_rdf_allows_them() {
+    let iri = "http://example.com/a";
+    let tagged = Element("chat".to_owned(), None, Some(LangTag("fr".to_owned())));
+    let invalid = [
+        Entry::role(literal("a"), iri, iri),
+        Entry::role(tagged.clone(), iri, iri),
+        Entry::role(iri, literal("a"), iri),
+        Entry::role(iri, tagged.clone(), iri),
+        Entry::role(iri, "_:b1", iri),
+        Entry::extends(literal("a"), iri),
+        Entry::not_extends(tagged.clone(), iri),
+    ];
+    for entry in invalid {
+        let entries = BTreeSet::from([entry]);
+        assert!(write_ntriples(&entries, None).is_err(), "{entries:?}");
+        assert!(write_turtle(&entries, None).is_err(), "{entries:?}");
+    }
+    let valid = BTreeSet::from([
+        Entry::role("_:b1", iri, literal("a")),
+        Entry::role(iri, iri, tagged.clone()),
+    ]);
+    assert_eq!(
+        read_ntriples(&write_ntriples(&valid, None).unwrap()).unwrap(),
+        valid
+    );
+    for graph in [literal("g"), tagged] {
+        let collections = BTreeMap::from([(graph, entries())]);
+        assert!(write_nquads(&collections, None).is_err());
+        assert!(write_trig(&collections, None).is_err());
+    }
+    let default = Element::new("default");
+    let collections = BTreeMap::from([(Element::new("_:g"), entries())]);
+    assert_eq!(
+        read_nquads(&write_nquads(&collections, None).unwrap(), &default).unwrap(),
+        collections
+    );
+}
+
+#[test]
+fn round_trip_literals() {
+    let input = r#"
+        @prefix ex: <http://example.com/> .
+        ex:alice ex:name "Alice", "Alice"@en ;
+            ex:knows ex:bob ;
+            ex:age 42 .
+    "#;
+    let entries = read_turtle(input).unwrap();
+    assert!(entries.contains(&Entry::role(
+        "http://example.com/alice",
+        "http://example.com/name",
+        literal("Alice")
+    )));
+    let written = write_ntriples(&entries, None).unwrap();
+    assert!(written.contains("<http://example.com/name> \"Alice\" .\n"));
+    assert!(written.contains("<http://example.com/age> \"42\" .\n"));
+    assert_eq!(read_ntriples(&written).unwrap(), entries);
+}
+
+#[test]
+fn generated_blank_nodes_are_distinct_from_labels() {
+    let input = r#"
+        @prefix ex: <http://example.com/> .
+        _:b1 ex:knows [ ex:name "Bob" ] .
+    "#;
+    let entries = read_turtle(input).unwrap();
+    let subjects: BTreeSet<&Element> = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Role { first, .. } => Some(first),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(subjects.len(), 2);
+    assert_eq!(
+        read_ntriples(&write_ntriples(&entries, None).unwrap()).unwrap(),
+        entries
+    );
+}
+
+#[test]
+fn read_turtle_with_prefixes_and_lists() {
+    let input = r#"
+        @prefix ex: <http://example.com/> .
+        PREFIX lig: <https://ligature.dev/ns#>
+        # a comment
+        ex:alice a ex:Person ;
+            lig:notExtends ex:Robot ;
+            ex:knows ex:bob, ex:carol ;
+            ex:age 42 .
+    "#;
+    let expected = BTreeSet::from([
+        Entry::Extends {
+            element: Element::new("http://example.com/alice"),
+            concept: Element::new("http://example.com/Person"),
+        },
+        Entry::NotExtends {
+            element: Element::new("http://example.com/alice"),
+            concept: Element::new("http://example.com/Robot"),
+        },
+        Entry::Role {
+            first: Element::new("http://example.com/alice"),
+            second: Element::new("http://example.com/bob"),
+            role: Element::new("http://example.com/knows"),
+        },
+        Entry::Role {
+            first: Element::new("http://example.com/alice"),
+            second: Element::new("http://example.com/carol"),
+            role: Element::new("http://example.com/knows"),
+        },
+        Entry::Role {
+            first: Element::new("http://example.com/alice"),
+            second: literal("42"),
+            role: Element::new("http://example.com/age"),
+        },
+    ]);
+    assert_eq!(read_turtle(input).unwrap(), expected);
+}
+
+#[test]
+fn read_trig_default_and_named_graphs() {
+    let input = r#"
+        @prefix ex: <http://example.com/> .
+        { ex:a ex:b ex:c }
+        GRAPH ex:g { ex:d a ex:E . }
+    "#;
+    let default = Element::new("default");
+    let expected = BTreeMap::from([
+        (
+            default.clone(),
+            BTreeSet::from([Entry::Role {
+                first: Element::new("http://example.com/a"),
+                second: Element::new("http://example.com/c"),
+                role: Element::new("http://example.com/b"),
+            }]),
+        ),
+        (
+            Element::new("http://example.com/g"),
+            BTreeSet::from([Entry::Extends {
+                element: Element::new("http://example.com/d"),
+                concept: Element::new("http://example.com/E"),
+            }]),
+        ),
+    ]);
+    assert_eq!(read_trig(input, &default).unwrap(), expected);
+}
+
+#[test]
+fn read_invalid_input() {
+    assert!(read_ntriples("<a> <b> .").is_err());
+    assert!(read_turtle("ex:a ex:b ex:c .").is_err());
+    assert!(read_turtle("@prefix ex: <http://example.com/> . ex:a ex:b .").is_err());
+    assert!(read_turtle("@prefix ex: <http://example.com/> . ex:a ex:b + .").is_err());
+    assert!(read_nquads("\"a\" <b> <c> .", &Element::new("default")).is_err());
+}
@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::shapes::{read_shapes, report, validate};
+use ligature::Entry;
+use std::collections::BTreeSet;
+
+fn shapes() -> BTreeSet<Entry> {
+    BTreeSet::from([
+        Entry::role("personName", "concept", "Person"),
+        Entry::role("personName", "role", "name"),
+        Entry::role("personName", "minCount", "1"),
+        Entry::role("personName", "maxCount", "1"),
+        Entry::role("personWorksAt", "concept", "Person"),
+        Entry::role("personWorksAt", "role", "worksAt"),
+        Entry::role("personWorksAt", "valueConcept", "Company"),
+    ])
+}
+
+#[test]
+fn valid_network() {
+    let entries = BTreeSet::from([
+        Entry::extends("alice", "Person"),
+        Entry::role("alice", "name", "Alice"),
+        Entry::role("alice", "worksAt", "acme"),
+        Entry::extends("acme", "Company"),
+    ]);
+    assert_eq!(validate(&entries, &read_shapes(&shapes()).unwrap()), vec![]);
+}
+
+#[test]
+fn counts_are_checked() {
+    let entries = BTreeSet::from([
+        Entry::extends("alice", "Person"),
+        Entry::extends("bob", "Person"),
+        Entry::role("bob", "name", "Bob"),
+        Entry::role("bob", "name", "Robert"),
+    ]);
+    let violations = validate(&entries, &read_shapes(&shapes()).unwrap());
+    assert_eq!(
+        report(&violations),
+        BTreeSet::from([
+            Entry::role("alice", "violates", "personName"),
+            Entry::role("bob", "violates", "personName"),
+        ])
+    );
+    assert_eq!(
+        violations[0].message,
+        "`alice` has 0 values for `name` but needs at least 1."
+    );
+}
+
+#[test]
+fn value_concepts_follow_the_hierarchy() {
+    let entries = BTreeSet::from([
+        Entry::extends("Employee", "Person"),
+        Entry::extends("alice", "Employee"),
+        Entry::role("alice", "name", "Alice"),
+        Entry::role("alice", "worksAt", "acme"),
+        Entry::role("alice", "worksAt", "bob"),
+        Entry::extends("acme", "Startup"),
+        Entry::extends("Startup", "Company"),
+    ]);
+    let violations = validate(&entries, &read_shapes(&shapes()).unwrap());
+    assert_eq!(violations.len(), 1);
+    assert_eq!(
+        violations[0].message,
+        "`bob` is a value of `worksAt` for `alice` but doesn't extend `Company`."
+    );
+}
+
+#[test]
+fn malformed_shapes() {
+    assert!(read_shapes(&BTreeSet::from([Entry::role("personName", "role", "name")])).is_err());
+    assert!(read_shapes(&BTreeSet::from([
+        Entry::role("personName", "concept", "Person"),
+        Entry::role("personName", "role", "name"),
+        Entry::role("personName", "minCount", "one"),
+    ]))
+    .is_err());
+}
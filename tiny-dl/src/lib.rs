@@ -1,3 +1,506 @@
 // This Source Code Form is subject to the terms of the Mozilla Public
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! tiny-dl is a small description logic over Ligature networks.
+//!
+//! Concepts are described in a TBox network:
+//!
+//! - `A : B` means every `A` is a `B`.
+//! - `A ¬: B` means nothing is both an `A` and a `B`.
+//! - `A r B` means every `A` has an `r` that is a `B`.
+//! - `A : Defined` makes the conditions on `A` sufficient as well as necessary,
+//!   so anything meeting all of them is an `A`.
+//!
+//! Individuals are described in an ABox network with `a : A`, `a ¬: A`, and `a r b` Entries.
+
+#![deny(missing_docs)]
+
+use ligature::{Element, Entry, Ligature};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use wander::{run_quote, Command, Environment, WanderError, WanderType, WanderValue};
+
+/// The Concept that marks a Concept as defined in a TBox.
+pub const DEFINED: &str = "Defined";
+
+/// The Concepts described by a TBox network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TBox {
+    supers: BTreeMap<Element, BTreeSet<Element>>,
+    existentials: BTreeMap<Element, BTreeSet<(Element, Element)>>,
+    disjoint: BTreeMap<Element, BTreeSet<Element>>,
+    defined: BTreeSet<Element>,
+}
+
+impl TBox {
+    /// Read a TBox from a network.
+    pub fn new(entries: &BTreeSet<Entry>) -> Self {
+        let mut tbox = TBox::default();
+        for entry in entries {
+            match entry {
+                Entry::Extends { element, concept } if concept.0 == DEFINED => {
+                    tbox.defined.insert(element.clone());
+                }
+                Entry::Extends { element, concept } => {
+                    tbox.supers
+                        .entry(element.clone())
+                        .or_default()
+                        .insert(concept.clone());
+                }
+                Entry::NotExtends { element, concept } => {
+                    tbox.disjoint
+                        .entry(element.clone())
+                        .or_default()
+                        .insert(concept.clone());
+                    tbox.disjoint
+                        .entry(concept.clone())
+                        .or_default()
+                        .insert(element.clone());
+                }
+                Entry::Role {
+                    first,
+                    second,
+                    role,
+                } => {
+                    tbox.existentials
+                        .entry(first.clone())
+                        .or_default()
+                        .insert((role.clone(), second.clone()));
+                }
+            }
+        }
+        tbox
+    }
+
+    /// Every Concept named in the TBox.
+    pub fn concepts(&self) -> BTreeSet<Element> {
+        let mut result: BTreeSet<Element> = self.defined.iter().cloned().collect();
+        for (concept, supers) in &self.supers {
+            result.insert(concept.clone());
+            result.extend(supers.iter().cloned());
+        }
+        for (concept, existentials) in &self.existentials {
+            result.insert(concept.clone());
+            result.extend(existentials.iter().map(|(_, filler)| filler.clone()));
+        }
+        result.extend(self.disjoint.keys().cloned());
+        result
+    }
+
+    /// Check if something with the given Concepts and existentials meets the conditions of a defined Concept.
+    fn meets(
+        &self,
+        definition: &Element,
+        concepts: &BTreeSet<Element>,
+        existentials: &BTreeSet<(Element, Element)>,
+        subsumers: &BTreeMap<Element, BTreeSet<Element>>,
+    ) -> bool {
+        self.supers
+            .get(definition)
+            .into_iter()
+            .flatten()
+            .all(|concept| concepts.contains(concept))
+            && self
+                .existentials
+                .get(definition)
+                .into_iter()
+                .flatten()
+                .all(|(role, filler)| {
+                    existentials.iter().any(|(r, f)| {
+                        r == role
+                            && (f == filler || subsumers.get(f).is_some_and(|s| s.contains(filler)))
+                    })
+                })
+    }
+
+    /// Add the Concepts implied by a set of Concepts, and the existentials they bring, until nothing changes.
+    fn complete(
+        &self,
+        concepts: &mut BTreeSet<Element>,
+        existentials: &mut BTreeSet<(Element, Element)>,
+        subsumers: &BTreeMap<Element, BTreeSet<Element>>,
+    ) -> bool {
+        let mut changed = false;
+        loop {
+            let mut next = concepts.clone();
+            for concept in concepts.iter() {
+                next.extend(self.supers.get(concept).into_iter().flatten().cloned());
+                next.extend(subsumers.get(concept).into_iter().flatten().cloned());
+                existentials.extend(
+                    self.existentials
+                        .get(concept)
+                        .into_iter()
+                        .flatten()
+                        .cloned(),
+                );
+            }
+            for definition in &self.defined {
+                if !next.contains(definition)
+                    && self.meets(definition, &next, existentials, subsumers)
+                {
+                    next.insert(definition.clone());
+                }
+            }
+            if next.len() == concepts.len() {
+                return changed;
+            }
+            *concepts = next;
+            changed = true;
+        }
+    }
+
+    /// Find every Concept that subsumes each Concept in the TBox, including itself.
+    pub fn classify(&self) -> BTreeMap<Element, BTreeSet<Element>> {
+        let concepts = self.concepts();
+        let mut subsumers: BTreeMap<Element, BTreeSet<Element>> = concepts
+            .iter()
+            .map(|concept| (concept.clone(), BTreeSet::from([concept.clone()])))
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for concept in &concepts {
+                let mut current = subsumers[concept].clone();
+                let mut existentials = BTreeSet::new();
+                if self.complete(&mut current, &mut existentials, &subsumers) {
+                    subsumers.insert(concept.clone(), current);
+                    changed = true;
+                }
+            }
+        }
+        subsumers
+    }
+
+    /// Check if every `sub` is a `sup`.
+    pub fn subsumes(&self, sup: &Element, sub: &Element) -> bool {
+        sup == sub
+            || self
+                .classify()
+                .get(sub)
+                .is_some_and(|subsumers| subsumers.contains(sup))
+    }
+
+    /// Check if a set of Concepts contains two disjoint Concepts.
+    fn clash<'a>(&self, concepts: &'a BTreeSet<Element>) -> Option<(&'a Element, &'a Element)> {
+        concepts.iter().find_map(|concept| {
+            self.disjoint
+                .get(concept)
+                .into_iter()
+                .flatten()
+                .find_map(|other| concepts.get(other))
+                .map(|other| (concept, other))
+        })
+    }
+
+    /// Find the Concepts that nothing can be an instance of.
+    pub fn unsatisfiable(&self) -> BTreeSet<Element> {
+        self.classify()
+            .into_iter()
+            .filter(|(_, subsumers)| self.clash(subsumers).is_some())
+            .map(|(concept, _)| concept)
+            .collect()
+    }
+
+    /// Find every Concept each individual in an ABox is an instance of.
+    pub fn realize(&self, abox: &BTreeSet<Entry>) -> BTreeMap<Element, BTreeSet<Element>> {
+        let subsumers = self.classify();
+        let mut types: BTreeMap<Element, BTreeSet<Element>> = BTreeMap::new();
+        let mut roles: BTreeMap<Element, BTreeSet<(Element, Element)>> = BTreeMap::new();
+        for entry in abox {
+            match entry {
+                Entry::Extends { element, concept } => {
+                    types
+                        .entry(element.clone())
+                        .or_default()
+                        .insert(concept.clone());
+                }
+                Entry::NotExtends { element, .. } => {
+                    types.entry(element.clone()).or_default();
+                }
+                Entry::Role {
+                    first,
+                    second,
+                    role,
+                } => {
+                    roles
+                        .entry(first.clone())
+                        .or_default()
+                        .insert((role.clone(), second.clone()));
+                    types.entry(first.clone()).or_default();
+                    types.entry(second.clone()).or_default();
+                }
+            }
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let individuals: Vec<Element> = types.keys().cloned().collect();
+            for individual in individuals {
+                let mut concepts = types[&individual].clone();
+                // A role assertion `a r b` gives `a` the existential `r B` for every Concept `B` of `b`.
+                let mut existentials: BTreeSet<(Element, Element)> = roles
+                    .get(&individual)
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|(role, other)| {
+                        types[other]
+                            .iter()
+                            .map(move |concept| (role.clone(), concept.clone()))
+                    })
+                    .collect();
+                if self.complete(&mut concepts, &mut existentials, &subsumers) {
+                    types.insert(individual, concepts);
+                    changed = true;
+                }
+            }
+        }
+        types
+    }
+
+    /// Check if an individual in an ABox is an instance of a Concept.
+    pub fn is_instance(
+        &self,
+        abox: &BTreeSet<Entry>,
+        individual: &Element,
+        concept: &Element,
+    ) -> bool {
+        self.realize(abox)
+            .get(individual)
+            .is_some_and(|concepts| concepts.contains(concept))
+    }
+
+    /// Find the Entries of an ABox that contradict the TBox.
+    /// An individual contradicts it when it is an instance of two disjoint Concepts
+    /// or of a Concept it is said not to extend.
+    pub fn inconsistencies(&self, abox: &BTreeSet<Entry>) -> BTreeSet<Entry> {
+        let types = self.realize(abox);
+        let mut result = BTreeSet::new();
+        for (individual, concepts) in &types {
+            if let Some((left, right)) = self.clash(concepts) {
+                for concept in [left, right] {
+                    result.insert(Entry::Extends {
+                        element: individual.clone(),
+                        concept: concept.clone(),
+                    });
+                }
+            }
+        }
+        for entry in abox {
+            if let Entry::NotExtends { element, concept } = entry {
+                if types
+                    .get(element)
+                    .is_some_and(|concepts| concepts.contains(concept))
+                {
+                    result.insert(entry.clone());
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Read a network from a network, a quote, or the name of a collection.
+fn network(
+    value: &WanderValue,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<BTreeSet<Entry>, WanderError> {
+    match value {
+        WanderValue::Network(network) => Ok(network.clone()),
+        WanderValue::Element(collection) => {
+            state.entries(collection).map_err(|err| WanderError(err.0))
+        }
+        WanderValue::Quote(quote) => match run_quote(quote, environment, state)? {
+            WanderValue::Network(network) => Ok(network),
+            value => Err(WanderError(format!(
+                "Expected the quote to result in a network, but got {value}."
+            ))),
+        },
+        value => Err(WanderError(format!(
+            "Expected a network, quote, or collection, but got {value}."
+        ))),
+    }
+}
+
+fn boolean(value: bool) -> WanderValue {
+    WanderValue::Element(Element::new(value.to_string()))
+}
+
+fn extends_entries(types: BTreeMap<Element, BTreeSet<Element>>) -> WanderValue {
+    let mut result = BTreeSet::new();
+    for (element, concepts) in types {
+        for concept in concepts {
+            if concept != element {
+                result.insert(Entry::Extends {
+                    element: element.clone(),
+                    concept,
+                });
+            }
+        }
+    }
+    WanderValue::Network(result)
+}
+
+fn classify_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [tbox] => {
+            let tbox = TBox::new(&network(tbox, state, environment)?);
+            Ok(extends_entries(tbox.classify()))
+        }
+        _ => Err(WanderError("`classify` requires a TBox.".to_owned())),
+    }
+}
+
+fn subsumes_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [tbox, WanderValue::Element(sup), WanderValue::Element(sub)] => {
+            let tbox = TBox::new(&network(tbox, state, environment)?);
+            Ok(boolean(tbox.subsumes(sup, sub)))
+        }
+        _ => Err(WanderError(
+            "`subsumes` requires a TBox and two Concepts.".to_owned(),
+        )),
+    }
+}
+
+fn unsatisfiable_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [tbox] => {
+            let tbox = TBox::new(&network(tbox, state, environment)?);
+            Ok(WanderValue::Network(
+                tbox.unsatisfiable()
+                    .into_iter()
+                    .map(|concept| Entry::Extends {
+                        element: concept,
+                        concept: Element::new("Nothing"),
+                    })
+                    .collect(),
+            ))
+        }
+        _ => Err(WanderError("`unsatisfiable` requires a TBox.".to_owned())),
+    }
+}
+
+fn realize_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [tbox, abox] => {
+            let tbox = TBox::new(&network(tbox, state, environment)?);
+            let abox = network(abox, state, environment)?;
+            Ok(extends_entries(tbox.realize(&abox)))
+        }
+        _ => Err(WanderError(
+            "`realize` requires a TBox and an ABox.".to_owned(),
+        )),
+    }
+}
+
+fn is_instance_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [tbox, abox, WanderValue::Element(individual), WanderValue::Element(concept)] => {
+            let tbox = TBox::new(&network(tbox, state, environment)?);
+            let abox = network(abox, state, environment)?;
+            Ok(boolean(tbox.is_instance(&abox, individual, concept)))
+        }
+        _ => Err(WanderError(
+            "`is-instance` requires a TBox, an ABox, an individual, and a Concept.".to_owned(),
+        )),
+    }
+}
+
+fn inconsistencies_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [tbox, abox] => {
+            let tbox = TBox::new(&network(tbox, state, environment)?);
+            let abox = network(abox, state, environment)?;
+            Ok(WanderValue::Network(tbox.inconsistencies(&abox)))
+        }
+        _ => Err(WanderError(
+            "`inconsistencies` requires a TBox and an ABox.".to_owned(),
+        )),
+    }
+}
+
+/// Commands for reasoning with tiny-dl from Wander.
+/// TBoxes and ABoxes can be passed as networks, quotes, or collection names.
+pub fn commands() -> HashMap<String, Command> {
+    let mut commands: HashMap<String, Command> = HashMap::new();
+    commands.insert(
+        "classify".to_owned(),
+        Command {
+            doc: "Find every Concept that subsumes each Concept in a TBox, as Extends Entries."
+                .to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(classify_command),
+        },
+    );
+    commands.insert(
+        "subsumes".to_owned(),
+        Command {
+            doc: "Takes a TBox and two Concepts and checks if every instance of the second Concept is an instance of the first. Returns the Element `true` or `false`.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Element, WanderType::Element]),
+            fun: Box::new(subsumes_command),
+        },
+    );
+    commands.insert(
+        "unsatisfiable".to_owned(),
+        Command {
+            doc: "Find the Concepts in a TBox that nothing can be an instance of, as Entries extending `Nothing`.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(unsatisfiable_command),
+        },
+    );
+    commands.insert(
+        "realize".to_owned(),
+        Command {
+            doc: "Takes a TBox and an ABox and finds every Concept each individual is an instance of, as Extends Entries.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(realize_command),
+        },
+    );
+    commands.insert(
+        "is-instance".to_owned(),
+        Command {
+            doc: "Takes a TBox, an ABox, an individual, and a Concept, and checks if the individual is an instance of the Concept. Returns the Element `true` or `false`.".to_owned(),
+            parameters: Some(vec![
+                WanderType::Any,
+                WanderType::Any,
+                WanderType::Element,
+                WanderType::Element,
+            ]),
+            fun: Box::new(is_instance_command),
+        },
+    );
+    commands.insert(
+        "inconsistencies".to_owned(),
+        Command {
+            doc: "Takes a TBox and an ABox and returns the Entries about individuals that contradict the TBox.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(inconsistencies_command),
+        },
+    );
+    commands
+}
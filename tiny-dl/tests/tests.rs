@@ -0,0 +1,148 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use tiny_dl::TBox;
+use wander::prelude::common;
+use wander::{run, WanderValue};
+
+/// Parents are People with a child who is a Person, and Mothers are Parents who are Women.
+fn family() -> TBox {
+    TBox::new(&BTreeSet::from([
+        Entry::extends("Woman", "Person"),
+        Entry::extends("Man", "Person"),
+        Entry::not_extends("Woman", "Man"),
+        Entry::extends("Parent", "Person"),
+        Entry::role("Parent", "hasChild", "Person"),
+        Entry::extends("Parent", "Defined"),
+        Entry::extends("Mother", "Parent"),
+        Entry::extends("Mother", "Woman"),
+        Entry::extends("Mother", "Defined"),
+        Entry::extends("Grandmother", "Mother"),
+        Entry::role("Grandmother", "hasChild", "Parent"),
+    ]))
+}
+
+#[test]
+fn told_subsumption() {
+    let tbox = family();
+    assert!(tbox.subsumes(&Element::new("Person"), &Element::new("Woman")));
+    assert!(tbox.subsumes(&Element::new("Person"), &Element::new("Grandmother")));
+    assert!(!tbox.subsumes(&Element::new("Woman"), &Element::new("Person")));
+}
+
+#[test]
+fn defined_subsumption() {
+    let tbox = TBox::new(&BTreeSet::from([
+        Entry::extends("Parent", "Person"),
+        Entry::role("Parent", "hasChild", "Person"),
+        Entry::extends("Parent", "Defined"),
+        Entry::extends("Woman", "Person"),
+        Entry::extends("Mom", "Woman"),
+        Entry::role("Mom", "hasChild", "Woman"),
+    ]));
+    assert!(tbox.subsumes(&Element::new("Parent"), &Element::new("Mom")));
+    assert!(!tbox.subsumes(&Element::new("Parent"), &Element::new("Woman")));
+}
+
+#[test]
+fn unsatisfiable_concepts() {
+    let mut entries = BTreeSet::from([
+        Entry::extends("Woman", "Person"),
+        Entry::extends("Man", "Person"),
+        Entry::not_extends("Woman", "Man"),
+    ]);
+    assert_eq!(TBox::new(&entries).unsatisfiable(), BTreeSet::new());
+    entries.insert(Entry::extends("Centaur", "Man"));
+    entries.insert(Entry::extends("Centaur", "Woman"));
+    assert_eq!(
+        TBox::new(&entries).unsatisfiable(),
+        BTreeSet::from([Element::new("Centaur")])
+    );
+}
+
+#[test]
+fn instance_checking() {
+    let abox = BTreeSet::from([
+        Entry::extends("ann", "Woman"),
+        Entry::extends("bob", "Man"),
+        Entry::extends("cal", "Person"),
+        Entry::role("ann", "hasChild", "bob"),
+        Entry::role("bob", "hasChild", "cal"),
+    ]);
+    let tbox = family();
+    assert!(tbox.is_instance(&abox, &Element::new("ann"), &Element::new("Mother")));
+    assert!(tbox.is_instance(&abox, &Element::new("ann"), &Element::new("Person")));
+    assert!(tbox.is_instance(&abox, &Element::new("bob"), &Element::new("Parent")));
+    assert!(!tbox.is_instance(&abox, &Element::new("bob"), &Element::new("Mother")));
+    assert!(!tbox.is_instance(&abox, &Element::new("cal"), &Element::new("Parent")));
+}
+
+#[test]
+fn inconsistent_individuals() {
+    let abox = BTreeSet::from([
+        Entry::extends("ann", "Mother"),
+        Entry::extends("ann", "Man"),
+        Entry::extends("bob", "Man"),
+        Entry::not_extends("bob", "Person"),
+        Entry::extends("cal", "Woman"),
+    ]);
+    let expected = BTreeSet::from([
+        Entry::extends("ann", "Man"),
+        Entry::extends("ann", "Woman"),
+        Entry::not_extends("bob", "Person"),
+    ]);
+    assert_eq!(family().inconsistencies(&abox), expected);
+}
+
+fn run_script(script: &str) -> Result<WanderValue, wander::WanderError> {
+    let mut commands = common();
+    commands.extend(tiny_dl::commands());
+    run(script, &commands, &mut LigatureGraph::new())
+}
+
+const TBOX: &str =
+    "store tbox {Woman : Person, Parent : Person, Parent hasChild Person, Parent : Defined}";
+
+#[test]
+fn wander_commands() {
+    assert_eq!(
+        run_script(&format!("{TBOX}, classify tbox")),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::extends("Parent", "Person"),
+            Entry::extends("Woman", "Person"),
+        ])))
+    );
+    assert_eq!(
+        run_script(&format!("{TBOX}, subsumes tbox Person Woman")),
+        Ok(WanderValue::Element(Element::new("true")))
+    );
+    assert_eq!(
+        run_script(&format!(
+            "{TBOX}, realize tbox {{ann : Woman, ann hasChild bob, bob : Person}}"
+        )),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::extends("ann", "Woman"),
+            Entry::extends("ann", "Person"),
+            Entry::extends("ann", "Parent"),
+            Entry::extends("bob", "Person"),
+        ])))
+    );
+    assert_eq!(
+        run_script(&format!(
+            "{TBOX}, is-instance tbox {{ann hasChild bob, bob : Woman}} ann Parent"
+        )),
+        Ok(WanderValue::Element(Element::new("false")))
+    );
+    assert_eq!(
+        run_script(&format!(
+            "{TBOX}, inconsistencies tbox {{ann : Woman, ann ¬: Person}}"
+        )),
+        Ok(WanderValue::Network(BTreeSet::from([Entry::not_extends(
+            "ann", "Person"
+        )])))
+    );
+}
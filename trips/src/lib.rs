@@ -75,10 +75,219 @@ pub trait Trips {
     /// Lookup a given pattern against the given Dataset.
     fn filter(&self, collection: String, pattern: Query) -> Result<BTreeSet<Trip>, TripsError>;
 
-    // /// Run a query against the given Dataset.
-    // fn query(
-    //     &self,
-    //     collection: String,
-    //     pattern: BTreeSet<Query>,
-    // ) -> Result<HashBag<BTreeMap<String, String>>, TripsError>;
+    /// Run a query against the given Dataset.
+    /// Patterns are matched in order with `filter`, after replacing the Variables bound by earlier patterns,
+    /// so each result binds a Variable to the same value in every pattern.
+    fn query(
+        &self,
+        collection: String,
+        pattern: BTreeSet<Query>,
+    ) -> Result<HashBag<BTreeMap<String, String>>, TripsError> {
+        let mut results = vec![BTreeMap::new()];
+        for query in &pattern {
+            let mut next = vec![];
+            for bindings in &results {
+                let filter = Query(
+                    bind_slot(&query.0, bindings),
+                    bind_slot(&query.1, bindings),
+                    bind_slot(&query.2, bindings),
+                );
+                for trip in self.filter(collection.clone(), filter)? {
+                    if let Some(bindings) = extend_bindings(query, &trip, bindings) {
+                        next.push(bindings);
+                    }
+                }
+            }
+            results = next;
+        }
+        Ok(results.into_iter().collect())
+    }
+
+    /// Run a query, group its results by the given Variables, and compute aggregates for each group.
+    /// See [aggregate].
+    fn aggregate_query(
+        &self,
+        collection: String,
+        pattern: BTreeSet<Query>,
+        group_by: &BTreeSet<String>,
+        aggregates: &BTreeMap<String, Aggregate>,
+    ) -> Result<Vec<BTreeMap<String, String>>, TripsError> {
+        aggregate(&self.query(collection, pattern)?, group_by, aggregates)
+    }
+}
+
+/// A value computed from a group of query results.
+/// Each one is given the name of the Variable it reads.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum Aggregate {
+    /// The number of results binding the Variable, counting repeated results.
+    Count(String),
+    /// The smallest value, compared as numbers if every value is a number.
+    Min(String),
+    /// The largest value, compared as numbers if every value is a number.
+    Max(String),
+    /// The sum of the values, which must all be numbers.
+    Sum(String),
+}
+
+/// A value read as a number.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+/// Read every value as an integer, or failing that as a float.
+fn numbers(values: &[&String]) -> Option<Vec<Number>> {
+    values
+        .iter()
+        .map(|value| value.parse().map(Number::Integer).ok())
+        .collect::<Option<Vec<Number>>>()
+        .or_else(|| {
+            values
+                .iter()
+                .map(|value| value.parse().map(Number::Float).ok())
+                .collect()
+        })
+}
+
+fn extreme<'a>(values: &[&'a String], largest: bool) -> Option<&'a String> {
+    let numbers = numbers(values);
+    let order = |a: &usize, b: &usize| match &numbers {
+        Some(numbers) => numbers[*a]
+            .partial_cmp(&numbers[*b])
+            .unwrap_or(std::cmp::Ordering::Equal),
+        None => values[*a].cmp(values[*b]),
+    };
+    let indexes = 0..values.len();
+    let index = if largest {
+        indexes.max_by(order)
+    } else {
+        indexes.min_by(order)
+    };
+    index.map(|index| values[index])
+}
+
+fn sum(variable: &str, values: &[&String]) -> Result<String, TripsError> {
+    match numbers(values) {
+        Some(numbers) => {
+            let mut integer: Option<i64> = Some(0);
+            let mut float = 0.0;
+            for number in numbers {
+                match number {
+                    Number::Integer(value) => {
+                        integer = integer.and_then(|total| total.checked_add(value));
+                        float += value as f64;
+                    }
+                    Number::Float(value) => {
+                        integer = None;
+                        float += value;
+                    }
+                }
+            }
+            Ok(match integer {
+                Some(total) => total.to_string(),
+                None => float.to_string(),
+            })
+        }
+        None => Err(TripsError(format!(
+            "Can't sum `{variable}` because not all of its values are numbers."
+        ))),
+    }
+}
+
+/// Group query results by the given Variables and compute aggregates for each group.
+/// Each row binds the grouping Variables and the name of each aggregate, and rows are ordered by group.
+/// A `Min` or `Max` over a group without values is left out of its row.
+/// Without grouping Variables there is always exactly one row, even if there are no results.
+pub fn aggregate(
+    results: &HashBag<BTreeMap<String, String>>,
+    group_by: &BTreeSet<String>,
+    aggregates: &BTreeMap<String, Aggregate>,
+) -> Result<Vec<BTreeMap<String, String>>, TripsError> {
+    let mut groups: BTreeMap<BTreeMap<String, String>, Vec<&BTreeMap<String, String>>> =
+        BTreeMap::new();
+    if group_by.is_empty() {
+        groups.insert(BTreeMap::new(), vec![]);
+    }
+    for (bindings, count) in results.set_iter() {
+        let mut key = BTreeMap::new();
+        for variable in group_by {
+            match bindings.get(variable) {
+                Some(value) => {
+                    key.insert(variable.clone(), value.clone());
+                }
+                None => {
+                    return Err(TripsError(format!(
+                        "Can't group by `{variable}` because it isn't bound."
+                    )))
+                }
+            }
+        }
+        groups
+            .entry(key)
+            .or_default()
+            .extend(std::iter::repeat_n(bindings, count));
+    }
+    let mut rows = vec![];
+    for (mut row, members) in groups {
+        for (name, aggregate) in aggregates {
+            let variable = match aggregate {
+                Aggregate::Count(variable)
+                | Aggregate::Min(variable)
+                | Aggregate::Max(variable)
+                | Aggregate::Sum(variable) => variable,
+            };
+            let values: Vec<&String> = members
+                .iter()
+                .filter_map(|bindings| bindings.get(variable))
+                .collect();
+            let value = match aggregate {
+                Aggregate::Count(_) => Some(values.len().to_string()),
+                Aggregate::Min(_) => extreme(&values, false).cloned(),
+                Aggregate::Max(_) => extreme(&values, true).cloned(),
+                Aggregate::Sum(_) => Some(sum(variable, &values)?),
+            };
+            if let Some(value) = value {
+                row.insert(name.clone(), value);
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Replace a bound Variable with its value and an unbound Variable with `Any`.
+fn bind_slot(slot: &Slot, bindings: &BTreeMap<String, String>) -> Slot {
+    match slot {
+        Slot::Variable(name) => match bindings.get(name) {
+            Some(value) => Slot::Value(value.clone()),
+            None => Slot::Any,
+        },
+        slot => slot.clone(),
+    }
+}
+
+/// Add the values a Trip gives to a Query's Variables to a set of bindings,
+/// or return None if the Trip doesn't match.
+fn extend_bindings(
+    query: &Query,
+    trip: &Trip,
+    bindings: &BTreeMap<String, String>,
+) -> Option<BTreeMap<String, String>> {
+    let mut result = bindings.clone();
+    for (slot, value) in [(&query.0, &trip.0), (&query.1, &trip.1), (&query.2, &trip.2)] {
+        match slot {
+            Slot::Variable(name) => match result.get(name) {
+                Some(bound) if bound != value => return None,
+                Some(_) => (),
+                None => {
+                    result.insert(name.clone(), value.clone());
+                }
+            },
+            Slot::Value(expected) if expected != value => return None,
+            _ => (),
+        }
+    }
+    Some(result)
 }
@@ -7,7 +7,6 @@
 #![deny(missing_docs)]
 
 use crate::{Query, Slot, Trip, Trips, TripsError};
-use hashbag::HashBag;
 use std::collections::{BTreeMap, BTreeSet};
 
 /// An in-memory implementation of Trips.
@@ -95,43 +94,6 @@ impl Trips for TripsMem {
         }
     }
 
-    // fn query(
-    //     &self,
-    //     collection: String,
-    //     pattern: BTreeSet<crate::Query>,
-    // ) -> Result<HashBag<BTreeMap<String, String>>, TripsError> {
-    //     match self.collections.get(&collection) {
-    //         Some(collection) => {
-    //             let mut results: HashBag<BTreeMap<String, String>> = HashBag::new();
-    //             let terms: Vec<&Query> = pattern.iter().collect();
-    //             let mut index = 0;
-    //             while index < terms.len() {
-    //                 match terms.get(index) {
-    //                     Some(query) => {
-    //                         let matches = match_query(query, collection.clone());
-    //                         if matches.is_empty() {
-    //                             return Ok(HashBag::new());
-    //                         } else {
-    //                             if index == 0 {
-    //                                 matches.iter().for_each(|m| {
-    //                                     results.insert(m.clone());
-    //                                     ()
-    //                                 });
-    //                             } else {
-    //                                 () //TODO  double check this
-    //                             }
-    //                         }
-    //                     }
-    //                     None => panic!("Should never reach."),
-    //                 }
-    //                 index = index + 1;
-    //             }
-    //             Ok(results)
-    //         } //Ok(res.clone()),
-    //         None => Err(TripsError("Collection not found.".to_owned())),
-    //     }
-    // }
-
     fn filter(&self, collection: String, pattern: Query) -> Result<BTreeSet<Trip>, TripsError> {
         match self.collections.get(&collection) {
             Some(collection) => {
@@ -146,7 +108,7 @@ impl Trips for TripsMem {
                                 continue;
                             }
                         }
-                        Slot::Variable(name) => todo!(),
+                        Slot::Variable(_) => (),
                     }
                     match pattern.1 {
                         Slot::Any => (),
@@ -157,7 +119,7 @@ impl Trips for TripsMem {
                                 continue;
                             }
                         }
-                        Slot::Variable(name) => todo!(),
+                        Slot::Variable(_) => (),
                     }
                     match pattern.2 {
                         Slot::Any => (),
@@ -168,7 +130,7 @@ impl Trips for TripsMem {
                                 continue;
                             }
                         }
-                        Slot::Variable(name) => todo!(),
+                        Slot::Variable(_) => (),
                     }
                     results.insert(trip.clone());
                 }
@@ -178,79 +140,3 @@ impl Trips for TripsMem {
         }
     }
 }
-
-fn match_query(query: &Query, triples: BTreeSet<Trip>) -> Vec<BTreeMap<String, String>> {
-    let mut results = vec![];
-    for trip in triples {
-        match match_query_single(query, trip) {
-            Some(res) => results.push(res),
-            None => (),
-        }
-    }
-    results
-}
-
-fn match_query_single(query: &Query, triple: Trip) -> Option<BTreeMap<String, String>> {
-    let mut result = BTreeMap::new();
-    match &query.0 {
-        Slot::Variable(variable_name) => {
-            result.insert(variable_name.to_owned(), triple.0);
-            ()
-        }
-        Slot::Value(value) => {
-            if *value == triple.0 {
-                ()
-            } else {
-                return None;
-            }
-        }
-        Slot::Any => (),
-    }
-    match &query.1 {
-        Slot::Variable(variable_name) => match result.get(variable_name) {
-            Some(value) => {
-                if *value == triple.1 {
-                    ()
-                } else {
-                    return None;
-                }
-            }
-            None => {
-                result.insert(variable_name.to_owned(), triple.1);
-                ()
-            }
-        },
-        Slot::Value(value) => {
-            if *value == triple.1 {
-                ()
-            } else {
-                return None;
-            }
-        }
-        Slot::Any => (),
-    }
-    match &query.2 {
-        Slot::Variable(variable_name) => match result.get(variable_name) {
-            Some(value) => {
-                if *value == triple.2 {
-                    ()
-                } else {
-                    return None;
-                }
-            }
-            None => {
-                result.insert(variable_name.to_owned(), triple.2);
-                ()
-            }
-        },
-        Slot::Value(value) => {
-            if *value == triple.2 {
-                ()
-            } else {
-                return None;
-            }
-        }
-        Slot::Any => (),
-    }
-    Some(result)
-}
@@ -5,7 +5,7 @@
 use hashbag::HashBag;
 use std::collections::{BTreeMap, BTreeSet};
 use trips::mem::TripsMem;
-use trips::{Query, Slot, Trip, Trips};
+use trips::{aggregate, Aggregate, Query, Slot, Trip, Trips};
 
 #[cfg(feature = "heed")]
 use heed::{Env, EnvOpenOptions};
@@ -130,96 +130,239 @@ fn basic_filter() {
     assert_eq!(collections, result);
 }
 
-// #[test]
-// fn match_all_query_collection() {
-//     let mut store: TripsMem = trips::mem::TripsMem::new();
-//     let _ = store.add_collection("T".to_owned());
-//     let _ = store.add_triples(
-//         "T".to_owned(),
-//         &mut BTreeSet::from([
-//             Trip("1".to_owned(), "2".to_owned(), "3".to_owned()),
-//             Trip("1".to_owned(), "2".to_owned(), "6".to_owned()),
-//             Trip("1".to_owned(), "2".to_owned(), "5".to_owned()),
-//         ]),
-//     );
-//     let results = store
-//         .query(
-//             "T".to_owned(),
-//             BTreeSet::from([Query(Slot::Any, Slot::Any, Slot::Any)]),
-//         )
-//         .unwrap();
-//     let expected = HashBag::from_iter([
-//         BTreeMap::from_iter([]),
-//         BTreeMap::from_iter([]),
-//         BTreeMap::from_iter([]),
-//     ]);
-//     assert_eq!(results, expected);
-// }
-
-// #[test]
-// fn basic_query_collection() {
-//     let mut store: TripsMem = trips::mem::TripsMem::new();
-//     let _ = store.add_collection("T".to_owned());
-//     let _ = store.add_triples(
-//         "T".to_owned(),
-//         &mut BTreeSet::from([
-//             Trip("1".to_owned(), "2".to_owned(), "3".to_owned()),
-//             Trip("1".to_owned(), "2".to_owned(), "6".to_owned()),
-//             Trip("1".to_owned(), "2".to_owned(), "5".to_owned()),
-//         ]),
-//     );
-//     let results = store
-//         .query(
-//             "T".to_owned(),
-//             BTreeSet::from([Query(
-//                 Slot::Variable("A".to_owned()),
-//                 Slot::Value("2".to_owned()),
-//                 Slot::Any,
-//             )]),
-//         )
-//         .unwrap();
-//     let expected = HashBag::from_iter([
-//         BTreeMap::from_iter([("A".to_owned(), "1".to_owned())]),
-//         BTreeMap::from_iter([("A".to_owned(), "1".to_owned())]),
-//         BTreeMap::from_iter([("A".to_owned(), "1".to_owned())]),
-//     ]);
-//     assert_eq!(results, expected);
-// }
-
-// #[test]
-// fn complex_single_query_collection() {
-//     let mut store: TripsMem = trips::mem::TripsMem::new();
-//     let _ = store.add_collection("T".to_owned());
-//     let _ = store.add_triples(
-//         "T".to_owned(),
-//         &mut BTreeSet::from([
-//             Trip("2".to_owned(), "2".to_owned(), "3".to_owned()),
-//             Trip("1".to_owned(), "2".to_owned(), "6".to_owned()),
-//             Trip("1".to_owned(), "3".to_owned(), "5".to_owned()),
-//         ]),
-//     );
-//     let results = store
-//         .query(
-//             "T".to_owned(),
-//             BTreeSet::from([
-//                 Query(
-//                     Slot::Variable("A".to_owned()),
-//                     Slot::Value("2".to_owned()),
-//                     Slot::Variable("C".to_owned()),
-//                 ),
-//                 Query(Slot::Any, Slot::Variable("C".to_owned()), Slot::Any),
-//             ]),
-//         )
-//         .unwrap();
-//     let expected = HashBag::from_iter([
-//         BTreeMap::from_iter([
-//             ("A".to_owned(), "1".to_owned()),
-//             ("C".to_owned(), "6".to_owned()),
-//         ]),
-//         BTreeMap::from_iter([
-//             ("A".to_owned(), "2".to_owned()),
-//             ("C".to_owned(), "3".to_owned()),
-//         ]),
-//     ]);
-//     assert_eq!(results, expected);
-// }
+#[test]
+fn match_all_query_collection() {
+    let mut store = initialize();
+    let _ = store.add_collection("T".to_owned());
+    let _ = store.add_triples(
+        "T".to_owned(),
+        &mut BTreeSet::from([
+            Trip("1".to_owned(), "2".to_owned(), "3".to_owned()),
+            Trip("1".to_owned(), "2".to_owned(), "6".to_owned()),
+            Trip("1".to_owned(), "2".to_owned(), "5".to_owned()),
+        ]),
+    );
+    let results = store
+        .query(
+            "T".to_owned(),
+            BTreeSet::from([Query(Slot::Any, Slot::Any, Slot::Any)]),
+        )
+        .unwrap();
+    let expected = HashBag::from_iter([
+        BTreeMap::from_iter([]),
+        BTreeMap::from_iter([]),
+        BTreeMap::from_iter([]),
+    ]);
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn basic_query_collection() {
+    let mut store = initialize();
+    let _ = store.add_collection("T".to_owned());
+    let _ = store.add_triples(
+        "T".to_owned(),
+        &mut BTreeSet::from([
+            Trip("1".to_owned(), "2".to_owned(), "3".to_owned()),
+            Trip("1".to_owned(), "2".to_owned(), "6".to_owned()),
+            Trip("1".to_owned(), "2".to_owned(), "5".to_owned()),
+        ]),
+    );
+    let results = store
+        .query(
+            "T".to_owned(),
+            BTreeSet::from([Query(
+                Slot::Variable("A".to_owned()),
+                Slot::Value("2".to_owned()),
+                Slot::Any,
+            )]),
+        )
+        .unwrap();
+    let expected = HashBag::from_iter([
+        BTreeMap::from_iter([("A".to_owned(), "1".to_owned())]),
+        BTreeMap::from_iter([("A".to_owned(), "1".to_owned())]),
+        BTreeMap::from_iter([("A".to_owned(), "1".to_owned())]),
+    ]);
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn join_query_collection() {
+    let mut store = initialize();
+    let _ = store.add_collection("T".to_owned());
+    let _ = store.add_triples(
+        "T".to_owned(),
+        &mut BTreeSet::from([
+            Trip("2".to_owned(), "2".to_owned(), "3".to_owned()),
+            Trip("1".to_owned(), "2".to_owned(), "6".to_owned()),
+            Trip("1".to_owned(), "3".to_owned(), "5".to_owned()),
+            Trip("6".to_owned(), "4".to_owned(), "7".to_owned()),
+        ]),
+    );
+    let results = store
+        .query(
+            "T".to_owned(),
+            BTreeSet::from([
+                Query(
+                    Slot::Variable("A".to_owned()),
+                    Slot::Value("2".to_owned()),
+                    Slot::Variable("C".to_owned()),
+                ),
+                Query(
+                    Slot::Variable("C".to_owned()),
+                    Slot::Any,
+                    Slot::Variable("D".to_owned()),
+                ),
+            ]),
+        )
+        .unwrap();
+    let expected = HashBag::from_iter([BTreeMap::from_iter([
+        ("A".to_owned(), "1".to_owned()),
+        ("C".to_owned(), "6".to_owned()),
+        ("D".to_owned(), "7".to_owned()),
+    ])]);
+    assert_eq!(results, expected);
+}
+
+#[test]
+fn query_repeated_variable() {
+    let mut store = initialize();
+    let _ = store.add_collection("T".to_owned());
+    let _ = store.add_triples(
+        "T".to_owned(),
+        &mut BTreeSet::from([
+            Trip("1".to_owned(), "2".to_owned(), "1".to_owned()),
+            Trip("1".to_owned(), "2".to_owned(), "3".to_owned()),
+        ]),
+    );
+    let results = store
+        .query(
+            "T".to_owned(),
+            BTreeSet::from([Query(
+                Slot::Variable("A".to_owned()),
+                Slot::Any,
+                Slot::Variable("A".to_owned()),
+            )]),
+        )
+        .unwrap();
+    let expected = HashBag::from_iter([BTreeMap::from_iter([("A".to_owned(), "1".to_owned())])]);
+    assert_eq!(results, expected);
+}
+
+fn employees() -> Box<dyn Trips> {
+    let mut store = initialize();
+    let _ = store.add_collection("T".to_owned());
+    let _ = store.add_triples(
+        "T".to_owned(),
+        &mut BTreeSet::from([
+            Trip("ann".to_owned(), "worksAt".to_owned(), "acme".to_owned()),
+            Trip("bob".to_owned(), "worksAt".to_owned(), "acme".to_owned()),
+            Trip("cat".to_owned(), "worksAt".to_owned(), "initech".to_owned()),
+            Trip("ann".to_owned(), "age".to_owned(), "9".to_owned()),
+            Trip("bob".to_owned(), "age".to_owned(), "30".to_owned()),
+            Trip("cat".to_owned(), "age".to_owned(), "41".to_owned()),
+        ]),
+    );
+    store
+}
+
+fn row(values: &[(&str, &str)]) -> BTreeMap<String, String> {
+    values
+        .iter()
+        .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+        .collect()
+}
+
+#[test]
+fn aggregate_query_by_group() {
+    let results = employees()
+        .aggregate_query(
+            "T".to_owned(),
+            BTreeSet::from([
+                Query(
+                    Slot::Variable("P".to_owned()),
+                    Slot::Value("worksAt".to_owned()),
+                    Slot::Variable("C".to_owned()),
+                ),
+                Query(
+                    Slot::Variable("P".to_owned()),
+                    Slot::Value("age".to_owned()),
+                    Slot::Variable("Age".to_owned()),
+                ),
+            ]),
+            &BTreeSet::from(["C".to_owned()]),
+            &BTreeMap::from([
+                ("N".to_owned(), Aggregate::Count("P".to_owned())),
+                ("Min".to_owned(), Aggregate::Min("Age".to_owned())),
+                ("Max".to_owned(), Aggregate::Max("Age".to_owned())),
+                ("Total".to_owned(), Aggregate::Sum("Age".to_owned())),
+            ]),
+        )
+        .unwrap();
+    assert_eq!(
+        results,
+        vec![
+            row(&[
+                ("C", "acme"),
+                ("N", "2"),
+                ("Min", "9"),
+                ("Max", "30"),
+                ("Total", "39")
+            ]),
+            row(&[
+                ("C", "initech"),
+                ("N", "1"),
+                ("Min", "41"),
+                ("Max", "41"),
+                ("Total", "41")
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn aggregate_without_groups() {
+    let results =
+        HashBag::from_iter([row(&[("X", "1.5")]), row(&[("X", "2")]), row(&[("X", "2")])]);
+    assert_eq!(
+        aggregate(
+            &results,
+            &BTreeSet::new(),
+            &BTreeMap::from([
+                ("N".to_owned(), Aggregate::Count("X".to_owned())),
+                ("Total".to_owned(), Aggregate::Sum("X".to_owned())),
+            ]),
+        )
+        .unwrap(),
+        vec![row(&[("N", "3"), ("Total", "5.5")])]
+    );
+    assert_eq!(
+        aggregate(
+            &HashBag::new(),
+            &BTreeSet::new(),
+            &BTreeMap::from([
+                ("N".to_owned(), Aggregate::Count("X".to_owned())),
+                ("Max".to_owned(), Aggregate::Max("X".to_owned())),
+            ]),
+        )
+        .unwrap(),
+        vec![row(&[("N", "0")])]
+    );
+}
+
+#[test]
+fn aggregate_errors() {
+    let results = HashBag::from_iter([row(&[("X", "a")])]);
+    assert!(aggregate(
+        &results,
+        &BTreeSet::new(),
+        &BTreeMap::from([("Total".to_owned(), Aggregate::Sum("X".to_owned()))]),
+    )
+    .is_err());
+    assert!(aggregate(
+        &results,
+        &BTreeSet::from(["Y".to_owned()]),
+        &BTreeMap::new()
+    )
+    .is_err());
+}
@@ -9,10 +9,11 @@ use ligature_graph::LigatureGraph;
 use rustyline::error::ReadlineError;
 use rustyline::{DefaultEditor, Result};
 use tabled::{
+    builder::Builder,
     settings::{object::Rows, Modify, Width},
     Table, Tabled,
 };
-use wander::run;
+use wander::{run_in, Environment};
 
 pub struct REPLState {
     pub state: LigatureGraph,
@@ -27,6 +28,8 @@ pub fn start_repl(state: &mut REPLState) -> Result<()> {
     if rl.load_history("history.txt").is_err() {
         println!("No previous history.");
     }
+    let commands = wander::prelude::common();
+    let mut environment = Environment::new(&commands);
     loop {
         let readline = rl.readline("> ");
         match readline {
@@ -37,13 +40,15 @@ pub fn start_repl(state: &mut REPLState) -> Result<()> {
                         break;
                     }
                 } else {
-                    match run(line.as_str(), &wander::prelude::common(), &mut state.state) {
+                    match run_in(line.as_str(), &mut environment, &mut state.state) {
                         Ok(result) => {
                             match result {
-                                wander::WanderValue::Element(ligature::Element(element)) => {
+                                wander::WanderValue::Element(ligature::Element(element, _, _)) => {
                                     println!("{}", element)
                                 }
                                 wander::WanderValue::Quote(quote) => todo!(), //println!("{}", quote),
+                                wander::WanderValue::Variable(name) => println!("${name}"),
+                                wander::WanderValue::Table(table) => print_table(&table),
                                 wander::WanderValue::Network(btree_set) => {
                                     if btree_set.is_empty() {
                                         println!("{{}}");
@@ -88,6 +93,19 @@ pub fn start_repl(state: &mut REPLState) -> Result<()> {
     rl.save_history("history.txt")
 }
 
+fn print_table(table: &wander::Table) {
+    let columns = table.columns();
+    let mut builder = Builder::default();
+    builder.set_header(columns.iter().map(|column| format!("?{column}")));
+    for row in &table.0 {
+        builder.push_record(columns.iter().map(|column| match row.get(column) {
+            Some(element) => wander::table::write_element(element),
+            None => String::new(),
+        }));
+    }
+    println!("{}", builder.build());
+}
+
 #[derive(Tabled)]
 struct Triple {
     first: String,
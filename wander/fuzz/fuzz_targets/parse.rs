@@ -0,0 +1,18 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Checks that tokenizing and parsing never panic.
+//! Run with `cargo +nightly fuzz run parse fuzz/corpus/parse fuzz/seeds/parse` from the wander directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wander::lexer::tokenize_and_filter;
+use wander::parser::parse;
+
+fuzz_target!(|script: &str| {
+    if let Ok(tokens) = tokenize_and_filter(script) {
+        let _ = parse(tokens);
+    }
+});
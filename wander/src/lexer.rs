@@ -6,7 +6,7 @@ use ligature::Element;
 use logos::{Lexer, Logos};
 use serde::Serialize;
 
-use crate::WanderError;
+use crate::{Diagnostic, Location, Span};
 
 #[derive(Logos, Debug, PartialEq, Eq, Clone, Serialize)]
 #[logos()]
@@ -39,6 +39,10 @@ pub enum Token {
     #[regex("[a-zA-Z0-9-._~:/?#\\[\\]@!$&'*+;%=\\x{00A0}-\\x{D7FF}\\x{F900}-\\x{FDCF}\\x{FDF0}-\\x{FFEF}\\x{10000}-\\x{1FFFD}\\x{20000}-\\x{2FFFD}\\x{30000}-\\x{3FFFD}\\x{40000}-\\x{4FFFD}\\x{50000}-\\x{5FFFD}\\x{60000}-\\x{6FFFD}\\x{70000}-\\x{7FFFD}\\x{80000}-\\x{8FFFD}\\x{90000}-\\x{9FFFD}\\x{A0000}-\\x{AFFFD}\\x{B0000}-\\x{BFFFD}\\x{C0000}-\\x{CFFFD}\\x{D0000}-\\x{DFFFD}\\x{E1000}-\\x{EFFFD}]+", identifier)]
     Element(ligature::Element),
 
+    //Note: uses the same characters as Element, the higher priority makes a leading $ a Variable
+    #[regex("\\$[a-zA-Z0-9-._~:/?#\\[\\]@!$&'*+;%=\\x{00A0}-\\x{D7FF}\\x{F900}-\\x{FDCF}\\x{FDF0}-\\x{FFEF}\\x{10000}-\\x{1FFFD}\\x{20000}-\\x{2FFFD}\\x{30000}-\\x{3FFFD}\\x{40000}-\\x{4FFFD}\\x{50000}-\\x{5FFFD}\\x{60000}-\\x{6FFFD}\\x{70000}-\\x{7FFFD}\\x{80000}-\\x{8FFFD}\\x{90000}-\\x{9FFFD}\\x{A0000}-\\x{AFFFD}\\x{B0000}-\\x{BFFFD}\\x{C0000}-\\x{CFFFD}\\x{D0000}-\\x{DFFFD}\\x{E1000}-\\x{EFFFD}]+", variable, priority = 100)]
+    Variable(String),
+
     #[token("|")]
     Pipe,
 
@@ -61,7 +65,11 @@ fn string(lex: &mut Lexer<Token>) -> Option<String> {
 }
 
 fn identifier(lex: &mut Lexer<Token>) -> Option<ligature::Element> {
-    Some(ligature::Element(lex.slice().to_string()))
+    Some(ligature::Element(lex.slice().to_string(), None, None))
+}
+
+fn variable(lex: &mut Lexer<Token>) -> Option<String> {
+    Some(lex.slice()[1..].to_string())
 }
 
 fn comment(lex: &mut Lexer<Token>) -> Option<String> {
@@ -72,28 +80,78 @@ fn ws(lex: &mut Lexer<Token>) -> Option<String> {
     Some(lex.slice().to_string())
 }
 
-pub fn tokenize(script: &str) -> Result<Vec<Token>, WanderError> {
+/// Tracks the line and column of offsets into a script as tokens are read in order.
+struct Positions<'a> {
+    script: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Positions<'_> {
+    fn span(&mut self, range: std::ops::Range<usize>) -> Span {
+        for c in self.script[self.offset..range.start].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = range.start;
+        Span {
+            start: range.start,
+            end: range.end,
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+fn describe_char(script: &str, offset: usize) -> String {
+    match script[offset..].chars().next() {
+        Some(c) => format!("Unexpected character `{}`.", c.escape_default()),
+        None => "Unexpected end of script.".to_owned(),
+    }
+}
+
+/// Split a script into Tokens.
+/// Every character that can't start a Token is reported.
+pub fn tokenize(script: &str) -> Result<Vec<Location<Token>>, Vec<Diagnostic>> {
     let lexer = Token::lexer(script).spanned();
+    let mut positions = Positions {
+        script,
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
     let mut results = vec![];
-    for (token, _range) in lexer {
+    let mut diagnostics = vec![];
+    for (token, range) in lexer {
+        let span = positions.span(range);
         match token {
-            Ok(token) => results.push(token),
-            Err(_) => return Err(WanderError(String::from("Error tokenizing input."))),
+            Ok(token) => results.push(Location(token, span)),
+            Err(_) => diagnostics.push(Diagnostic::new(&describe_char(script, span.start), span)),
         }
     }
-    Ok(results)
+    if diagnostics.is_empty() {
+        Ok(results)
+    } else {
+        Err(diagnostics)
+    }
 }
 
-pub fn tokenize_and_filter(script: &str) -> Result<Vec<Token>, WanderError> {
+pub fn tokenize_and_filter(script: &str) -> Result<Vec<Location<Token>>, Vec<Diagnostic>> {
     let tokens = tokenize(script);
     tokens.map(|mut tokens| {
+        tokens.retain(|Location(token, _)| {
+            !matches!(token, Token::Comment(_)) && !matches!(token, Token::WS(_))
+        });
         tokens
-            .retain(|token| !matches!(token, Token::Comment(_)) && !matches!(token, Token::WS(_)));
-        tokens
-            .iter()
-            .map(|token| match token {
-                Token::String(value) => Token::Element(Element(value.to_owned())),
-                token => token.clone(),
+            .into_iter()
+            .map(|Location(token, span)| match token {
+                Token::String(value) => Location(Token::Element(Element(value, None, None)), span),
+                token => Location(token, span),
             })
             .collect()
     })
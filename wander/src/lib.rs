@@ -9,6 +9,7 @@
 use std::{
     collections::{BTreeSet, HashMap},
     fmt::Display,
+    path::{Path, PathBuf},
 };
 
 use lexer::tokenize_and_filter;
@@ -23,23 +24,272 @@ pub mod lexer;
 pub mod parser;
 #[doc(hidden)]
 pub mod prelude;
+pub mod table;
+
+pub use table::Table;
 
 /// An error that occurs while running a Wander script.
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize, Clone)]
 pub struct WanderError(pub String);
 
+/// A region of a script.
+/// Offsets are in bytes while lines and columns start at one and count characters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Span {
+    /// The offset of the first byte of the region.
+    pub start: usize,
+    /// The offset just past the last byte of the region.
+    pub end: usize,
+    /// The line the region starts on.
+    pub line: usize,
+    /// The column the region starts on.
+    pub column: usize,
+}
+
+/// A value along with where it was found in a script.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Location<T>(pub T, pub Span);
+
+/// A problem found in a script along with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Diagnostic {
+    /// A description of the problem.
+    pub message: String,
+    /// Where the problem was found.
+    pub span: Span,
+    /// Descriptions of what would have been valid at this position.
+    pub expected: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Create a Diagnostic that doesn't list any expected tokens.
+    pub fn new(message: &str, span: Span) -> Self {
+        Diagnostic {
+            message: message.to_owned(),
+            span,
+            expected: vec![],
+        }
+    }
+
+    /// Render this Diagnostic as a message followed by the line of the script it refers to
+    /// and a caret under the problem.
+    pub fn render(&self, script: &str) -> String {
+        let mut message = self.message.clone();
+        if let Some((last, rest)) = self.expected.split_last() {
+            message += ", expected ";
+            if !rest.is_empty() {
+                message += &rest.join(", ");
+                message += " or ";
+            }
+            message += last;
+            message += ".";
+        }
+        let line = script.lines().nth(self.span.line.saturating_sub(1)).unwrap_or("");
+        let number = self.span.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let indent: String = line
+            .chars()
+            .take(self.span.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = script
+            .get(self.span.start..self.span.end)
+            .map(|text| text.chars().count())
+            .unwrap_or(0)
+            .max(1);
+        format!(
+            "{message}\n{gutter}--> line {}, column {}\n{gutter} |\n{number} | {line}\n{gutter} | {indent}{}",
+            self.span.line,
+            self.span.column,
+            "^".repeat(width)
+        )
+    }
+
+    /// Render this Diagnostic into a WanderError.
+    pub fn to_error(&self, script: &str) -> WanderError {
+        WanderError(self.render(script))
+    }
+}
+
+/// The type of a value passed to a Command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WanderType {
+    /// Accepts any value.
+    Any,
+    /// Accepts an Element.
+    Element,
+    /// Accepts a Quote.
+    Quote,
+    /// Accepts a Network.
+    Network,
+    /// Accepts a Table.
+    Table,
+}
+
+impl WanderType {
+    /// Check if a value has this type.
+    pub fn accepts(&self, value: &WanderValue) -> bool {
+        matches!(
+            (self, value),
+            (WanderType::Any, _)
+                | (WanderType::Element, WanderValue::Element(_))
+                | (WanderType::Quote, WanderValue::Quote(_))
+                | (WanderType::Network, WanderValue::Network(_))
+                | (WanderType::Table, WanderValue::Table(_))
+        )
+    }
+}
+
+impl Display for WanderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WanderType::Any => write!(f, "any value"),
+            WanderType::Element => write!(f, "an Element"),
+            WanderType::Quote => write!(f, "a Quote"),
+            WanderType::Network => write!(f, "a Network"),
+            WanderType::Table => write!(f, "a Table"),
+        }
+    }
+}
+
+/// The function called when a Command is called from Wander.
+/// Takes three arguments: the list of arguments to the command, the current state, and the current Environment.
+pub type CommandFn = Box<
+    dyn Fn(Vec<WanderValue>, &mut dyn Ligature, &mut Environment) -> Result<WanderValue, WanderError>,
+>;
+
 /// A struct representing a function exported from the hosting application that
 /// can be called from Wander.
+/// Since `fun` is a closure, a Command can capture state from the host application.
 pub struct Command {
     /// Documentation for the Command.
     pub doc: String,
-    /// The function called when the HostFunction is called from Wander.
-    /// Takes three arguments: the list of arguments to the command, the current state, and the set of commmands.
-    pub fun: fn(
-        Vec<WanderValue>,
-        &mut dyn Ligature,
-        &HashMap<String, Command>,
-    ) -> Result<WanderValue, WanderError>,
+    /// The types of the Command's parameters, which are checked before the Command is called.
+    /// None means the Command accepts any arguments and checks them itself.
+    pub parameters: Option<Vec<WanderType>>,
+    /// The function called when the Command is called from Wander.
+    pub fun: CommandFn,
+}
+
+fn check_arguments(
+    name: &str,
+    parameters: &[WanderType],
+    arguments: &[WanderValue],
+) -> Result<(), WanderError> {
+    if parameters.len() != arguments.len() {
+        return Err(WanderError(format!(
+            "`{name}` takes {} arguments but was given {}.",
+            parameters.len(),
+            arguments.len()
+        )));
+    }
+    for (i, (parameter, argument)) in parameters.iter().zip(arguments).enumerate() {
+        if !parameter.accepts(argument) {
+            return Err(WanderError(format!(
+                "Argument {} of `{name}` must be {parameter} but was {argument}.",
+                i + 1
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A Command defined in Wander with `define`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Definition {
+    /// The parameters of the Definition.
+    /// When the Definition is called, each occurrence of a parameter in the body is replaced by its argument
+    /// and each parameter is bound as a variable in a new scope.
+    pub parameters: Vec<ligature::Element>,
+    /// The Quote that is run when the Definition is called.
+    pub body: Quote,
+}
+
+/// The deepest that Definitions and quotes can nest before the script fails.
+const MAX_DEPTH: usize = 128;
+
+/// The Commands and Definitions available while running a script.
+pub struct Environment<'a> {
+    /// Commands provided by the host application.
+    pub commands: &'a HashMap<String, Command>,
+    /// Commands defined in Wander.
+    pub definitions: HashMap<String, Definition>,
+    /// Variables, from the script's scope to the innermost scope.
+    scopes: Vec<HashMap<String, WanderValue>>,
+    depth: usize,
+    /// The files being imported, from the first import to the innermost one.
+    imports: Vec<PathBuf>,
+}
+
+impl<'a> Environment<'a> {
+    /// Create an Environment with the given Commands, no Definitions, and an empty scope.
+    pub fn new(commands: &'a HashMap<String, Command>) -> Self {
+        Environment {
+            commands,
+            definitions: HashMap::new(),
+            scopes: vec![HashMap::new()],
+            depth: 0,
+            imports: vec![],
+        }
+    }
+
+    /// Create the Environment that an imported file runs in.
+    /// It continues at this Environment's depth and fails if the file is already being imported.
+    fn import(&self, path: PathBuf) -> Result<Environment<'a>, WanderError> {
+        if let Some(start) = self.imports.iter().position(|import| *import == path) {
+            let cycle: Vec<String> = self.imports[start..]
+                .iter()
+                .chain([&path])
+                .map(|import| import.display().to_string())
+                .collect();
+            return Err(WanderError(format!(
+                "Import cycle: {}.",
+                cycle.join(" -> ")
+            )));
+        }
+        if self.depth >= MAX_DEPTH {
+            return Err(WanderError(format!(
+                "Importing exceeded the maximum depth of {MAX_DEPTH}."
+            )));
+        }
+        let mut imports = self.imports.clone();
+        imports.push(path);
+        Ok(Environment {
+            commands: self.commands,
+            definitions: HashMap::new(),
+            scopes: vec![HashMap::new()],
+            depth: self.depth + 1,
+            imports,
+        })
+    }
+
+    /// The directory of the file being imported, which relative imports are resolved against.
+    fn import_directory(&self) -> Option<&Path> {
+        self.imports.last().and_then(|import| import.parent())
+    }
+
+    /// Bind a variable in the innermost scope, replacing any existing variable with that name in that scope.
+    pub fn bind(&mut self, name: &str, value: WanderValue) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), value);
+        }
+    }
+
+    /// Look up a variable, starting with the innermost scope.
+    pub fn lookup(&self, name: &str) -> Option<&WanderValue> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Replace a Variable with its value.
+    fn resolve(&self, value: WanderValue) -> Result<WanderValue, WanderError> {
+        match value {
+            WanderValue::Variable(name) => match self.lookup(&name) {
+                Some(value) => Ok(value.clone()),
+                None => Err(WanderError(format!("Variable `${name}` is not defined."))),
+            },
+            value => Ok(value),
+        }
+    }
 }
 
 /// A function call.
@@ -51,6 +301,9 @@ pub struct Call {
     pub arguments: Vec<WanderValue>,
 }
 
+/// Calls joined with `|`, where the result of each Call is passed as the last argument of the next Call.
+pub type Pipeline = Vec<Location<Call>>;
+
 /// A quote of WanderValues.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct Quote(
@@ -68,6 +321,10 @@ pub enum WanderValue {
     Quote(Quote),
     /// A Network.
     Network(BTreeSet<ligature::Entry>),
+    /// A reference to a variable, written as `$name`.
+    Variable(String),
+    /// A Table of variable bindings.
+    Table(Table),
 }
 
 impl core::hash::Hash for WanderValue {
@@ -156,70 +413,233 @@ impl Display for WanderValue {
             },
             WanderValue::Network(values) => write_network(values, f),
             WanderValue::Quote(quote) => write_quote(quote, f),
+            WanderValue::Variable(name) => write!(f, "${name}"),
+            WanderValue::Table(table) => write!(f, "{table}"),
         }
     }
 }
 
 /// Run a quote.
+/// When the first value is an Element it names the command to call.
+/// When it is a quote, that quote is run with the remaining values appended to it,
+/// and when it is a Variable, its value takes its place.
+/// A quote holding a single value that isn't an Element results in that value.
+/// Every quote, including the bodies of Definitions, is run through here,
+/// so this is where the depth of nested quotes is limited.
 pub fn run_quote(
     quote: &Quote,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
     state: &mut dyn Ligature,
 ) -> Result<WanderValue, WanderError> {
-    if quote.0.is_empty() {
-        Ok(WanderValue::Network(BTreeSet::new()))
-    } else {
-        match quote.0.first() {
-            Some(WanderValue::Element(name)) => {
-                let mut arguments = quote.0.clone();
-                arguments.remove(0);
-                let calls = vec![Call {
-                    name: name.clone(),
-                    arguments,
-                }];
-                run_calls(&calls, commands, state)
-            }
-            _ => todo!(),
+    if environment.depth >= MAX_DEPTH {
+        return Err(WanderError(format!(
+            "Running a quote exceeded the maximum depth of {MAX_DEPTH}."
+        )));
+    }
+    environment.depth += 1;
+    let result = run_quote_values(quote, environment, state);
+    environment.depth -= 1;
+    result
+}
+
+fn run_quote_values(
+    quote: &Quote,
+    environment: &mut Environment,
+    state: &mut dyn Ligature,
+) -> Result<WanderValue, WanderError> {
+    let (head, arguments) = match quote.0.split_first() {
+        None => return Ok(WanderValue::Network(BTreeSet::new())),
+        Some((WanderValue::Element(name), arguments)) => {
+            let calls = vec![Call {
+                name: name.clone(),
+                arguments: arguments.to_vec(),
+            }];
+            return run_calls(&calls, environment, state);
+        }
+        Some((value @ (WanderValue::Network(_) | WanderValue::Table(_)), [])) => {
+            return Ok(value.clone())
+        }
+        Some((value @ WanderValue::Network(_), _)) => {
+            return Err(WanderError(format!(
+                "The first value of a quote is called, but {value} is a network."
+            )))
         }
+        Some((value @ WanderValue::Table(_), _)) => {
+            return Err(WanderError(format!(
+                "The first value of a quote is called, but {value} is a table."
+            )))
+        }
+        Some((WanderValue::Quote(head), arguments)) => (head.0.clone(), arguments),
+        Some((variable @ WanderValue::Variable(_), arguments)) => {
+            (vec![environment.resolve(variable.clone())?], arguments)
+        }
+    };
+    let mut values = head;
+    values.extend(arguments.iter().cloned());
+    run_quote(&Quote(values), environment, state)
+}
+
+/// Replace a definition's parameter names with the arguments it was called with.
+/// A parameter can name the command to call, so the first value of a quote is replaced too when it is a
+/// parameter, and other command names are left alone.
+fn substitute(
+    value: &WanderValue,
+    bindings: &HashMap<&ligature::Element, &WanderValue>,
+) -> WanderValue {
+    let element = |element: &ligature::Element| match bindings.get(element) {
+        Some(WanderValue::Element(value)) => value.clone(),
+        _ => element.clone(),
+    };
+    match value {
+        WanderValue::Element(name) => match bindings.get(name) {
+            Some(value) => (*value).clone(),
+            None => value.clone(),
+        },
+        WanderValue::Variable(_) | WanderValue::Table(_) => value.clone(),
+        WanderValue::Quote(quote) => WanderValue::Quote(Quote(
+            quote.0.iter().map(|value| substitute(value, bindings)).collect(),
+        )),
+        WanderValue::Network(network) => WanderValue::Network(
+            network
+                .iter()
+                .map(|entry| match entry {
+                    Entry::Role {
+                        first,
+                        second,
+                        role,
+                    } => Entry::Role {
+                        first: element(first),
+                        second: element(second),
+                        role: element(role),
+                    },
+                    Entry::Extends { element: e, concept } => Entry::Extends {
+                        element: element(e),
+                        concept: element(concept),
+                    },
+                    Entry::NotExtends { element: e, concept } => Entry::NotExtends {
+                        element: element(e),
+                        concept: element(concept),
+                    },
+                })
+                .collect(),
+        ),
     }
 }
 
+fn run_definition(
+    name: &str,
+    definition: &Definition,
+    arguments: Vec<WanderValue>,
+    environment: &mut Environment,
+    state: &mut dyn Ligature,
+) -> Result<WanderValue, WanderError> {
+    if arguments.len() != definition.parameters.len() {
+        return Err(WanderError(format!(
+            "`{name}` takes {} arguments but was given {}.",
+            definition.parameters.len(),
+            arguments.len()
+        )));
+    }
+    let bindings = definition.parameters.iter().zip(arguments.iter()).collect();
+    let body = match substitute(&WanderValue::Quote(definition.body.clone()), &bindings) {
+        WanderValue::Quote(body) => body,
+        _ => unreachable!(),
+    };
+    // Definitions only see the script's variables and their parameters, not the variables of their caller.
+    let mut scope = HashMap::new();
+    for (parameter, argument) in definition.parameters.iter().zip(arguments) {
+        scope.insert(parameter.0.clone(), argument);
+    }
+    let callers = environment.scopes.split_off(1);
+    environment.scopes.push(scope);
+    let result = run_quote(&body, environment, state);
+    environment.scopes.truncate(1);
+    environment.scopes.extend(callers);
+    result
+}
+
 /// Run a vec of Calls
 pub fn run_calls(
     calls: &Vec<Call>,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
     state: &mut dyn Ligature,
 ) -> Result<WanderValue, WanderError> {
     let mut result = Ok(WanderValue::Network(BTreeSet::new()));
     for call in calls.clone() {
-        match commands.get(&call.name.0) {
-            Some(res) => match (res.fun)(call.arguments, state, &commands) {
+        let mut arguments = vec![];
+        for argument in call.arguments {
+            arguments.push(environment.resolve(argument)?);
+        }
+        let call = Call {
+            name: call.name,
+            arguments,
+        };
+        if let Some(res) = environment.commands.get(&call.name.0) {
+            if let Some(parameters) = &res.parameters {
+                check_arguments(&call.name.0, parameters, &call.arguments)?;
+            }
+            match (res.fun)(call.arguments, state, environment) {
                 Ok(res) => result = Ok(res),
                 Err(err) => return Err(err),
-            },
-            _ => {
-                return Err(WanderError(
-                    "Could not find command: ".to_owned() + &call.name.0,
-                ))
             }
+        } else if let Some(definition) = environment.definitions.get(&call.name.0).cloned() {
+            result = Ok(run_definition(
+                &call.name.0,
+                &definition,
+                call.arguments,
+                environment,
+                state,
+            )?);
+        } else {
+            return Err(WanderError(
+                "Could not find command: ".to_owned() + &call.name.0,
+            ));
         }
     }
     result
 }
 
+/// Render a list of Diagnostics into a single WanderError.
+pub fn render_all(diagnostics: &[Diagnostic], script: &str) -> WanderError {
+    let rendered: Vec<String> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(script))
+        .collect();
+    WanderError(rendered.join("\n\n"))
+}
+
 /// Run a Wander script with the given Bindings.
+/// Errors are reported with the position of the problem in the script.
 pub fn run(
     script: &str,
     commands: &HashMap<String, Command>,
     state: &mut dyn Ligature,
 ) -> Result<WanderValue, WanderError> {
-    let tokens = match tokenize_and_filter(script) {
-        Ok(v) => v,
-        Err(err) => return Err(err),
-    };
-    let calls = match parse(tokens) {
-        Ok(v) => v,
-        Err(err) => return Err(err),
-    };
-    run_calls(&calls, commands, state)
+    run_in(script, &mut Environment::new(commands), state)
+}
+
+/// Run a Wander script in an existing Environment, so Definitions are kept between scripts.
+pub fn run_in(
+    script: &str,
+    environment: &mut Environment,
+    state: &mut dyn Ligature,
+) -> Result<WanderValue, WanderError> {
+    let tokens = tokenize_and_filter(script).map_err(|errors| render_all(&errors, script))?;
+    let pipelines = parse(tokens).map_err(|errors| render_all(&errors, script))?;
+    let mut result = WanderValue::Network(BTreeSet::new());
+    for pipeline in pipelines {
+        let mut piped: Option<WanderValue> = None;
+        for Location(mut call, span) in pipeline {
+            if let Some(value) = piped.take() {
+                call.arguments.push(value);
+            }
+            let value = run_calls(&vec![call], environment, state)
+                .map_err(|err| Diagnostic::new(&err.0, span).to_error(script))?;
+            piped = Some(value);
+        }
+        if let Some(value) = piped {
+            result = value;
+        }
+    }
+    Ok(result)
 }
@@ -4,7 +4,7 @@
 
 use std::collections::BTreeSet;
 
-use crate::{lexer::Token, Call, Quote, WanderError, WanderValue};
+use crate::{lexer::Token, Call, Diagnostic, Location, Pipeline, Quote, Span, WanderValue};
 use gaze::Gaze;
 use ligature::{Element, Entry};
 
@@ -50,79 +50,215 @@ use ligature::{Element, Entry};
 //     // Some(results)
 // }
 
-/// Parse a sequence of Tokens into a sequence of ASTs.
-pub fn parse(tokens: Vec<Token>) -> Result<Vec<Call>, WanderError> {
-    let mut gaze = Gaze::from_vec(tokens);
-    let mut calls: Vec<Call> = vec![];
-    while !gaze.is_complete() {
-        let mut current_result = vec![];
-        let mut cont = true;
-        while !gaze.is_complete() && cont {
-            match gaze.next() {
-                Some(Token::String(value)) => {
-                    current_result.push(WanderValue::Element(Element(value)));
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Element(element) => format!("`{}`", element.0),
+        Token::String(value) => format!("`\"{value}\"`"),
+        Token::OpenParen => "`(`".to_owned(),
+        Token::CloseParen => "`)`".to_owned(),
+        Token::OpenBrace => "`{`".to_owned(),
+        Token::CloseBrace => "`}`".to_owned(),
+        Token::OpenSquare => "`[`".to_owned(),
+        Token::CloseSquare => "`]`".to_owned(),
+        Token::Variable(name) => format!("`${name}`"),
+        Token::Pipe => "`|`".to_owned(),
+        Token::Comma => "`,`".to_owned(),
+        Token::WS(_) => "whitespace".to_owned(),
+        Token::Comment(_) => "a comment".to_owned(),
+    }
+}
+
+/// Create a Diagnostic for an unexpected token, or the end of the script when there are no more tokens.
+fn unexpected(found: Option<Location<Token>>, end: Span, expected: &[&str]) -> Diagnostic {
+    let (message, span) = match found {
+        Some(Location(token, span)) => (format!("Unexpected {}", describe(&token)), span),
+        None => ("Unexpected end of script".to_owned(), end),
+    };
+    Diagnostic {
+        message,
+        span,
+        expected: expected.iter().map(|value| value.to_string()).collect(),
+    }
+}
+
+/// The deepest that quotes can be nested before parsing stops with an error.
+const MAX_NESTING: usize = 128;
+
+/// The Tokens being parsed, along with how deeply nested inside of networks and quotes the parser is.
+struct Tokens {
+    gaze: Gaze<Location<Token>>,
+    depth: usize,
+    end: Span,
+}
+
+impl Tokens {
+    /// Read the next Token, keeping track of the `{` and `(` that haven't been closed yet.
+    fn next(&mut self) -> Option<Location<Token>> {
+        let token = self.gaze.next();
+        match token {
+            Some(Location(Token::OpenBrace | Token::OpenParen, _)) => self.depth += 1,
+            Some(Location(Token::CloseBrace | Token::CloseParen, _)) => {
+                self.depth = self.depth.saturating_sub(1)
+            }
+            _ => (),
+        }
+        token
+    }
+
+    fn is_complete(&self) -> bool {
+        self.gaze.is_complete()
+    }
+}
+
+/// Skip tokens until the next `,` that isn't nested inside of a network or quote so parsing can continue
+/// after an error.
+/// Networks and quotes that were open when the error was found are skipped up to their closing brackets.
+fn recover(tokens: &mut Tokens) {
+    while let Some(Location(token, _)) = tokens.next() {
+        if token == Token::Comma && tokens.depth == 0 {
+            break;
+        }
+    }
+    tokens.depth = 0;
+}
+
+/// How reading a Call ended.
+enum Ending {
+    /// The Call ended with a `,` or the end of the script.
+    Call,
+    /// The Call ended with a `|` so the next Call is part of the same Pipeline.
+    Pipe,
+    /// The Call has an error and tokens need to be skipped before parsing can continue.
+    Unfinished,
+}
+
+/// Parse a sequence of Tokens into a sequence of Pipelines.
+/// When a Call can't be parsed, parsing continues after the next `,` so that every error is reported.
+pub fn parse(tokens: Vec<Location<Token>>) -> Result<Vec<Pipeline>, Vec<Diagnostic>> {
+    let end = tokens
+        .last()
+        .map(|Location(_, span)| *span)
+        .unwrap_or_default();
+    let mut tokens = Tokens {
+        gaze: Gaze::from_vec(tokens),
+        depth: 0,
+        end,
+    };
+    let mut pipelines: Vec<Pipeline> = vec![];
+    let mut diagnostics = vec![];
+    while !tokens.is_complete() {
+        let mut pipeline = vec![];
+        let mut valid = true;
+        loop {
+            let (call, ending) = read_call(&mut tokens);
+            match call {
+                Ok(call) => pipeline.push(call),
+                Err(diagnostic) => {
+                    diagnostics.push(diagnostic);
+                    valid = false;
                 }
-                Some(Token::Element(value)) => {
-                    current_result.push(WanderValue::Element(value));
+            }
+            match ending {
+                Ending::Call => break,
+                Ending::Unfinished => {
+                    recover(&mut tokens);
+                    break;
                 }
-                Some(Token::Comma) => cont = false,
-                Some(Token::OpenBrace) => match read_network(&mut gaze) {
-                    Ok(res) => {
-                        current_result.push(res);
-                    }
-                    _ => todo!(),
-                },
-                Some(Token::OpenParen) => match read_quote(&mut gaze) {
-                    Ok(res) => {
-                        current_result.push(res);
-                    }
-                    _ => todo!(),
-                },
-                Some(_) => return Err(WanderError(format!("Error parsing {:?}", gaze.peek()))),
-                None => return Err(WanderError(format!("Error parsing {:?}", gaze.peek()))),
+                Ending::Pipe if tokens.is_complete() => {
+                    diagnostics.push(unexpected(None, end, &["a command name"]));
+                    valid = false;
+                    break;
+                }
+                Ending::Pipe => (),
             }
         }
-        match &current_result[..] {
-            [WanderValue::Element(name)] => calls.push(Call {
-                name: name.clone(),
-                arguments: vec![],
-            }),
-            [WanderValue::Element(name), ref args @ ..] => {
-                calls.push(Call {
-                    name: name.clone(),
-                    arguments: args.to_vec(),
-                });
+        if valid {
+            pipelines.push(pipeline);
+        }
+    }
+    if diagnostics.is_empty() {
+        Ok(pipelines)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+/// Read a single Call up to and including the `,` or `|` that ends it.
+fn read_call(tokens: &mut Tokens) -> (Result<Location<Call>, Diagnostic>, Ending) {
+    let mut current_result = vec![];
+    let mut start = None;
+    let mut ending = Ending::Call;
+    while !tokens.is_complete() {
+        let token = tokens.next();
+        if start.is_none() {
+            start = token.clone();
+        }
+        let value = match token {
+            Some(Location(Token::String(value), _)) => {
+                Ok(WanderValue::Element(Element(value, None, None)))
+            }
+            Some(Location(Token::Element(value), _)) => Ok(WanderValue::Element(value)),
+            Some(Location(Token::Variable(name), _)) => Ok(WanderValue::Variable(name)),
+            Some(Location(Token::Comma, _)) => break,
+            Some(Location(Token::Pipe, _)) => {
+                ending = Ending::Pipe;
+                break;
             }
-            _ => todo!(),
+            Some(Location(Token::OpenBrace, _)) => read_network(tokens),
+            Some(Location(Token::OpenParen, span)) => read_quote(tokens, span),
+            token => Err(unexpected(
+                token,
+                tokens.end,
+                &["an Element", "a Variable", "`{`", "`(`", "`,`", "`|`"],
+            )),
+        };
+        match value {
+            Ok(value) => current_result.push(value),
+            Err(diagnostic) => return (Err(diagnostic), Ending::Unfinished),
         }
     }
-    Ok(calls)
+    match (&current_result[..], start) {
+        ([WanderValue::Element(name), ref args @ ..], Some(Location(_, span))) => (
+            Ok(Location(
+                Call {
+                    name: name.clone(),
+                    arguments: args.to_vec(),
+                },
+                span,
+            )),
+            ending,
+        ),
+        (_, start) => (
+            Err(unexpected(start, tokens.end, &["a command name"])),
+            ending,
+        ),
+    }
+}
+
+fn read_element(tokens: &mut Tokens) -> Result<Element, Diagnostic> {
+    match tokens.next() {
+        Some(Location(Token::Element(element), _)) => Ok(element),
+        token => Err(unexpected(token, tokens.end, &["an Element"])),
+    }
 }
 
-fn read_network(gaze: &mut Gaze<Token>) -> Result<WanderValue, WanderError> {
-    let mut cont = true;
+fn read_network(tokens: &mut Tokens) -> Result<WanderValue, Diagnostic> {
     let mut result: BTreeSet<ligature::Entry> = BTreeSet::new();
-    while cont {
-        let first = match gaze.next() {
-            Some(Token::Element(first)) => first,
-            Some(Token::CloseBrace) => return Ok(WanderValue::Network(result)),
-            _ => todo!(),
-        };
-        let second = match gaze.next() {
-            Some(Token::Element(second)) => second,
-            _ => todo!(),
-        };
-        let third = match gaze.next() {
-            Some(Token::Element(third)) => third,
-            _ => todo!(),
+    loop {
+        let first = match tokens.next() {
+            Some(Location(Token::Element(first), _)) => first,
+            Some(Location(Token::CloseBrace, _)) => return Ok(WanderValue::Network(result)),
+            token => return Err(unexpected(token, tokens.end, &["an Element", "`}`"])),
         };
+        let second = read_element(tokens)?;
+        let third = read_element(tokens)?;
 
-        if second == Element(":".to_owned()) {
+        if second == Element(":".to_owned(), None, None) {
             result.insert(Entry::Extends {
                 element: first,
                 concept: third,
             });
-        } else if second == Element("¬:".to_owned()) {
+        } else if second == Element("¬:".to_owned(), None, None) {
             result.insert(Entry::NotExtends {
                 element: first,
                 concept: third,
@@ -135,31 +271,42 @@ fn read_network(gaze: &mut Gaze<Token>) -> Result<WanderValue, WanderError> {
             });
         }
 
-        match gaze.next() {
-            Some(Token::Comma) => (),
-            Some(Token::CloseBrace) => cont = false,
-            Some(_) => return Err(WanderError("Error parsing Network.".to_owned())),
-            None => return Err(WanderError("Error parsing Network.".to_owned())),
+        match tokens.next() {
+            Some(Location(Token::Comma, _)) => (),
+            Some(Location(Token::CloseBrace, _)) => return Ok(WanderValue::Network(result)),
+            token => return Err(unexpected(token, tokens.end, &["`,`", "`}`"])),
         }
     }
-    return Ok(WanderValue::Network(result));
 }
 
-fn read_quote(gaze: &mut Gaze<Token>) -> Result<WanderValue, WanderError> {
-    let mut cont = true;
+/// Read a quote after its opening `(`, which is at the given Span.
+fn read_quote(tokens: &mut Tokens, open: Span) -> Result<WanderValue, Diagnostic> {
+    if tokens.depth > MAX_NESTING {
+        return Err(Diagnostic::new(
+            &format!("Quotes can't be nested more than {MAX_NESTING} deep."),
+            open,
+        ));
+    }
     let mut values: Vec<WanderValue> = vec![];
-    while cont {
-        match gaze.next() {
-            Some(Token::Element(element)) => {
+    loop {
+        match tokens.next() {
+            Some(Location(Token::Element(element), _)) => {
                 values.push(WanderValue::Element(element));
             }
-            Some(Token::CloseParen) => cont = false,
-            Some(Token::OpenBrace) => match read_network(gaze) {
-                Ok(res) => values.push(res),
-                Err(err) => return Err(err),
-            },
-            _ => todo!(),
+            Some(Location(Token::String(value), _)) => {
+                values.push(WanderValue::Element(Element(value, None, None)));
+            }
+            Some(Location(Token::Variable(name), _)) => values.push(WanderValue::Variable(name)),
+            Some(Location(Token::CloseParen, _)) => return Ok(WanderValue::Quote(Quote(values))),
+            Some(Location(Token::OpenBrace, _)) => values.push(read_network(tokens)?),
+            Some(Location(Token::OpenParen, span)) => values.push(read_quote(tokens, span)?),
+            token => {
+                return Err(unexpected(
+                    token,
+                    tokens.end,
+                    &["an Element", "a Variable", "`{`", "`(`", "`)`"],
+                ))
+            }
         };
     }
-    Ok(WanderValue::Quote(Quote(values)))
 }
@@ -2,76 +2,443 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{run_quote, Command, WanderError, WanderValue};
-use ligature::{Element, Ligature};
-use std::collections::{BTreeSet, HashMap};
+use crate::{
+    run_in, run_quote, Command, Definition, Environment, Quote, Table, WanderError, WanderType,
+    WanderValue,
+};
+use ligature::analytics::{
+    community_entries, component_entries, degree_entries, page_rank_entries,
+};
+use ligature::concepts::{closure, contradictions};
+use ligature::csv::{export_from, import_into, TableMapping};
+use ligature::paths::{follow, reachable, shortest_path, Path};
+use ligature::projection::{project, write_dot, write_gexf, write_graphml, Projection};
+use ligature::rules::{infer_into, Rule};
+use ligature::shapes::{read_shapes, report, to_error, validate, Violation};
+use ligature::{Element, Entry, Ligature};
+use hashbag::HashBag;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use trips::Aggregate;
 
 /// Creates a set of Bindings for Wander that consists of all of the common
 /// functionality, but doesn't interact with an instance of Ligature.
 pub fn common() -> HashMap<String, Command> {
     let mut commands: HashMap<String, Command> = HashMap::new();
-    // commands.insert("eq".to_owned(), Box::new(EqCommand {}));
+    commands.insert(
+        "eq".to_owned(),
+        Command {
+            doc: "Check if two values are equal. Returns the Element `true` or `false`.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(eq_command),
+        },
+    );
+    commands.insert(
+        "eval".to_owned(),
+        Command {
+            doc: "Run a quote and return its result.".to_owned(),
+            parameters: Some(vec![WanderType::Quote]),
+            fun: Box::new(eval_command),
+        },
+    );
+    commands.insert(
+        "apply".to_owned(),
+        Command {
+            doc: "Run a quote with the remaining arguments appended to it.".to_owned(),
+            parameters: None,
+            fun: Box::new(apply_command),
+        },
+    );
+    commands.insert(
+        "if".to_owned(),
+        Command {
+            doc: "Takes a condition and two values. Returns the first value if the condition is true and the second otherwise, running quotes. An empty network and the Element `false` are false, every other value is true.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any, WanderType::Any]),
+            fun: Box::new(if_command),
+        },
+    );
+    commands.insert(
+        "when".to_owned(),
+        Command {
+            doc: "Takes a condition and a value. Returns the value if the condition is true and an empty network otherwise, running quotes.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(when_command),
+        },
+    );
+    commands.insert(
+        "each".to_owned(),
+        Command {
+            doc: "Takes a quote and a network, quote, or collection. Runs the quote once for each Entry, passing the Entry as a network, and returns an empty network.".to_owned(),
+            parameters: Some(vec![WanderType::Quote, WanderType::Any]),
+            fun: Box::new(each_command),
+        },
+    );
+    commands.insert(
+        "map".to_owned(),
+        Command {
+            doc: "Takes a quote and a network, quote, or collection. Runs the quote once for each Entry, passing the Entry as a network, and returns the union of the resulting networks.".to_owned(),
+            parameters: Some(vec![WanderType::Quote, WanderType::Any]),
+            fun: Box::new(map_command),
+        },
+    );
+    commands.insert(
+        "reduce".to_owned(),
+        Command {
+            doc: "Takes a quote, an initial value, and a network, quote, or collection. Runs the quote once for each Entry, passing the result so far and the Entry as a network, and returns the last result.".to_owned(),
+            parameters: Some(vec![WanderType::Quote, WanderType::Any, WanderType::Any]),
+            fun: Box::new(reduce_command),
+        },
+    );
     commands.insert(
         "assert-equal".to_owned(),
         Command {
             doc: "Check if two arguments are equal (quotes are evaluated before comparing) and fail if they are not equal.".to_owned(),
-            fun: assert_equal_command,
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(assert_equal_command),
         },
     );
     commands.insert(
         "assert-fail".to_owned(),
         Command {
             doc: "Accept a quote and run it. If the quote results in a failure continue otherwise fail.".to_owned(),
-            fun: assert_fail_command,
+            parameters: Some(vec![WanderType::Quote]),
+            fun: Box::new(assert_fail_command),
         },
     );
     commands.insert(
         "ignore".to_owned(),
         Command {
             doc: "Ignore all arguments to this command and return an empty network.".to_owned(),
-            fun: ignore_command,
+            parameters: None,
+            fun: Box::new(ignore_command),
         },
     );
     commands.insert(
         "let".to_owned(),
         Command {
-            doc: "Set a named network.".to_owned(),
-            fun: let_command,
+            doc: "Bind a value to a variable in the current scope. Takes a name and a value, which is read as `$name`.".to_owned(),
+            parameters: Some(vec![WanderType::Element, WanderType::Any]),
+            fun: Box::new(let_command),
+        },
+    );
+    commands.insert(
+        "store".to_owned(),
+        Command {
+            doc: "Replace a collection in the store with a network, or the network that results from running a quote.".to_owned(),
+            parameters: Some(vec![WanderType::Element, WanderType::Any]),
+            fun: Box::new(store_command),
         },
     );
     commands.insert(
         "read".to_owned(),
         Command {
             doc: "Read a network.".to_owned(),
-            fun: read_command,
+            parameters: Some(vec![WanderType::Element]),
+            fun: Box::new(read_command),
         },
     );
     commands.insert(
         "id".to_owned(),
         Command {
             doc: "Return a value.".to_owned(),
-            fun: id_command,
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(id_command),
         },
     );
     commands.insert(
         "docs".to_owned(),
         Command {
             doc: "Get a list of commands and a description.".to_owned(),
-            fun: docs_command,
+            parameters: Some(vec![]),
+            fun: Box::new(docs_command),
         },
     );
     commands.insert(
         "union".to_owned(),
         Command {
-            doc: "Combine two networks.".to_owned(),
-            fun: union_command,
+            doc: "Combine two networks. Each argument can be a network, a quote, or the name of a collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(union_command),
+        },
+    );
+    commands.insert(
+        "difference".to_owned(),
+        Command {
+            doc: "Return the Entries of the first network that aren't in the second. Each argument can be a network, a quote, or the name of a collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(difference_command),
+        },
+    );
+    commands.insert(
+        "intersection".to_owned(),
+        Command {
+            doc: "Return the Entries found in both networks. Each argument can be a network, a quote, or the name of a collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(intersection_command),
+        },
+    );
+    commands.insert(
+        "symmetric-difference".to_owned(),
+        Command {
+            doc: "Return the Entries found in only one of two networks. Each argument can be a network, a quote, or the name of a collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(symmetric_difference_command),
+        },
+    );
+    commands.insert(
+        "is-subset".to_owned(),
+        Command {
+            doc: "Check if every Entry of the first network is in the second. Returns the Element `true` or `false`.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(is_subset_command),
+        },
+    );
+    commands.insert(
+        "count".to_owned(),
+        Command {
+            doc: "Return the number of Entries in a network, a quote's result, or a collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(count_command),
+        },
+    );
+    commands.insert(
+        "is-empty".to_owned(),
+        Command {
+            doc: "Check if a network, a quote's result, or a collection has no Entries. Returns the Element `true` or `false`.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(is_empty_command),
         },
     );
     commands.insert(
         "filter".to_owned(),
         Command {
-            doc: "Filter a network.".to_owned(),
-            fun: filter_command,
+            doc: "Filter a collection. Takes a collection and either a pattern written as three Elements or a network of patterns, and returns the Entries matching any pattern. `?` matches any Element.".to_owned(),
+            parameters: None,
+            fun: Box::new(filter_command),
+        },
+    );
+    commands.insert(
+        "query".to_owned(),
+        Command {
+            doc: "Query a collection. Takes a collection, a network of patterns, and optionally a template network. Elements starting with `?` are variables and `?` on its own matches anything. Returns the template, or the patterns, filled in with each match.".to_owned(),
+            parameters: None,
+            fun: Box::new(query_command),
+        },
+    );
+    commands.insert(
+        "bindings".to_owned(),
+        Command {
+            doc: "Query a collection and return a table with a row of variable bindings for each match. Takes a collection and a network of patterns.".to_owned(),
+            parameters: Some(vec![WanderType::Element, WanderType::Network]),
+            fun: Box::new(bindings_command),
+        },
+    );
+    commands.insert(
+        "to-table".to_owned(),
+        Command {
+            doc: "Read a network into a table with a row for each Element that has roles and a column for each role. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(to_table_command),
+        },
+    );
+    commands.insert(
+        "to-network".to_owned(),
+        Command {
+            doc: "Write a table as a network with a `rowN column value` Entry for each binding.".to_owned(),
+            parameters: Some(vec![WanderType::Table]),
+            fun: Box::new(to_network_command),
+        },
+    );
+    commands.insert(
+        "aggregate".to_owned(),
+        Command {
+            doc: "Query a collection and aggregate the results. Takes a collection, a network of patterns, a network of `?output function ?input` aggregates using count, min, max, or sum, and optionally a template. Results are grouped by the template's other variables, and the template is filled in once per group. Without a template the results aren't grouped and are returned as a table.".to_owned(),
+            parameters: None,
+            fun: Box::new(aggregate_command),
+        },
+    );
+    commands.insert(
+        "infer".to_owned(),
+        Command {
+            doc: "Add Entries to a collection and apply rules to what they imply until nothing new is inferred, adding the inferred Entries to the collection too. Takes a network, quote, or collection of Entries to add, the collection to add to, and a quote of rules written as pairs of networks, the premises followed by the conclusions. Returns the newly inferred Entries.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Element, WanderType::Quote]),
+            fun: Box::new(infer_command),
+        },
+    );
+    commands.insert(
+        "closure".to_owned(),
+        Command {
+            doc: "Return every Concept each Element extends, directly or through other Concepts, as Extends Entries. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(closure_command),
+        },
+    );
+    commands.insert(
+        "contradictions".to_owned(),
+        Command {
+            doc: "Return the NotExtends Entries that contradict the concept hierarchy, along with the Extends Entries leading to them. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(contradictions_command),
+        },
+    );
+    commands.insert(
+        "assert-consistent".to_owned(),
+        Command {
+            doc: "Fail if any NotExtends Entry contradicts the concept hierarchy. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(assert_consistent_command),
+        },
+    );
+    commands.insert(
+        "validate".to_owned(),
+        Command {
+            doc: "Check a network against a shapes network and return a report of `element violates constraint` Entries. Both arguments can be a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(validate_command),
+        },
+    );
+    commands.insert(
+        "assert-valid".to_owned(),
+        Command {
+            doc: "Fail if a network doesn't match a shapes network. Both arguments can be a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Any]),
+            fun: Box::new(assert_valid_command),
+        },
+    );
+    commands.insert(
+        "store-valid".to_owned(),
+        Command {
+            doc: "Store a network in a collection like `store`, but only if the collection would still match a shapes network. Takes a collection, shapes, and a network or quote.".to_owned(),
+            parameters: Some(vec![WanderType::Element, WanderType::Any, WanderType::Any]),
+            fun: Box::new(store_valid_command),
+        },
+    );
+    commands.insert(
+        "path".to_owned(),
+        Command {
+            doc: "Follow a path expression from an Element, or from every Element if given `?`. Returns `start path end` Entries. Takes a network, quote, or collection, a path, and a starting Element.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Element, WanderType::Element]),
+            fun: Box::new(path_command),
+        },
+    );
+    commands.insert(
+        "reachable".to_owned(),
+        Command {
+            doc: "Find every Element reached by following roles from an Element. Returns `start reaches end` Entries. Takes a network, quote, or collection and a starting Element.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Element]),
+            fun: Box::new(reachable_command),
+        },
+    );
+    commands.insert(
+        "shortest-path".to_owned(),
+        Command {
+            doc: "Return the fewest role Entries leading from one Element to another, or an empty network if there is no path. Takes a network, quote, or collection and two Elements.".to_owned(),
+            parameters: Some(vec![WanderType::Any, WanderType::Element, WanderType::Element]),
+            fun: Box::new(shortest_path_command),
+        },
+    );
+    commands.insert(
+        "degree".to_owned(),
+        Command {
+            doc: "Count the Entries entering and leaving each Element. Returns `element inDegree n` and `element outDegree n` Entries. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(degree_command),
+        },
+    );
+    commands.insert(
+        "components".to_owned(),
+        Command {
+            doc: "Group Elements that are connected, ignoring direction. Returns `element component n` Entries. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(components_command),
+        },
+    );
+    commands.insert(
+        "communities".to_owned(),
+        Command {
+            doc: "Group Elements into communities using label propagation. Returns `element community n` Entries. Takes a network, quote, or collection.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(communities_command),
+        },
+    );
+    commands.insert(
+        "page-rank".to_owned(),
+        Command {
+            doc: "Rank Elements with PageRank. Returns `element pageRank r` Entries. Takes a network, quote, or collection, and optionally a damping factor, which defaults to 0.85.".to_owned(),
+            parameters: None,
+            fun: Box::new(page_rank_command),
+        },
+    );
+    commands.insert(
+        "import-csv".to_owned(),
+        Command {
+            doc: "Import a CSV file into a collection. Takes a collection, a file path, a key column, optionally a concept for each row, and optionally a mapping of `column role Role` and `column : Concept` Entries.".to_owned(),
+            parameters: None,
+            fun: Box::new(import_csv_command),
+        },
+    );
+    commands.insert(
+        "import-tsv".to_owned(),
+        Command {
+            doc: "Import a TSV file into a collection. Takes a collection, a file path, a key column, optionally a concept for each row, and optionally a mapping of `column role Role` and `column : Concept` Entries.".to_owned(),
+            parameters: None,
+            fun: Box::new(import_tsv_command),
+        },
+    );
+    commands.insert(
+        "export-csv".to_owned(),
+        Command {
+            doc: "Export a collection as CSV. Takes a collection, the name of the key column, and optionally a mapping of `column role Role` and `column : Concept` Entries.".to_owned(),
+            parameters: None,
+            fun: Box::new(export_csv_command),
+        },
+    );
+    commands.insert(
+        "export-tsv".to_owned(),
+        Command {
+            doc: "Export a collection as TSV. Takes a collection, the name of the key column, and optionally a mapping of `column role Role` and `column : Concept` Entries.".to_owned(),
+            parameters: None,
+            fun: Box::new(export_tsv_command),
+        },
+    );
+    commands.insert(
+        "graphml".to_owned(),
+        Command {
+            doc: "Write a network or a named collection as GraphML.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(graphml_command),
+        },
+    );
+    commands.insert(
+        "gexf".to_owned(),
+        Command {
+            doc: "Write a network or a named collection as GEXF.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(gexf_command),
+        },
+    );
+    commands.insert(
+        "dot".to_owned(),
+        Command {
+            doc: "Write a network or a named collection as a Graphviz DOT digraph.".to_owned(),
+            parameters: Some(vec![WanderType::Any]),
+            fun: Box::new(dot_command),
+        },
+    );
+    commands.insert(
+        "define".to_owned(),
+        Command {
+            doc: "Define a command. Takes a name, a quote of parameter names, and a quote to run when the command is called.".to_owned(),
+            parameters: Some(vec![WanderType::Element, WanderType::Quote, WanderType::Quote]),
+            fun: Box::new(define_command),
+        },
+    );
+    commands.insert(
+        "import".to_owned(),
+        Command {
+            doc: "Run a Wander file and add the commands it defines. Takes a path, relative to the importing file when there is one, and optionally a prefix added to each name.".to_owned(),
+            parameters: None,
+            fun: Box::new(import_command),
         },
     );
     commands
@@ -80,16 +447,32 @@ pub fn common() -> HashMap<String, Command> {
 fn docs_command(
     args: Vec<WanderValue>,
     _: &mut dyn Ligature,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
     match &args[..] {
         [] => {
             let mut results = BTreeSet::new();
-            commands.iter().for_each(|(name, command)| {
+            environment.commands.iter().for_each(|(name, command)| {
                 results.insert(ligature::Entry::Role {
-                    first: Element(name.to_owned()),
-                    second: Element(command.doc.to_owned()),
-                    role: Element("docString".to_owned()),
+                    first: Element(name.to_owned(), None, None),
+                    second: Element(command.doc.to_owned(), None, None),
+                    role: Element("docString".to_owned(), None, None),
+                });
+            });
+            environment.definitions.iter().for_each(|(name, definition)| {
+                let parameters: Vec<String> = definition
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.0.clone())
+                    .collect();
+                results.insert(ligature::Entry::Role {
+                    first: Element(name.to_owned(), None, None),
+                    second: Element(
+                        format!("Defined in Wander with parameters ({}).", parameters.join(" ")),
+                        None,
+                        None,
+                    ),
+                    role: Element("docString".to_owned(), None, None),
                 });
             });
             Ok(WanderValue::Network(results))
@@ -98,34 +481,140 @@ fn docs_command(
     }
 }
 
-fn union_command(
-    args: Vec<WanderValue>,
+/// Get the network a value stands for.
+/// Quotes are run and Elements are read as collections.
+fn to_network(
+    value: &WanderValue,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+    name: &str,
+) -> Result<BTreeSet<Entry>, WanderError> {
+    match value {
+        WanderValue::Network(network) => Ok(network.clone()),
+        WanderValue::Element(collection) => {
+            state.entries(collection).map_err(|err| WanderError(err.0))
+        }
+        WanderValue::Table(table) => Ok(table.to_network()),
+        WanderValue::Quote(quote) => match run_quote(quote, environment, state)? {
+            WanderValue::Network(network) => Ok(network),
+            WanderValue::Table(table) => Ok(table.to_network()),
+            value => Err(WanderError(format!(
+                "`{name}` expected the quote to result in a network, but got {value}."
+            ))),
+        },
+        value => Err(WanderError(format!(
+            "`{name}` requires networks, tables, quotes, or collections, but got {value}."
+        ))),
+    }
+}
+
+/// Run a command that takes two networks.
+fn combine(
+    arguments: Vec<WanderValue>,
     state: &mut dyn Ligature,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
+    name: &str,
+    operation: fn(&BTreeSet<Entry>, &BTreeSet<Entry>) -> WanderValue,
 ) -> Result<WanderValue, WanderError> {
-    match &args[..] {
+    match &arguments[..] {
         [left, right] => {
-            let mut left = if let WanderValue::Network(left) = left {
-                left.clone()
-            } else {
-                todo!()
-            };
-            let mut right = if let WanderValue::Network(right) = right {
-                right.clone()
-            } else {
-                todo!()
-            };
-            left.append(&mut right);
-            Ok(WanderValue::Network(left.clone()))
+            let left = to_network(left, state, environment, name)?;
+            let right = to_network(right, state, environment, name)?;
+            Ok(operation(&left, &right))
+        }
+        _ => Err(WanderError(format!("`{name}` takes two arguments."))),
+    }
+}
+
+fn union_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    combine(arguments, state, environment, "union", |left, right| {
+        WanderValue::Network(left.union(right).cloned().collect())
+    })
+}
+
+fn difference_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    combine(arguments, state, environment, "difference", |left, right| {
+        WanderValue::Network(left.difference(right).cloned().collect())
+    })
+}
+
+fn intersection_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    combine(arguments, state, environment, "intersection", |left, right| {
+        WanderValue::Network(left.intersection(right).cloned().collect())
+    })
+}
+
+fn symmetric_difference_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    combine(
+        arguments,
+        state,
+        environment,
+        "symmetric-difference",
+        |left, right| WanderValue::Network(left.symmetric_difference(right).cloned().collect()),
+    )
+}
+
+fn is_subset_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    combine(arguments, state, environment, "is-subset", |left, right| {
+        boolean(left.is_subset(right))
+    })
+}
+
+fn count_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [network] => {
+            let network = to_network(network, state, environment, "count")?;
+            Ok(WanderValue::Element(Element(
+                network.len().to_string(),
+                None,
+                None,
+            )))
         }
-        _ => Err(WanderError("union takes two arguments.".to_owned())),
+        _ => Err(WanderError("`count` takes one argument.".to_owned())),
+    }
+}
+
+fn is_empty_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [network] => Ok(boolean(
+            to_network(network, state, environment, "is-empty")?.is_empty(),
+        )),
+        _ => Err(WanderError("`is-empty` takes one argument.".to_owned())),
     }
 }
 
 fn id_command(
     args: Vec<WanderValue>,
     _: &mut dyn Ligature,
-    _: &HashMap<String, Command>,
+    _: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
     match &args[..] {
         [value] => Ok(value.clone()),
@@ -136,7 +625,7 @@ fn id_command(
 fn ignore_command(
     _: Vec<WanderValue>,
     _: &mut dyn Ligature,
-    _: &HashMap<String, Command>,
+    _: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
     Ok(WanderValue::Network(BTreeSet::new()))
 }
@@ -144,11 +633,11 @@ fn ignore_command(
 fn assert_equal_command(
     arguments: Vec<WanderValue>,
     state: &mut dyn Ligature,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
     if let [left, right] = &arguments[..] {
         let left = if let WanderValue::Quote(quote) = left {
-            match run_quote(quote, commands, state) {
+            match run_quote(quote, environment, state) {
                 Ok(value) => value,
                 Err(err) => return Err(err),
             }
@@ -156,7 +645,7 @@ fn assert_equal_command(
             left.clone()
         };
         let right = if let WanderValue::Quote(quote) = right {
-            match run_quote(quote, commands, state) {
+            match run_quote(quote, environment, state) {
                 Ok(value) => value,
                 Err(err) => return Err(err),
             }
@@ -182,10 +671,10 @@ fn assert_equal_command(
 fn assert_fail_command(
     arguments: Vec<WanderValue>,
     state: &mut dyn Ligature,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
     if let [WanderValue::Quote(quote)] = &arguments[..] {
-        match run_quote(quote, commands, state) {
+        match run_quote(quote, environment, state) {
             Ok(_) => return Err(WanderError("Expected failure.".to_owned())),
             Err(_) => return Ok(WanderValue::Network(BTreeSet::new())),
         }
@@ -196,95 +685,1258 @@ fn assert_fail_command(
     }
 }
 
-fn let_command(
-    arguments: Vec<WanderValue>,
+fn boolean(value: bool) -> WanderValue {
+    WanderValue::Element(Element(value.to_string(), None, None))
+}
+
+/// Run a value if it is a quote, otherwise return it.
+fn evaluate(
+    value: &WanderValue,
     state: &mut dyn Ligature,
-    commands: &HashMap<String, Command>,
+    environment: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
-    match &arguments[..] {
-        [WanderValue::Element(name), WanderValue::Network(network)] => {
-            state.add_collection(name.clone());
-            state.add_entries(name.clone(), &mut network.clone());
-        }
-        [WanderValue::Element(name), WanderValue::Quote(quote)] => {
-            match run_quote(quote, commands, state) {
-                Ok(WanderValue::Network(res)) => {
-                    state.add_collection(name.clone());
-                    state.add_entries(name.clone(), &mut res.clone());
-                }
-                _ => todo!(),
-            }
-        }
-        _ => return Err(WanderError("Invalid call to let.".to_owned())),
+    match value {
+        WanderValue::Quote(quote) => run_quote(quote, environment, state),
+        value => Ok(value.clone()),
     }
-    Ok(WanderValue::Network(BTreeSet::new()))
 }
 
-fn read_command(
-    arguments: Vec<WanderValue>,
-    state: &mut dyn Ligature,
-    _: &HashMap<String, Command>,
-) -> Result<WanderValue, WanderError> {
-    match &arguments[..] {
-        [WanderValue::Element(name)] => match state.entries(name) {
-            Ok(entries) => return Ok(WanderValue::Network(entries)),
-            Err(err) => Err(WanderError(format!("Error {}", err.0))),
-        },
-        _ => todo!("Error"),
+/// An empty network and the Element `false` are false, every other value is true.
+fn is_true(value: &WanderValue) -> bool {
+    match value {
+        WanderValue::Network(network) => !network.is_empty(),
+        WanderValue::Element(Element(value, None, None)) => value != "false",
+        _ => true,
     }
 }
 
-fn filter_command(
+/// Run a quote with extra arguments appended to it.
+fn call_quote(
+    quote: &Quote,
     arguments: Vec<WanderValue>,
     state: &mut dyn Ligature,
-    _: &HashMap<String, Command>,
+    environment: &mut Environment,
 ) -> Result<WanderValue, WanderError> {
-    match &arguments[..] {
-        [WanderValue::Element(collection), WanderValue::Element(first), WanderValue::Element(second), WanderValue::Element(third)] => {
-            match state.filter(
-                collection.clone(),
-                ligature::Entry::Role {
+    let mut call = quote.0.clone();
+    call.extend(arguments);
+    run_quote(&Quote(call), environment, state)
+}
+
+fn eq_command(
+    arguments: Vec<WanderValue>,
+    _: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [left, right] => Ok(boolean(left == right)),
+        _ => Err(WanderError("`eq` requires two values.".to_owned())),
+    }
+}
+
+fn eval_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Quote(quote)] => run_quote(quote, environment, state),
+        _ => Err(WanderError("`eval` requires a quote.".to_owned())),
+    }
+}
+
+fn apply_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Quote(quote), arguments @ ..] => {
+            call_quote(quote, arguments.to_vec(), state, environment)
+        }
+        _ => Err(WanderError(
+            "`apply` requires a quote followed by its arguments.".to_owned(),
+        )),
+    }
+}
+
+fn if_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [condition, then, otherwise] => {
+            if is_true(&evaluate(condition, state, environment)?) {
+                evaluate(then, state, environment)
+            } else {
+                evaluate(otherwise, state, environment)
+            }
+        }
+        _ => Err(WanderError(
+            "`if` requires a condition and two values.".to_owned(),
+        )),
+    }
+}
+
+fn when_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [condition, then] => {
+            if is_true(&evaluate(condition, state, environment)?) {
+                evaluate(then, state, environment)
+            } else {
+                Ok(WanderValue::Network(BTreeSet::new()))
+            }
+        }
+        _ => Err(WanderError(
+            "`when` requires a condition and a value.".to_owned(),
+        )),
+    }
+}
+
+fn each_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Quote(quote), network] => {
+            for entry in to_network(network, state, environment, "each")? {
+                let entry = WanderValue::Network(BTreeSet::from([entry]));
+                call_quote(quote, vec![entry], state, environment)?;
+            }
+            Ok(WanderValue::Network(BTreeSet::new()))
+        }
+        _ => Err(WanderError(
+            "`each` requires a quote and a network, quote, or collection.".to_owned(),
+        )),
+    }
+}
+
+fn map_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Quote(quote), network] => {
+            let mut result: BTreeSet<Entry> = BTreeSet::new();
+            for entry in to_network(network, state, environment, "map")? {
+                let entry = WanderValue::Network(BTreeSet::from([entry]));
+                match call_quote(quote, vec![entry], state, environment)? {
+                    WanderValue::Network(mut network) => result.append(&mut network),
+                    value => {
+                        return Err(WanderError(format!(
+                            "`map` expected the quote to result in a network, but got {value}."
+                        )))
+                    }
+                }
+            }
+            Ok(WanderValue::Network(result))
+        }
+        _ => Err(WanderError(
+            "`map` requires a quote and a network, quote, or collection.".to_owned(),
+        )),
+    }
+}
+
+fn reduce_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Quote(quote), initial, network] => {
+            let mut result = initial.clone();
+            for entry in to_network(network, state, environment, "reduce")? {
+                let entry = WanderValue::Network(BTreeSet::from([entry]));
+                result = call_quote(quote, vec![result, entry], state, environment)?;
+            }
+            Ok(result)
+        }
+        _ => Err(WanderError(
+            "`reduce` requires a quote, an initial value, and a network, quote, or collection.".to_owned(),
+        )),
+    }
+}
+
+fn let_command(
+    arguments: Vec<WanderValue>,
+    _: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Element(name), value] => {
+            environment.bind(&name.0, value.clone());
+            Ok(WanderValue::Network(BTreeSet::new()))
+        }
+        _ => Err(WanderError(
+            "`let` requires a name and a value.".to_owned(),
+        )),
+    }
+}
+
+fn store_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    let (name, mut network) = match &arguments[..] {
+        [WanderValue::Element(name), WanderValue::Network(network)] => (name, network.clone()),
+        [WanderValue::Element(name), WanderValue::Quote(quote)] => {
+            match run_quote(quote, environment, state)? {
+                WanderValue::Network(network) => (name, network),
+                value => {
+                    return Err(WanderError(format!(
+                        "`store` expected the quote to result in a network, but got {value}."
+                    )))
+                }
+            }
+        }
+        _ => {
+            return Err(WanderError(
+                "`store` requires a name and a network or a quote.".to_owned(),
+            ))
+        }
+    };
+    state
+        .remove_collection(name.clone())
+        .and_then(|_| state.add_collection(name.clone()))
+        .and_then(|_| state.add_entries(name.clone(), &mut network))
+        .map_err(|err| WanderError(err.0))?;
+    Ok(WanderValue::Network(BTreeSet::new()))
+}
+
+fn read_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Element(name)] => match state.entries(name) {
+            Ok(entries) => return Ok(WanderValue::Network(entries)),
+            Err(err) => Err(WanderError(format!("Error {}", err.0))),
+        },
+        _ => todo!("Error"),
+    }
+}
+
+fn filter_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    let (collection, patterns) = match &arguments[..] {
+        [WanderValue::Element(collection), WanderValue::Element(first), WanderValue::Element(second), WanderValue::Element(third)] => {
+            let pattern = match second.0.as_str() {
+                ":" => Entry::Extends {
+                    element: first.clone(),
+                    concept: third.clone(),
+                },
+                "¬:" => Entry::NotExtends {
+                    element: first.clone(),
+                    concept: third.clone(),
+                },
+                _ => Entry::Role {
                     first: first.clone(),
                     second: third.clone(),
                     role: second.clone(),
                 },
-            ) {
-                Ok(entries) => return Ok(WanderValue::Network(entries)),
-                _ => todo!(),
+            };
+            (collection, BTreeSet::from([pattern]))
+        }
+        [WanderValue::Element(collection), WanderValue::Network(patterns)] => {
+            (collection, patterns.clone())
+        }
+        _ => {
+            return Err(WanderError(
+                "`filter` requires a collection and either three Elements or a network of patterns."
+                    .to_owned(),
+            ))
+        }
+    };
+    let mut result = BTreeSet::new();
+    for pattern in patterns {
+        let mut entries = state
+            .filter(collection.clone(), pattern)
+            .map_err(|err| WanderError(err.0))?;
+        result.append(&mut entries);
+    }
+    Ok(WanderValue::Network(result))
+}
+
+/// Rebuild an Entry with every Element replaced.
+fn map_entry(
+    entry: &Entry,
+    mut f: impl FnMut(&Element) -> Result<Element, WanderError>,
+) -> Result<Entry, WanderError> {
+    Ok(match entry {
+        Entry::Role {
+            first,
+            second,
+            role,
+        } => Entry::Role {
+            first: f(first)?,
+            second: f(second)?,
+            role: f(role)?,
+        },
+        Entry::Extends { element, concept } => Entry::Extends {
+            element: f(element)?,
+            concept: f(concept)?,
+        },
+        Entry::NotExtends { element, concept } => Entry::NotExtends {
+            element: f(element)?,
+            concept: f(concept)?,
+        },
+    })
+}
+
+fn query_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    let (collection, pattern, template) = match &arguments[..] {
+        [WanderValue::Element(collection), WanderValue::Network(pattern)] => {
+            (collection, pattern, None)
+        }
+        [WanderValue::Element(collection), WanderValue::Network(pattern), WanderValue::Network(template)] => {
+            (collection, pattern, Some(template))
+        }
+        _ => {
+            return Err(WanderError(
+                "`query` requires a collection, a network of patterns, and an optional template."
+                    .to_owned(),
+            ))
+        }
+    };
+    let patterns = number_wildcards(pattern)?;
+    let template = template.unwrap_or(&patterns);
+    let results = state
+        .query(collection.clone(), patterns.clone())
+        .map_err(|err| WanderError(err.0))?;
+    let mut network = BTreeSet::new();
+    for (bindings, _) in results.set_iter() {
+        fill_template(template, bindings, &mut network)?;
+    }
+    Ok(WanderValue::Network(network))
+}
+
+/// Give each `?` in a set of patterns its own variable so the matched Elements can be filled back in.
+fn number_wildcards(pattern: &BTreeSet<Entry>) -> Result<BTreeSet<Entry>, WanderError> {
+    let mut wildcards = 0;
+    let mut patterns = BTreeSet::new();
+    for entry in pattern {
+        patterns.insert(map_entry(entry, |element| {
+            if element.0 == "?" {
+                wildcards += 1;
+                Ok(Element(format!("??{wildcards}"), None, None))
+            } else {
+                Ok(element.clone())
             }
+        })?);
+    }
+    Ok(patterns)
+}
+
+/// Add a template to a network with its variables replaced by the Elements they are bound to.
+fn fill_template(
+    template: &BTreeSet<Entry>,
+    bindings: &BTreeMap<String, Element>,
+    network: &mut BTreeSet<Entry>,
+) -> Result<(), WanderError> {
+    for entry in template {
+        network.insert(map_entry(entry, |element| match element.0.strip_prefix('?') {
+            Some(name) if !name.is_empty() => bindings.get(name).cloned().ok_or(WanderError(
+                format!("Variable `{}` isn't in the query's patterns.", element.0),
+            )),
+            _ => Ok(element.clone()),
+        })?);
+    }
+    Ok(())
+}
+
+fn bindings_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Element(collection), WanderValue::Network(pattern)] => {
+            let results = state
+                .query(collection.clone(), number_wildcards(pattern)?)
+                .map_err(|err| WanderError(err.0))?;
+            // The variables given to each `?` aren't columns of the table.
+            let Table(mut rows) = Table::from(results);
+            for row in &mut rows {
+                row.retain(|name, _| !name.starts_with('?'));
+            }
+            rows.sort();
+            Ok(WanderValue::Table(Table(rows)))
         }
-        // match state.entries(name) {
-        //     Ok(entries) => return Ok(WanderValue::Network(entries)),
-        //     Err(err) => Err(WanderError(format!("Error {}", err.0))),
-        // },
-        _ => todo!("Error"),
+        _ => Err(WanderError(
+            "`bindings` requires a collection and a network of patterns.".to_owned(),
+        )),
     }
 }
 
-// pub struct EqCommand {}
-// impl<E> Command<E> for EqCommand {
-//     fn run(
-//         &self,
-//         arguments: &[WanderValue],
-//         _: &mut dyn Ligature<E>,
-//     ) -> Result<WanderValue, WanderError> {
-//         if let [left, right] = arguments {
-//             if left == right {
-//                 Ok(crate::WanderValue::Element(Element("true".to_owned())))
-//             } else {
-//                 Ok(crate::WanderValue::Element(Element("false".to_owned())))
-//             }
-//         } else {
-//             Err(WanderError(
-//                 "`eq` function requires two parameters.".to_owned(),
-//             ))
-//         }
-//     }
+fn to_table_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Table(table)] => Ok(WanderValue::Table(table.clone())),
+        [source] => {
+            let network = to_network(source, state, environment, "to-table")?;
+            Table::from_network(&network).map(WanderValue::Table)
+        }
+        _ => Err(WanderError("`to-table` takes one argument.".to_owned())),
+    }
+}
 
-//     fn doc(&self) -> String {
-//         "".to_owned()
-//     }
-// }
+fn to_network_command(
+    arguments: Vec<WanderValue>,
+    _: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Table(table)] => Ok(WanderValue::Network(table.to_network())),
+        _ => Err(WanderError("`to-network` requires a table.".to_owned())),
+    }
+}
+
+/// Read aggregates written as `?output function ?input` Entries.
+fn read_aggregates(network: &BTreeSet<Entry>) -> Result<BTreeMap<String, Aggregate>, WanderError> {
+    let mut aggregates = BTreeMap::new();
+    for entry in network {
+        let (output, function, input) = match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => (
+                first.0.strip_prefix('?'),
+                role.0.as_str(),
+                second.0.strip_prefix('?'),
+            ),
+            _ => (None, "", None),
+        };
+        let (Some(output), Some(input)) = (output, input) else {
+            return Err(WanderError(
+                "Aggregates must be written as `?output function ?input`.".to_owned(),
+            ));
+        };
+        let input = input.to_owned();
+        let aggregate = match function {
+            "count" => Aggregate::Count(input),
+            "min" => Aggregate::Min(input),
+            "max" => Aggregate::Max(input),
+            "sum" => Aggregate::Sum(input),
+            function => {
+                return Err(WanderError(format!(
+                    "Unknown aggregate `{function}`, expected count, min, max, or sum."
+                )))
+            }
+        };
+        aggregates.insert(output.to_owned(), aggregate);
+    }
+    Ok(aggregates)
+}
+
+/// The smallest or largest Element, compared by number when every value is a number
+/// and otherwise by value, with Namespaces and LangTags breaking ties.
+fn extreme<'a>(values: &[&'a Element], largest: bool) -> Option<&'a Element> {
+    let numbers: Option<Vec<f64>> = values.iter().map(|value| value.0.parse().ok()).collect();
+    let order = |a: &usize, b: &usize| {
+        let by_number = match &numbers {
+            Some(numbers) => numbers[*a]
+                .partial_cmp(&numbers[*b])
+                .unwrap_or(std::cmp::Ordering::Equal),
+            None => std::cmp::Ordering::Equal,
+        };
+        by_number.then_with(|| values[*a].cmp(values[*b]))
+    };
+    let indexes = 0..values.len();
+    let index = if largest {
+        indexes.max_by(order)
+    } else {
+        indexes.min_by(order)
+    };
+    index.map(|index| values[index])
+}
+
+/// Sum values as integers, or as floats if they aren't all integers or the sum overflows.
+fn sum(variable: &str, values: &[&Element]) -> Result<Element, WanderError> {
+    let integers: Option<Vec<i64>> = values.iter().map(|value| value.0.parse().ok()).collect();
+    if let Some(total) = integers.and_then(|integers| {
+        integers
+            .into_iter()
+            .try_fold(0i64, |total, value| total.checked_add(value))
+    }) {
+        return Ok(Element::new(total.to_string()));
+    }
+    let floats: Option<Vec<f64>> = values.iter().map(|value| value.0.parse().ok()).collect();
+    match floats {
+        Some(floats) => Ok(Element::new(floats.into_iter().sum::<f64>().to_string())),
+        None => Err(WanderError(format!(
+            "Can't sum `{variable}` because not all of its values are numbers."
+        ))),
+    }
+}
+
+/// Group query results by the given variables and compute aggregates over the Elements in each group.
+/// A min or max over a group without values is left out of its row,
+/// and without grouping variables there is always exactly one row.
+fn aggregate(
+    results: &HashBag<BTreeMap<String, Element>>,
+    group_by: &BTreeSet<String>,
+    aggregates: &BTreeMap<String, Aggregate>,
+) -> Result<Vec<BTreeMap<String, Element>>, WanderError> {
+    let mut groups: BTreeMap<BTreeMap<String, Element>, Vec<&BTreeMap<String, Element>>> =
+        BTreeMap::new();
+    if group_by.is_empty() {
+        groups.insert(BTreeMap::new(), vec![]);
+    }
+    for (bindings, count) in results.set_iter() {
+        let mut key = BTreeMap::new();
+        for variable in group_by {
+            match bindings.get(variable) {
+                Some(value) => {
+                    key.insert(variable.clone(), value.clone());
+                }
+                None => {
+                    return Err(WanderError(format!(
+                        "Can't group by `{variable}` because it isn't bound."
+                    )))
+                }
+            }
+        }
+        groups
+            .entry(key)
+            .or_default()
+            .extend(std::iter::repeat_n(bindings, count));
+    }
+    let mut rows = vec![];
+    for (mut row, members) in groups {
+        for (name, aggregate) in aggregates {
+            let variable = match aggregate {
+                Aggregate::Count(variable)
+                | Aggregate::Min(variable)
+                | Aggregate::Max(variable)
+                | Aggregate::Sum(variable) => variable,
+            };
+            let values: Vec<&Element> = members
+                .iter()
+                .filter_map(|bindings| bindings.get(variable))
+                .collect();
+            let value = match aggregate {
+                Aggregate::Count(_) => Some(Element::new(values.len().to_string())),
+                Aggregate::Min(_) => extreme(&values, false).cloned(),
+                Aggregate::Max(_) => extreme(&values, true).cloned(),
+                Aggregate::Sum(_) => Some(sum(variable, &values)?),
+            };
+            if let Some(value) = value {
+                row.insert(name.clone(), value);
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn aggregate_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    let (collection, pattern, aggregates, template) = match &arguments[..] {
+        [WanderValue::Element(collection), WanderValue::Network(pattern), WanderValue::Network(aggregates)] => {
+            (collection, pattern, aggregates, None)
+        }
+        [WanderValue::Element(collection), WanderValue::Network(pattern), WanderValue::Network(aggregates), WanderValue::Network(template)] => {
+            (collection, pattern, aggregates, Some(template))
+        }
+        _ => {
+            return Err(WanderError(
+                "`aggregate` requires a collection, a network of patterns, a network of aggregates, and an optional template."
+                    .to_owned(),
+            ))
+        }
+    };
+    let aggregates = read_aggregates(aggregates)?;
+    let group_by: BTreeSet<String> = template
+        .into_iter()
+        .flatten()
+        .flat_map(|entry| match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } => vec![first, role, second],
+            Entry::Extends { element, concept } | Entry::NotExtends { element, concept } => {
+                vec![element, concept]
+            }
+        })
+        .filter_map(|element| element.0.strip_prefix('?'))
+        .filter(|name| !name.is_empty() && !aggregates.contains_key(*name))
+        .map(str::to_owned)
+        .collect();
+    let results = state
+        .query(collection.clone(), number_wildcards(pattern)?)
+        .map_err(|err| WanderError(err.0))?;
+    let rows = aggregate(&results, &group_by, &aggregates)?;
+    match template {
+        Some(template) => {
+            let mut network = BTreeSet::new();
+            for bindings in &rows {
+                fill_template(template, bindings, &mut network)?;
+            }
+            Ok(WanderValue::Network(network))
+        }
+        None => Ok(WanderValue::Table(Table(rows))),
+    }
+}
+
+/// Read rules written as pairs of networks, the premises followed by the conclusions.
+fn read_rules(quote: &Quote) -> Result<Vec<Rule>, WanderError> {
+    let mut rules = vec![];
+    for pair in quote.0.chunks(2) {
+        match pair {
+            [WanderValue::Network(premises), WanderValue::Network(conclusions)] => rules.push(
+                Rule::new(premises.clone(), conclusions.clone())
+                    .map_err(|err| WanderError(err.0))?,
+            ),
+            _ => {
+                return Err(WanderError(
+                    "Rules must be written as pairs of networks, the premises followed by the conclusions."
+                        .to_owned(),
+                ))
+            }
+        }
+    }
+    Ok(rules)
+}
+
+fn infer_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source, WanderValue::Element(target), WanderValue::Quote(rules)] => {
+            let rules = read_rules(rules)?;
+            let entries = to_network(source, state, environment, "infer")?;
+            infer_into(state, target.clone(), &entries, &rules)
+                .map(WanderValue::Network)
+                .map_err(|err| WanderError(err.0))
+        }
+        _ => Err(WanderError(
+            "`infer` requires a source, a target collection, and a quote of rules.".to_owned(),
+        )),
+    }
+}
+
+fn closure_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source] => Ok(WanderValue::Network(closure(&to_network(
+            source,
+            state,
+            environment,
+            "closure",
+        )?))),
+        _ => Err(WanderError("`closure` takes one argument.".to_owned())),
+    }
+}
+
+fn contradictions_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source] => Ok(WanderValue::Network(contradictions(&to_network(
+            source,
+            state,
+            environment,
+            "contradictions",
+        )?))),
+        _ => Err(WanderError(
+            "`contradictions` takes one argument.".to_owned(),
+        )),
+    }
+}
+
+fn assert_consistent_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source] => {
+            let found = contradictions(&to_network(
+                source,
+                state,
+                environment,
+                "assert-consistent",
+            )?);
+            if found.is_empty() {
+                Ok(WanderValue::Network(BTreeSet::new()))
+            } else {
+                Err(WanderError(format!(
+                    "Found contradictions: {}",
+                    WanderValue::Network(found)
+                )))
+            }
+        }
+        _ => Err(WanderError(
+            "`assert-consistent` takes one argument.".to_owned(),
+        )),
+    }
+}
+
+fn violations(
+    source: &WanderValue,
+    shapes: &WanderValue,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+    name: &str,
+) -> Result<Vec<Violation>, WanderError> {
+    let source = to_network(source, state, environment, name)?;
+    let shapes = to_network(shapes, state, environment, name)?;
+    let constraints = read_shapes(&shapes).map_err(|err| WanderError(err.0))?;
+    Ok(validate(&source, &constraints))
+}
+
+fn validate_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source, shapes] => Ok(WanderValue::Network(report(&violations(
+            source,
+            shapes,
+            state,
+            environment,
+            "validate",
+        )?))),
+        _ => Err(WanderError("`validate` takes two arguments.".to_owned())),
+    }
+}
+
+fn assert_valid_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source, shapes] => {
+            let found = violations(source, shapes, state, environment, "assert-valid")?;
+            if found.is_empty() {
+                Ok(WanderValue::Network(BTreeSet::new()))
+            } else {
+                Err(WanderError(to_error(&found).0))
+            }
+        }
+        _ => Err(WanderError(
+            "`assert-valid` takes two arguments.".to_owned(),
+        )),
+    }
+}
+
+fn store_valid_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Element(name), shapes, network] => {
+            let mut network = to_network(network, state, environment, "store-valid")?;
+            let shapes = to_network(shapes, state, environment, "store-valid")?;
+            let constraints = read_shapes(&shapes).map_err(|err| WanderError(err.0))?;
+            let mut result = network.clone();
+            let exists = state
+                .collections()
+                .map_err(|err| WanderError(err.0))?
+                .contains(name);
+            if exists {
+                result.extend(state.entries(name).map_err(|err| WanderError(err.0))?);
+            }
+            let found = validate(&result, &constraints);
+            if !found.is_empty() {
+                return Err(WanderError(to_error(&found).0));
+            }
+            if !exists {
+                state
+                    .add_collection(name.clone())
+                    .map_err(|err| WanderError(err.0))?;
+            }
+            state
+                .add_entries(name.clone(), &mut network)
+                .map_err(|err| WanderError(err.0))?;
+            Ok(WanderValue::Network(BTreeSet::new()))
+        }
+        _ => Err(WanderError(
+            "`store-valid` requires a name, shapes, and a network or a quote.".to_owned(),
+        )),
+    }
+}
+
+fn path_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source, WanderValue::Element(expression), WanderValue::Element(start)] => {
+            let path = Path::parse(&expression.0).map_err(|err| WanderError(err.0))?;
+            let network = to_network(source, state, environment, "path")?;
+            let starts: BTreeSet<Element> = if start.0 == "?" {
+                network
+                    .iter()
+                    .flat_map(|entry| match entry {
+                        Entry::Role { first, second, .. } => vec![first.clone(), second.clone()],
+                        _ => vec![],
+                    })
+                    .collect()
+            } else {
+                BTreeSet::from([start.clone()])
+            };
+            let mut result = BTreeSet::new();
+            for start in starts {
+                for end in follow(&network, &path, &start) {
+                    result.insert(Entry::Role {
+                        first: start.clone(),
+                        second: end,
+                        role: expression.clone(),
+                    });
+                }
+            }
+            Ok(WanderValue::Network(result))
+        }
+        _ => Err(WanderError(
+            "`path` requires a network, a path, and a starting Element.".to_owned(),
+        )),
+    }
+}
+
+fn reachable_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source, WanderValue::Element(start)] => {
+            let network = to_network(source, state, environment, "reachable")?;
+            Ok(WanderValue::Network(
+                reachable(&network, start)
+                    .into_iter()
+                    .map(|end| Entry::Role {
+                        first: start.clone(),
+                        second: end,
+                        role: Element("reaches".to_owned(), None, None),
+                    })
+                    .collect(),
+            ))
+        }
+        _ => Err(WanderError(
+            "`reachable` requires a network and a starting Element.".to_owned(),
+        )),
+    }
+}
+
+fn shortest_path_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source, WanderValue::Element(from), WanderValue::Element(to)] => {
+            let network = to_network(source, state, environment, "shortest-path")?;
+            Ok(WanderValue::Network(
+                shortest_path(&network, from, to)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect(),
+            ))
+        }
+        _ => Err(WanderError(
+            "`shortest-path` requires a network and two Elements.".to_owned(),
+        )),
+    }
+}
+
+/// Run an analysis over the projection of a network.
+fn analyze(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+    name: &str,
+    analysis: fn(&Projection) -> BTreeSet<Entry>,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [source] => Ok(WanderValue::Network(analysis(&project(&to_network(
+            source,
+            state,
+            environment,
+            name,
+        )?)))),
+        _ => Err(WanderError(format!("`{name}` takes one argument."))),
+    }
+}
+
+fn degree_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    analyze(arguments, state, environment, "degree", degree_entries)
+}
+
+fn components_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    analyze(
+        arguments,
+        state,
+        environment,
+        "components",
+        component_entries,
+    )
+}
+
+fn communities_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    analyze(
+        arguments,
+        state,
+        environment,
+        "communities",
+        community_entries,
+    )
+}
+
+fn page_rank_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    let (source, damping) = match &arguments[..] {
+        [source] => (source, 0.85),
+        [source, WanderValue::Element(damping)] => match damping.0.parse::<f64>() {
+            Ok(damping) if (0.0..=1.0).contains(&damping) => (source, damping),
+            _ => {
+                return Err(WanderError(format!(
+                    "`page-rank` requires a damping factor between 0 and 1, but got {}.",
+                    damping.0
+                )))
+            }
+        },
+        _ => {
+            return Err(WanderError(
+                "`page-rank` requires a network and optionally a damping factor.".to_owned(),
+            ))
+        }
+    };
+    let network = to_network(source, state, environment, "page-rank")?;
+    Ok(WanderValue::Network(page_rank_entries(
+        &project(&network),
+        damping,
+    )))
+}
+
+/// Read a table mapping written as `column role Role` Entries, which map columns to roles,
+/// and a `column : Concept` Entry, which names the concept column.
+fn read_table_mapping(
+    network: &BTreeSet<Entry>,
+    mapping: &mut TableMapping,
+    name: &str,
+) -> Result<(), WanderError> {
+    for entry in network {
+        match entry {
+            Entry::Role {
+                first,
+                second,
+                role,
+            } if role.0 == "role" => {
+                mapping.columns.insert(first.0.clone(), second.clone());
+            }
+            Entry::Extends { element, concept }
+                if concept.0 == "Concept" && mapping.concept_column.is_none() =>
+            {
+                mapping.concept_column = Some(element.0.clone());
+            }
+            _ => {
+                return Err(WanderError(format!(
+                    "`{name}` mappings are written as `column role Role` and a single `column : Concept`."
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn import_table(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+    mut mapping: TableMapping,
+    name: &str,
+) -> Result<WanderValue, WanderError> {
+    let (collection, path, key, rest) = match &arguments[..] {
+        [WanderValue::Element(collection), WanderValue::Element(path), WanderValue::Element(key), rest @ ..] => {
+            (collection, path, key, rest)
+        }
+        _ => return Err(WanderError(format!(
+            "`{name}` requires a collection, a path, a key column, an optional concept, and an optional mapping."
+        ))),
+    };
+    mapping.key = key.0.clone();
+    let rest = match rest {
+        [WanderValue::Element(concept), rest @ ..] => {
+            mapping.concept = Some(concept.clone());
+            rest
+        }
+        rest => rest,
+    };
+    match rest {
+        [] => (),
+        [value] => read_table_mapping(
+            &to_network(value, state, environment, name)?,
+            &mut mapping,
+            name,
+        )?,
+        _ => return Err(WanderError(format!(
+            "`{name}` requires a collection, a path, a key column, an optional concept, and an optional mapping."
+        ))),
+    }
+    let input = std::fs::read_to_string(&path.0)
+        .map_err(|err| WanderError(format!("Could not read {}: {err}", path.0)))?;
+    import_into(state, collection.clone(), &input, &mapping)
+        .map_err(|err| WanderError(err.0))?;
+    Ok(WanderValue::Network(BTreeSet::new()))
+}
+
+fn import_csv_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    import_table(
+        arguments,
+        state,
+        environment,
+        TableMapping::csv(""),
+        "import-csv",
+    )
+}
+
+fn import_tsv_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    import_table(
+        arguments,
+        state,
+        environment,
+        TableMapping::tsv(""),
+        "import-tsv",
+    )
+}
+
+fn export_table(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+    mut mapping: TableMapping,
+    name: &str,
+) -> Result<WanderValue, WanderError> {
+    let collection = match &arguments[..] {
+        [WanderValue::Element(collection), WanderValue::Element(key)] => {
+            mapping.key = key.0.clone();
+            collection
+        }
+        [WanderValue::Element(collection), WanderValue::Element(key), value] => {
+            mapping.key = key.0.clone();
+            read_table_mapping(
+                &to_network(value, state, environment, name)?,
+                &mut mapping,
+                name,
+            )?;
+            collection
+        }
+        _ => {
+            return Err(WanderError(format!(
+                "`{name}` requires a collection, a key column, and an optional mapping."
+            )))
+        }
+    };
+    match export_from(state, collection, &mapping) {
+        Ok(table) => Ok(WanderValue::Element(Element(table, None, None))),
+        Err(err) => Err(WanderError(err.0)),
+    }
+}
+
+fn export_csv_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    export_table(
+        arguments,
+        state,
+        environment,
+        TableMapping::csv(""),
+        "export-csv",
+    )
+}
+
+fn export_tsv_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    export_table(
+        arguments,
+        state,
+        environment,
+        TableMapping::tsv(""),
+        "export-tsv",
+    )
+}
+
+fn write_graph(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    write: fn(&BTreeSet<ligature::Entry>) -> String,
+    name: &str,
+) -> Result<WanderValue, WanderError> {
+    let network = match &arguments[..] {
+        [WanderValue::Network(network)] => network.clone(),
+        [WanderValue::Element(collection)] => state
+            .entries(collection)
+            .map_err(|err| WanderError(err.0))?,
+        _ => {
+            return Err(WanderError(format!(
+                "`{name}` requires a network or a collection."
+            )))
+        }
+    };
+    Ok(WanderValue::Element(Element(write(&network), None, None)))
+}
+
+fn graphml_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    write_graph(arguments, state, write_graphml, "graphml")
+}
+
+fn gexf_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    write_graph(arguments, state, write_gexf, "gexf")
+}
+
+fn dot_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    _: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    write_graph(arguments, state, write_dot, "dot")
+}
+
+fn define_command(
+    arguments: Vec<WanderValue>,
+    _: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    match &arguments[..] {
+        [WanderValue::Element(name), WanderValue::Quote(parameters), WanderValue::Quote(body)] => {
+            if environment.commands.contains_key(&name.0) {
+                return Err(WanderError(format!(
+                    "`{}` is already a command and can't be redefined.",
+                    name.0
+                )));
+            }
+            let mut names = vec![];
+            for parameter in &parameters.0 {
+                match parameter {
+                    WanderValue::Element(parameter) => names.push(parameter.clone()),
+                    _ => {
+                        return Err(WanderError(
+                            "Parameters passed to `define` must be Elements.".to_owned(),
+                        ))
+                    }
+                }
+            }
+            environment.definitions.insert(
+                name.0.clone(),
+                Definition {
+                    parameters: names,
+                    body: body.clone(),
+                },
+            );
+            Ok(WanderValue::Network(BTreeSet::new()))
+        }
+        _ => Err(WanderError(
+            "`define` requires a name, a quote of parameters, and a quote.".to_owned(),
+        )),
+    }
+}
+
+fn import_command(
+    arguments: Vec<WanderValue>,
+    state: &mut dyn Ligature,
+    environment: &mut Environment,
+) -> Result<WanderValue, WanderError> {
+    let (path, prefix) = match &arguments[..] {
+        [WanderValue::Element(path)] => (path, String::new()),
+        [WanderValue::Element(path), WanderValue::Element(prefix)] => {
+            (path, format!("{}.", prefix.0))
+        }
+        _ => {
+            return Err(WanderError(
+                "`import` requires a path and an optional prefix.".to_owned(),
+            ))
+        }
+    };
+    let file = match environment.import_directory() {
+        Some(directory) => directory.join(&path.0),
+        None => PathBuf::from(&path.0),
+    };
+    let script = std::fs::read_to_string(&file)
+        .map_err(|err| WanderError(format!("Could not read {}: {err}", path.0)))?;
+    let file = file
+        .canonicalize()
+        .map_err(|err| WanderError(format!("Could not read {}: {err}", path.0)))?;
+    let mut module = environment.import(file)?;
+    run_in(&script, &mut module, state)
+        .map_err(|err| WanderError(format!("Error importing {}:\n{}", path.0, err.0)))?;
+    for (name, definition) in module.definitions {
+        environment
+            .definitions
+            .insert(prefix.clone() + &name, definition);
+    }
+    Ok(WanderValue::Network(BTreeSet::new()))
+}
 
 //TODO https://github.com/almibe/ligature-rs/issues/305
 // struct EnvironmentCommand {}
@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module defines tables of variable bindings, such as the results of a query.
+
+use crate::WanderError;
+use hashbag::HashBag;
+use ligature::json::{element_from_json, element_to_json};
+use ligature::{Element, Entry, LangTag, Namespace};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Rows of Elements bound to variables.
+/// A row can leave some of the table's columns unbound, and rows can repeat.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Table(
+    /// The rows, each mapping a variable's name without the `?` to its value.
+    pub Vec<BTreeMap<String, Element>>,
+);
+
+impl From<HashBag<BTreeMap<String, Element>>> for Table {
+    /// Collect query results into a table, repeating each row as many times as it occurs.
+    fn from(results: HashBag<BTreeMap<String, Element>>) -> Self {
+        let mut rows: Vec<BTreeMap<String, Element>> = results
+            .set_iter()
+            .flat_map(|(row, count)| std::iter::repeat_n(row.clone(), count))
+            .collect();
+        rows.sort();
+        Table(rows)
+    }
+}
+
+/// Write an Element as it is written in a table cell: as a JSON string with its Namespace prepended,
+/// followed by `@` and its LangTag if it has one.
+pub fn write_element(element: &Element) -> String {
+    let value = match &element.1 {
+        Some(Namespace(namespace)) => namespace.clone() + &element.0,
+        None => element.0.clone(),
+    };
+    match &element.2 {
+        Some(LangTag(tag)) => format!("{}@{tag}", Value::String(value)),
+        None => Value::String(value).to_string(),
+    }
+}
+
+/// Tables are written as `[?name value, ...]`, with the bindings of each row separated by spaces.
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, row) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            for (j, (name, value)) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "?{name} {}", write_element(value))?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+impl Table {
+    /// The names of every variable bound in any row.
+    pub fn columns(&self) -> BTreeSet<String> {
+        self.0.iter().flat_map(|row| row.keys().cloned()).collect()
+    }
+
+    /// Write this table as a JSON array with an object for each row.
+    /// Elements are written as in [ligature::json].
+    pub fn to_json(&self) -> Value {
+        Value::Array(
+            self.0
+                .iter()
+                .map(|row| {
+                    Value::Object(
+                        row.iter()
+                            .map(|(name, value)| (name.clone(), element_to_json(value)))
+                            .collect::<Map<String, Value>>(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Read a table written by [Table::to_json].
+    pub fn from_json(value: &Value) -> Result<Table, WanderError> {
+        let Value::Array(rows) = value else {
+            return Err(WanderError(format!(
+                "Expected an array of rows but found {value}."
+            )));
+        };
+        let mut result = vec![];
+        for row in rows {
+            let Value::Object(row) = row else {
+                return Err(WanderError(format!(
+                    "Expected a row object but found {row}."
+                )));
+            };
+            let mut bindings = BTreeMap::new();
+            for (name, value) in row {
+                bindings.insert(
+                    name.clone(),
+                    element_from_json(value).map_err(|err| WanderError(err.0))?,
+                );
+            }
+            result.push(bindings);
+        }
+        Ok(Table(result))
+    }
+
+    /// Write this table as a network, with an Element for each row named `row0`, `row1`, and so on,
+    /// and a `row column value` Entry for each binding.
+    /// Row numbers are padded with zeros so the rows keep their order when read back.
+    pub fn to_network(&self) -> BTreeSet<Entry> {
+        let width = self.0.len().saturating_sub(1).to_string().len();
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter().map(move |(name, value)| Entry::Role {
+                    first: Element::new(format!("row{i:0width$}")),
+                    second: value.clone(),
+                    role: Element::new(name.clone()),
+                })
+            })
+            .collect()
+    }
+
+    /// Read a table from a network, with a row for each Element that has roles
+    /// and a column for each role.
+    /// Fails if the network has Extends or NotExtends Entries or an Element has two values for a role.
+    pub fn from_network(network: &BTreeSet<Entry>) -> Result<Table, WanderError> {
+        let mut rows: BTreeMap<&Element, BTreeMap<String, Element>> = BTreeMap::new();
+        for entry in network {
+            match entry {
+                Entry::Role {
+                    first,
+                    second,
+                    role,
+                } => {
+                    if let Some(existing) = rows
+                        .entry(first)
+                        .or_default()
+                        .insert(role.0.clone(), second.clone())
+                    {
+                        return Err(WanderError(format!(
+                            "`{}` has more than one value for `{}`: {} and {}.",
+                            first.0, role.0, existing.0, second.0
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(WanderError(
+                        "Only role Entries can be read into a table.".to_owned(),
+                    ))
+                }
+            }
+        }
+        Ok(Table(rows.into_values().collect()))
+    }
+}
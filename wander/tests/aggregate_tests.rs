@@ -0,0 +1,99 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry, LangTag, Ligature, Namespace};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderError, WanderValue};
+
+fn run_on_people(script: &str) -> Result<WanderValue, WanderError> {
+    run(
+        &format!(
+            "store people {{ann worksAt acme, bob worksAt acme, cat worksAt initech, \
+            ann age 9, bob age 30, cat age 41}}, {script}"
+        ),
+        &common(),
+        &mut LigatureGraph::new(),
+    )
+}
+
+#[test]
+fn count_employees_per_company() {
+    assert_eq!(
+        run_on_people("aggregate people {?p worksAt ?company} {?n count ?p} {?company employees ?n}"),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("acme", "employees", "2"),
+            Entry::role("initech", "employees", "1"),
+        ])))
+    );
+}
+
+#[test]
+fn aggregate_over_everything() {
+    assert_eq!(
+        run_on_people(
+            "aggregate people {? age ?age} {?total sum ?age, ?oldest max ?age} \
+            {people totalAge ?total, people oldest ?oldest}"
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("people", "totalAge", "80"),
+            Entry::role("people", "oldest", "41"),
+        ])))
+    );
+}
+
+#[test]
+fn bad_aggregates() {
+    assert!(run_on_people("aggregate people {?p worksAt ?c} {?n average ?p} {?c n ?n}").is_err());
+    assert!(run_on_people("aggregate people {?p worksAt ?c} {n count ?p} {?c n n}").is_err());
+    assert!(run_on_people("aggregate people {?p worksAt ?c} {?s sum ?c} {x s ?s}").is_err());
+}
+
+#[test]
+fn aggregate_keeps_namespaces_and_lang_tags() {
+    let acme = Element(
+        "acme".to_owned(),
+        Some(Namespace("https://example.com/".to_owned())),
+        None,
+    );
+    let name = |tag: &str| Element("Ann".to_owned(), None, Some(LangTag(tag.to_owned())));
+    let mut ligature = LigatureGraph::new();
+    ligature.add_collection(Element::new("people")).unwrap();
+    ligature
+        .add_entries(
+            Element::new("people"),
+            &mut BTreeSet::from([
+                Entry::role("ann", "worksAt", acme.clone()),
+                Entry::role("ann", "name", name("fr")),
+                Entry::role("ann", "name", name("en")),
+                Entry::role("bob", "worksAt", "acme"),
+            ]),
+        )
+        .unwrap();
+    let res = run(
+        "aggregate people {?p worksAt ?company} {?n count ?p} {?company employees ?n}",
+        &common(),
+        &mut ligature,
+    );
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role(acme, "employees", "1"),
+            Entry::role("acme", "employees", "1"),
+        ])))
+    );
+    let res = run(
+        "aggregate people {ann name ?name} {?first min ?name, ?last max ?name} {ann first ?first, ann last ?last}",
+        &common(),
+        &mut ligature,
+    );
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("ann", "first", name("en")),
+            Entry::role("ann", "last", name("fr")),
+        ])))
+    );
+}
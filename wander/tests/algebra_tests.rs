@@ -0,0 +1,127 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use wander::prelude::common;
+use wander::{run, WanderError, WanderValue};
+
+fn network(entries: &[Entry]) -> Result<WanderValue, WanderError> {
+    Ok(WanderValue::Network(entries.iter().cloned().collect()))
+}
+
+#[test]
+fn union_of_networks() {
+    assert_eq!(
+        run(
+            "union {a b c} {d e f}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        network(&[Entry::role("a", "b", "c"), Entry::role("d", "e", "f")])
+    );
+}
+
+#[test]
+fn difference_of_networks() {
+    assert_eq!(
+        run(
+            "difference {a b c, d e f} {d e f, g h i}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        network(&[Entry::role("a", "b", "c")])
+    );
+}
+
+#[test]
+fn intersection_of_networks() {
+    assert_eq!(
+        run(
+            "intersection {a b c, d e f} {d e f, g h i}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        network(&[Entry::role("d", "e", "f")])
+    );
+}
+
+#[test]
+fn symmetric_difference_of_networks() {
+    assert_eq!(
+        run(
+            "symmetric-difference {a b c, d e f} {d e f, g h i}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        network(&[Entry::role("a", "b", "c"), Entry::role("g", "h", "i")])
+    );
+}
+
+#[test]
+fn subsets() {
+    assert_eq!(
+        run(
+            "is-subset {a b c} {a b c, d e f}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("true")))
+    );
+    assert_eq!(
+        run(
+            "is-subset {a b c, d e f} {a b c}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("false")))
+    );
+}
+
+#[test]
+fn count_and_is_empty() {
+    assert_eq!(
+        run("count {a b c, d e f}", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("2")))
+    );
+    assert_eq!(
+        run("is-empty {}", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("true")))
+    );
+    assert_eq!(
+        run("is-empty {a b c}", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("false")))
+    );
+}
+
+#[test]
+fn arguments_can_be_quotes_or_collections() {
+    let script = "store old {a b c, d e f}, store new {d e f, g h i}, difference new (read old)";
+    assert_eq!(
+        run(script, &common(), &mut LigatureGraph::new()),
+        network(&[Entry::role("g", "h", "i")])
+    );
+    assert_eq!(
+        run(
+            "store old {a b c}, count old",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("1")))
+    );
+    assert_eq!(
+        run(
+            "intersection (union {a b c} {d e f}) {a b c}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        network(&[Entry::role("a", "b", "c")])
+    );
+}
+
+#[test]
+fn invalid_arguments_fail() {
+    assert!(run("union {a b c} (id x)", &common(), &mut LigatureGraph::new()).is_err());
+    assert!(run("count", &common(), &mut LigatureGraph::new()).is_err());
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::Entry;
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderValue};
+
+#[test]
+fn degree_of_a_collection() {
+    assert_eq!(
+        run(
+            "store people {alice knows bob}, degree people",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("alice", "inDegree", "0"),
+            Entry::role("alice", "outDegree", "1"),
+            Entry::role("bob", "inDegree", "1"),
+            Entry::role("bob", "outDegree", "0"),
+        ])))
+    );
+}
+
+#[test]
+fn components_can_be_stored() {
+    assert_eq!(
+        run(
+            "store results (components {a b c, d e f}), read results",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("a", "component", "0"),
+            Entry::role("c", "component", "0"),
+            Entry::role("d", "component", "1"),
+            Entry::role("f", "component", "1"),
+        ])))
+    );
+}
+
+#[test]
+fn page_rank_with_damping() {
+    assert_eq!(
+        run(
+            "page-rank {a b c, c b a} 0.5",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("a", "pageRank", "0.500000"),
+            Entry::role("c", "pageRank", "0.500000"),
+        ])))
+    );
+    assert!(run("page-rank {a b c} 2", &common(), &mut LigatureGraph::new()).is_err());
+}
+
+#[test]
+fn communities_of_a_network() {
+    assert_eq!(
+        run(
+            "communities {a b c, d e f}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("a", "community", "0"),
+            Entry::role("c", "community", "0"),
+            Entry::role("d", "community", "1"),
+            Entry::role("f", "community", "1"),
+        ])))
+    );
+}
@@ -0,0 +1,61 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::Element;
+use ligature_graph::LigatureGraph;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use wander::prelude::common;
+use wander::{run, Command, WanderType, WanderValue};
+
+#[test]
+fn commands_capture_host_state() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let captured = log.clone();
+    let mut commands = common();
+    commands.insert(
+        "log".to_owned(),
+        Command {
+            doc: "Log a value.".to_owned(),
+            parameters: Some(vec![WanderType::Element]),
+            fun: Box::new(move |arguments, _, _| {
+                captured.borrow_mut().push(arguments[0].clone());
+                Ok(WanderValue::Network(BTreeSet::new()))
+            }),
+        },
+    );
+    let res = run("log a, log b", &commands, &mut LigatureGraph::new());
+    assert!(res.is_ok());
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            WanderValue::Element(Element::new("a")),
+            WanderValue::Element(Element::new("b"))
+        ]
+    );
+}
+
+#[test]
+fn arguments_are_checked() {
+    let commands = common();
+    let mut state = LigatureGraph::new();
+    let res = run("id", &commands, &mut state).unwrap_err();
+    assert!(res.0.starts_with("`id` takes 1 arguments but was given 0."));
+    let res = run("each x {}", &commands, &mut state).unwrap_err();
+    assert!(res
+        .0
+        .starts_with("Argument 1 of `each` must be a Quote but was \"x\"."));
+    let res = run("let x {}, union {} $x", &commands, &mut state);
+    assert_eq!(res, Ok(WanderValue::Network(BTreeSet::new())));
+}
+
+#[test]
+fn types_accept_values() {
+    let network = WanderValue::Network(BTreeSet::new());
+    assert!(WanderType::Any.accepts(&network));
+    assert!(WanderType::Network.accepts(&network));
+    assert!(!WanderType::Element.accepts(&network));
+    assert!(!WanderType::Quote.accepts(&network));
+}
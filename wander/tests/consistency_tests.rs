@@ -0,0 +1,62 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderValue};
+
+#[test]
+fn closure_of_a_collection() {
+    assert_eq!(
+        run(
+            "store animals {rex : Dog, Dog : Animal}, closure animals",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::extends("rex", "Dog"),
+            Entry::extends("rex", "Animal"),
+            Entry::extends("Dog", "Animal"),
+        ])))
+    );
+}
+
+#[test]
+fn contradictions_in_a_network() {
+    assert_eq!(
+        run(
+            "contradictions {rex : Dog, Dog : Animal, rex ¬: Animal, rex ¬: Cat}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::extends("rex", "Dog"),
+            Entry::extends("Dog", "Animal"),
+            Entry::NotExtends {
+                element: Element::new("rex"),
+                concept: Element::new("Animal"),
+            },
+        ])))
+    );
+}
+
+#[test]
+fn assert_consistent() {
+    assert_eq!(
+        run(
+            "assert-consistent {rex : Dog, rex ¬: Cat}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::new()))
+    );
+    assert!(run(
+        "assert-consistent {rex : Dog, rex ¬: Dog}",
+        &common(),
+        &mut LigatureGraph::new()
+    )
+    .is_err());
+}
@@ -0,0 +1,172 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderValue};
+
+#[test]
+fn eq_compares_values() {
+    assert_eq!(
+        run("eq a a", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("true")))
+    );
+    assert_eq!(
+        run("eq {a b c} {a b d}", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("false")))
+    );
+}
+
+#[test]
+fn if_chooses_a_branch() {
+    assert_eq!(
+        run("if (eq a a) yes no", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("yes")))
+    );
+    assert_eq!(
+        run("if (eq a b) yes no", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("no")))
+    );
+    assert_eq!(
+        run(
+            "if {} (id yes) (id no)",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("no")))
+    );
+    assert_eq!(
+        run(
+            "let x {a b c}, if $x (id yes) (assert-fail)",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("yes")))
+    );
+}
+
+#[test]
+fn if_only_runs_the_chosen_branch() {
+    let res = run(
+        "if false (read missing) (id no)",
+        &common(),
+        &mut LigatureGraph::new(),
+    );
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("no"))));
+}
+
+#[test]
+fn when_runs_on_true() {
+    assert_eq!(
+        run(
+            "when (eq a a) {a b c}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([Entry::role(
+            "a", "b", "c"
+        )])))
+    );
+    assert_eq!(
+        run("when {} {a b c}", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Network(BTreeSet::new()))
+    );
+}
+
+#[test]
+fn each_runs_for_every_entry() {
+    let res = run(
+        "each (store out) {a b c, d e f}, read out",
+        &common(),
+        &mut LigatureGraph::new(),
+    );
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([Entry::role(
+            "d", "e", "f"
+        )])))
+    );
+}
+
+#[test]
+fn map_unions_results() {
+    let res = run(
+        "id {a b c, d e f} | map (union {x y z})",
+        &common(),
+        &mut LigatureGraph::new(),
+    );
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("a", "b", "c"),
+            Entry::role("d", "e", "f"),
+            Entry::role("x", "y", "z"),
+        ])))
+    );
+    assert!(run("map (id a) {a b c}", &common(), &mut LigatureGraph::new()).is_err());
+}
+
+#[test]
+fn reduce_accumulates() {
+    let res = run(
+        "reduce (union) {x y z} {a b c, d e f}",
+        &common(),
+        &mut LigatureGraph::new(),
+    );
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("a", "b", "c"),
+            Entry::role("d", "e", "f"),
+            Entry::role("x", "y", "z"),
+        ])))
+    );
+    assert_eq!(
+        run("reduce (union) {} {}", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Network(BTreeSet::new()))
+    );
+}
+
+#[test]
+fn loops_over_collections_and_quotes() {
+    let mut ligature = LigatureGraph::new();
+    let res = run(
+        "store in {a b c, d e f}, each (store out) in, read out",
+        &common(),
+        &mut ligature,
+    );
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([Entry::role(
+            "d", "e", "f"
+        )])))
+    );
+    let expected = Ok(WanderValue::Network(BTreeSet::from([
+        Entry::role("a", "b", "c"),
+        Entry::role("d", "e", "f"),
+        Entry::role("x", "y", "z"),
+    ])));
+    assert_eq!(
+        run("map (union {x y z}) in", &common(), &mut ligature),
+        expected
+    );
+    assert_eq!(
+        run("reduce (union) {x y z} (read in)", &common(), &mut ligature),
+        expected
+    );
+}
+
+#[test]
+fn loops_with_definitions() {
+    let script = "define tag (entry) (union {tagged a b} entry), map (tag) {a b c}";
+    assert_eq!(
+        run(script, &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("a", "b", "c"),
+            Entry::role("tagged", "a", "b"),
+        ])))
+    );
+}
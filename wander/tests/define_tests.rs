@@ -0,0 +1,137 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, run_in, Environment, WanderValue};
+
+#[test]
+fn call_definition() {
+    let script = "define second (a b) (id b), second x y";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("y"))));
+}
+
+#[test]
+fn parameters_are_replaced_in_networks() {
+    let script = "define knows (a b) (union {a knows b} {b : Person}), knows alice bob";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    let expected = BTreeSet::from([
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("bob"),
+            role: Element::new("knows"),
+        },
+        Entry::Extends {
+            element: Element::new("bob"),
+            concept: Element::new("Person"),
+        },
+    ]);
+    assert_eq!(res, Ok(WanderValue::Network(expected)));
+}
+
+#[test]
+fn definitions_can_be_piped_to() {
+    let script = "define wrap (value) (id value), id x | wrap";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("x"))));
+}
+
+#[test]
+fn parameters_can_name_commands() {
+    let script = "define apply-twice (f x) (f (f x)), apply-twice (union {a b c}) {d e f}";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    let expected = BTreeSet::from([Entry::role("a", "b", "c"), Entry::role("d", "e", "f")]);
+    assert_eq!(res, Ok(WanderValue::Network(expected)));
+    let script = "define second (a b) (id b), define call (f x y) (f x y), call second x y";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("y"))));
+}
+
+#[test]
+fn definition_errors() {
+    let commands = common();
+    let mut state = LigatureGraph::new();
+    assert!(run("define id (a) (id a)", &commands, &mut state).is_err());
+    assert!(run("define f (a) (id a), f", &commands, &mut state).is_err());
+    assert!(run("define f ({}) (id a)", &commands, &mut state).is_err());
+    let res = run("define loop () (loop), loop", &commands, &mut state).unwrap_err();
+    assert!(res.0.contains("exceeded the maximum depth"));
+}
+
+#[test]
+fn definitions_are_scoped_to_an_environment() {
+    let commands = common();
+    let mut state = LigatureGraph::new();
+    let mut environment = Environment::new(&commands);
+    assert!(run_in("define f () (id x)", &mut environment, &mut state).is_ok());
+    assert_eq!(
+        run_in("f", &mut environment, &mut state),
+        Ok(WanderValue::Element(Element::new("x")))
+    );
+    assert!(run("f", &commands, &mut state).is_err());
+}
+
+#[test]
+fn import_definitions() {
+    let path = std::env::temp_dir().join(format!(
+        "wander_import_definitions_{}.wander",
+        std::process::id()
+    ));
+    std::fs::write(&path, "define first (a b) (id a)").unwrap();
+    let script = format!(
+        "import \"{0}\", import \"{0}\" lib, lib.first x y | first z",
+        path.display()
+    );
+    let res = run(&script, &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("z"))));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn import_relative_to_the_importing_file() {
+    let directory =
+        std::env::temp_dir().join(format!("wander_import_relative_{}", std::process::id()));
+    std::fs::create_dir_all(directory.join("lib")).unwrap();
+    std::fs::write(
+        directory.join("main.wander"),
+        "import \"lib/second.wander\", define first (a b) (second a b)",
+    )
+    .unwrap();
+    std::fs::write(
+        directory.join("lib").join("second.wander"),
+        "import \"third.wander\", define second (a b) (third a b)",
+    )
+    .unwrap();
+    std::fs::write(
+        directory.join("lib").join("third.wander"),
+        "define third (a b) (id b)",
+    )
+    .unwrap();
+    let script = format!(
+        "import \"{}\", first x y",
+        directory.join("main.wander").display()
+    );
+    let res = run(&script, &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("y"))));
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn import_cycles_are_an_error() {
+    let directory =
+        std::env::temp_dir().join(format!("wander_import_cycles_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("self.wander"), "import \"self.wander\"").unwrap();
+    std::fs::write(directory.join("a.wander"), "import \"b.wander\"").unwrap();
+    std::fs::write(directory.join("b.wander"), "import \"a.wander\"").unwrap();
+    for file in ["self.wander", "a.wander"] {
+        let script = format!("import \"{}\"", directory.join(file).display());
+        let res = run(&script, &common(), &mut LigatureGraph::new());
+        assert!(res.unwrap_err().0.contains("Import cycle"));
+    }
+    std::fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,78 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::Entry;
+use ligature_graph::LigatureGraph;
+use wander::prelude::common;
+use wander::{run, WanderError, WanderValue};
+
+const PEOPLE: &str =
+    "store people {alice knows bob, alice : Person, bob : Person, rex : Dog, rex ¬: Person}";
+
+fn run_filter(filter: &str) -> Result<WanderValue, WanderError> {
+    run(
+        &format!("{PEOPLE}, {filter}"),
+        &common(),
+        &mut LigatureGraph::new(),
+    )
+}
+
+fn network(entries: &[Entry]) -> Result<WanderValue, WanderError> {
+    Ok(WanderValue::Network(entries.iter().cloned().collect()))
+}
+
+#[test]
+fn filter_roles() {
+    assert_eq!(
+        run_filter("filter people alice knows ?"),
+        network(&[Entry::role("alice", "knows", "bob")])
+    );
+}
+
+#[test]
+fn wildcard_roles_match_every_entry() {
+    assert_eq!(
+        run_filter("filter people alice ? ?"),
+        network(&[
+            Entry::role("alice", "knows", "bob"),
+            Entry::extends("alice", "Person")
+        ])
+    );
+}
+
+#[test]
+fn filter_extends() {
+    assert_eq!(
+        run_filter("filter people ? : Person"),
+        network(&[
+            Entry::extends("alice", "Person"),
+            Entry::extends("bob", "Person")
+        ])
+    );
+}
+
+#[test]
+fn filter_not_extends() {
+    assert_eq!(
+        run_filter("filter people ? ¬: ?"),
+        network(&[Entry::not_extends("rex", "Person")])
+    );
+}
+
+#[test]
+fn filter_with_a_network_of_patterns() {
+    assert_eq!(
+        run_filter("filter people {? : Dog, ? knows bob}"),
+        network(&[
+            Entry::role("alice", "knows", "bob"),
+            Entry::extends("rex", "Dog")
+        ])
+    );
+}
+
+#[test]
+fn filter_with_invalid_arguments() {
+    assert!(run_filter("filter people alice").is_err());
+    assert!(run_filter("filter missing ? ? ?").is_err());
+}
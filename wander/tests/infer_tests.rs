@@ -0,0 +1,68 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry, Ligature};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderValue};
+
+const RULES: &str = "({?a : ?b, ?b : ?c} {?a : ?c})";
+
+#[test]
+fn infer_into_a_collection() {
+    let mut state = LigatureGraph::new();
+    let script = format!(
+        "store animals {{rex : Dog, Dog : Mammal, Mammal : Animal}}, infer animals inferred {RULES}"
+    );
+    let expected = BTreeSet::from([
+        Entry::extends("rex", "Mammal"),
+        Entry::extends("rex", "Animal"),
+        Entry::extends("Dog", "Animal"),
+    ]);
+    let res = run(&script, &common(), &mut state);
+    assert_eq!(res, Ok(WanderValue::Network(expected.clone())));
+    let mut stored = expected;
+    stored.extend([
+        Entry::extends("rex", "Dog"),
+        Entry::extends("Dog", "Mammal"),
+        Entry::extends("Mammal", "Animal"),
+    ]);
+    assert_eq!(state.entries(&Element::new("inferred")), Ok(stored));
+}
+
+#[test]
+fn infer_incrementally() {
+    let mut state = LigatureGraph::new();
+    let script = format!(
+        "infer {{rex : Dog, Dog : Mammal}} inferred {RULES}, infer {{Mammal : Animal}} inferred {RULES}"
+    );
+    let res = run(&script, &common(), &mut state);
+    assert_eq!(
+        res,
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::extends("rex", "Animal"),
+            Entry::extends("Dog", "Animal"),
+        ])))
+    );
+    assert_eq!(
+        state
+            .entries(&Element::new("inferred"))
+            .map(|entries| entries.len()),
+        Ok(6)
+    );
+}
+
+#[test]
+fn infer_with_invalid_rules() {
+    let mut state = LigatureGraph::new();
+    assert!(run("infer {} inferred ({?a : ?b})", &common(), &mut state).is_err());
+    assert!(run(
+        "infer {} inferred ({?a : ?b} {?a : ?c})",
+        &common(),
+        &mut state
+    )
+    .is_err());
+    assert!(run("infer {} inferred (a b)", &common(), &mut state).is_err());
+}
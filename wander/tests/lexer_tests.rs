@@ -4,6 +4,7 @@
 
 use ligature::Element;
 use wander::lexer::{tokenize_and_filter, Token};
+use wander::{Location, Span};
 
 #[test]
 fn tokenize_true() {
@@ -11,9 +12,9 @@ fn tokenize_true() {
     let res: Vec<Token> = tokenize_and_filter(input)
         .unwrap()
         .iter()
-        .map(|t| t.clone())
+        .map(|Location(t, _)| t.clone())
         .collect();
-    let expected = vec![Token::Element(Element("true".to_owned()))];
+    let expected = vec![Token::Element(Element("true".to_owned(), None, None))];
     assert_eq!(res, expected);
 }
 
@@ -23,12 +24,12 @@ fn tokenize_booleans() {
     let res: Vec<Token> = tokenize_and_filter(input)
         .unwrap()
         .iter()
-        .map(|t| t.clone())
+        .map(|Location(t, _)| t.clone())
         .collect();
     let expected = vec![
-        Token::Element(Element("true".to_owned())),
-        Token::Element(Element("false".to_owned())),
-        Token::Element(Element("false".to_string())),
+        Token::Element(Element("true".to_owned(), None, None)),
+        Token::Element(Element("false".to_owned(), None, None)),
+        Token::Element(Element("false".to_string(), None, None)),
     ];
     assert_eq!(res, expected);
 }
@@ -38,9 +39,9 @@ fn tokenize_integer() {
     let input = "123450";
     let res: Vec<Token> = tokenize_and_filter(input)
         .iter()
-        .map(|t| t.first().unwrap().clone())
+        .map(|t| t.first().unwrap().0.clone())
         .collect();
-    let expected = vec![Token::Element(Element("123450".to_owned()))];
+    let expected = vec![Token::Element(Element("123450".to_owned(), None, None))];
     assert_eq!(res, expected);
 }
 
@@ -50,12 +51,12 @@ fn tokenize_integers() {
     let res: Vec<_> = tokenize_and_filter(input)
         .unwrap()
         .iter()
-        .map(|t| t.clone())
+        .map(|Location(t, _)| t.clone())
         .collect();
     let expected = vec![
-        Token::Element(Element("0".to_owned())),
-        Token::Element(Element("-100".to_owned())),
-        Token::Element(Element("4200".to_owned())),
+        Token::Element(Element("0".to_owned(), None, None)),
+        Token::Element(Element("-100".to_owned(), None, None)),
+        Token::Element(Element("4200".to_owned(), None, None)),
     ];
     assert_eq!(res, expected);
 }
@@ -63,24 +64,24 @@ fn tokenize_integers() {
 #[test]
 fn tokenize_strings() {
     let input = "\"Hello, world\"";
-    let res = tokenize_and_filter(input).unwrap().first().unwrap().clone();
-    let expected = Token::Element(Element("Hello, world".to_owned()));
+    let res = tokenize_and_filter(input).unwrap().first().unwrap().0.clone();
+    let expected = Token::Element(Element("Hello, world".to_owned(), None, None));
     assert_eq!(res, expected);
 }
 
 #[test]
 fn tokenize_strings_with_quotes() {
     let input = "\"\\\"Hello, world\\\"\"";
-    let res = tokenize_and_filter(input).unwrap().first().unwrap().clone();
-    let expected = Token::Element(Element(String::from("\\\"Hello, world\\\"")));
+    let res = tokenize_and_filter(input).unwrap().first().unwrap().0.clone();
+    let expected = Token::Element(Element(String::from("\\\"Hello, world\\\""), None, None));
     assert_eq!(res, expected);
 }
 
 #[test]
 fn tokenize_name() {
     let input = "hello123";
-    let res = tokenize_and_filter(input).unwrap().first().unwrap().clone();
-    let expected = Token::Element(Element("hello123".to_owned()));
+    let res = tokenize_and_filter(input).unwrap().first().unwrap().0.clone();
+    let expected = Token::Element(Element("hello123".to_owned(), None, None));
     assert_eq!(res, expected);
 }
 
@@ -90,11 +91,11 @@ fn tokenize_function_call() {
     let res: Vec<Token> = tokenize_and_filter(input)
         .unwrap()
         .iter()
-        .map(|t| t.clone())
+        .map(|Location(t, _)| t.clone())
         .collect();
     let expected = vec![
-        Token::Element(Element("not".to_owned())),
-        Token::Element(Element("false".to_owned())),
+        Token::Element(Element("not".to_owned(), None, None)),
+        Token::Element(Element("false".to_owned(), None, None)),
     ];
     assert_eq!(res, expected);
 }
@@ -135,8 +136,44 @@ fn multiline_comment() {
     let res: Vec<Token> = tokenize_and_filter(input)
         .unwrap()
         .iter()
-        .map(|t| t.clone())
+        .map(|Location(t, _)| t.clone())
         .collect();
-    let expected = vec![Token::Element(Element("5".to_owned()))];
+    let expected = vec![Token::Element(Element("5".to_owned(), None, None))];
     assert_eq!(res, expected);
 }
+
+#[test]
+fn tokens_have_spans() {
+    let input = "-- comment\nlet x\n  {a b c}";
+    let res = tokenize_and_filter(input).unwrap();
+    assert_eq!(
+        res[1],
+        Location(
+            Token::Element(Element("x".to_owned(), None, None)),
+            Span {
+                start: 15,
+                end: 16,
+                line: 2,
+                column: 5
+            }
+        )
+    );
+    assert_eq!(
+        res[2].1,
+        Span {
+            start: 19,
+            end: 20,
+            line: 3,
+            column: 3
+        }
+    );
+}
+
+#[test]
+fn tokenize_error_has_position() {
+    let input = "test\n  ^";
+    let res = tokenize_and_filter(input).unwrap_err();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].message, "Unexpected character `^`.");
+    assert_eq!((res[0].span.line, res[0].span.column), (2, 3));
+}
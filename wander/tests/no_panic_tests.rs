@@ -0,0 +1,55 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use wander::lexer::tokenize_and_filter;
+use wander::parser::parse;
+
+const PIECES: [&str; 14] = [
+    "a", ":", "{", "}", "(", ")", "[", "]", ",", "|", "\"s\"", "^", "\u{FFFF}", "$v",
+];
+
+fn check(script: &str) {
+    if let Ok(tokens) = tokenize_and_filter(script) {
+        let _ = parse(tokens);
+    }
+}
+
+#[test]
+fn short_scripts_do_not_panic() {
+    let mut scripts = vec![String::new()];
+    for _ in 0..4 {
+        let mut next = vec![];
+        for script in &scripts {
+            for piece in PIECES {
+                let script = format!("{script} {piece}");
+                check(&script);
+                next.push(script);
+            }
+        }
+        scripts = next;
+    }
+}
+
+#[test]
+fn long_scripts_do_not_panic() {
+    let mut seed: u64 = 42;
+    for _ in 0..2000 {
+        let mut script = String::new();
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let length = (seed >> 33) % 40;
+        for _ in 0..length {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            script += PIECES[((seed >> 33) % PIECES.len() as u64) as usize];
+            script += " ";
+        }
+        check(&script);
+    }
+}
+
+#[test]
+fn deeply_nested_scripts_do_not_panic() {
+    for piece in ["(", "{", "test (", "test ({"] {
+        check(&piece.repeat(100000));
+    }
+}
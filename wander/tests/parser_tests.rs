@@ -5,20 +5,23 @@
 use std::collections::{BTreeSet, HashSet};
 
 use ligature::{Element, Entry};
-use wander::{Call, Quote, WanderError, WanderValue};
+use wander::{Call, Diagnostic, Location, Quote, Span, WanderValue};
 
-fn parse_str(script: &str) -> Result<Vec<Call>, WanderError> {
-    match wander::lexer::tokenize_and_filter(script) {
-        Ok(results) => wander::parser::parse(results),
-        Err(_) => todo!(),
-    }
+fn parse_str(script: &str) -> Result<Vec<Call>, Vec<Diagnostic>> {
+    let tokens = wander::lexer::tokenize_and_filter(script)?;
+    let calls = wander::parser::parse(tokens)?;
+    Ok(calls
+        .into_iter()
+        .flatten()
+        .map(|Location(call, _)| call)
+        .collect())
 }
 
 #[test]
 fn no_arg_command() {
     let res = parse_str("true");
     let expected = Ok(vec![Call {
-        name: Element("true".to_owned()),
+        name: Element("true".to_owned(), None, None),
         arguments: vec![],
     }]);
     assert_eq!(res, expected);
@@ -28,7 +31,7 @@ fn no_arg_command() {
 fn parse_integers() {
     let res = parse_str("-100");
     let expected = Ok(vec![Call {
-        name: Element("-100".to_owned()),
+        name: Element("-100".to_owned(), None, None),
         arguments: vec![],
     }]);
     assert_eq!(res, expected);
@@ -38,7 +41,7 @@ fn parse_integers() {
 fn parse_strings() {
     let res = parse_str("\"Hello\"");
     let expected = Ok(vec![Call {
-        name: Element("Hello".to_owned()),
+        name: Element("Hello".to_owned(), None, None),
         arguments: vec![],
     }]);
     assert_eq!(res, expected);
@@ -48,10 +51,10 @@ fn parse_strings() {
 fn parse_call_with_args() {
     let res = parse_str("Bool.and x true");
     let expected = Ok(vec![Call {
-        name: Element("Bool.and".to_owned()),
+        name: Element("Bool.and".to_owned(), None, None),
         arguments: vec![
-            WanderValue::Element(Element("x".to_owned())),
-            WanderValue::Element(Element("true".to_owned())),
+            WanderValue::Element(Element("x".to_owned(), None, None)),
+            WanderValue::Element(Element("true".to_owned(), None, None)),
         ],
     }]);
     assert_eq!(res, expected);
@@ -62,11 +65,11 @@ fn parse_applications() {
     let res = parse_str("Bool.not x, true");
     let expected = Ok(vec![
         Call {
-            name: Element("Bool.not".to_owned()),
-            arguments: vec![WanderValue::Element(Element("x".to_owned()))],
+            name: Element("Bool.not".to_owned(), None, None),
+            arguments: vec![WanderValue::Element(Element("x".to_owned(), None, None))],
         },
         Call {
-            name: Element("true".to_owned()),
+            name: Element("true".to_owned(), None, None),
             arguments: vec![],
         },
     ]);
@@ -77,7 +80,7 @@ fn parse_applications() {
 fn parse_empty_network_as_() {
     let res = parse_str("test {}");
     let expected = Ok(vec![Call {
-        name: Element("test".to_owned()),
+        name: Element("test".to_owned(), None, None),
         arguments: vec![WanderValue::Network(BTreeSet::from([]))],
     }]);
     assert_eq!(res, expected);
@@ -87,11 +90,11 @@ fn parse_empty_network_as_() {
 fn parse_network_with_single_entry() {
     let res = parse_str("test {a b c}");
     let expected = Ok(vec![Call {
-        name: Element("test".to_owned()),
+        name: Element("test".to_owned(), None, None),
         arguments: vec![WanderValue::Network(BTreeSet::from([Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("c".to_owned()),
-            role: Element("b".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("c".to_owned(), None, None),
+            role: Element("b".to_owned(), None, None),
         }]))],
     }]);
     assert_eq!(res, expected);
@@ -101,11 +104,11 @@ fn parse_network_with_single_entry() {
 fn parse_network_with_single_entry_and_trailing_comma() {
     let res = parse_str("test {a b c,}");
     let expected = Ok(vec![Call {
-        name: Element("test".to_owned()),
+        name: Element("test".to_owned(), None, None),
         arguments: vec![WanderValue::Network(BTreeSet::from([Entry::Role {
-            first: Element("a".to_owned()),
-            second: Element("c".to_owned()),
-            role: Element("b".to_owned()),
+            first: Element("a".to_owned(), None, None),
+            second: Element("c".to_owned(), None, None),
+            role: Element("b".to_owned(), None, None),
         }]))],
     }]);
     assert_eq!(res, expected);
@@ -116,16 +119,16 @@ fn parse_network_with_two_entries_and_trailing_comma() {
     let res = parse_str("test {a b c, a : A,}");
 
     let expected = Ok(vec![Call {
-        name: Element("test".to_owned()),
+        name: Element("test".to_owned(), None, None),
         arguments: vec![WanderValue::Network(BTreeSet::from([
             Entry::Role {
-                first: Element("a".to_owned()),
-                second: Element("c".to_owned()),
-                role: Element("b".to_owned()),
+                first: Element("a".to_owned(), None, None),
+                second: Element("c".to_owned(), None, None),
+                role: Element("b".to_owned(), None, None),
             },
             Entry::Extends {
-                element: Element("a".to_owned()),
-                concept: Element("A".to_owned()),
+                element: Element("a".to_owned(), None, None),
+                concept: Element("A".to_owned(), None, None),
             },
         ]))],
     }]);
@@ -136,11 +139,119 @@ fn parse_network_with_two_entries_and_trailing_comma() {
 fn parse_nested_function_calls() {
     let res = parse_str("Bool.not (Bool.not false)");
     let expected = Ok(vec![Call {
-        name: Element("Bool.not".to_owned()),
+        name: Element("Bool.not".to_owned(), None, None),
         arguments: vec![WanderValue::Quote(Quote(vec![
-            WanderValue::Element(Element("Bool.not".to_owned())),
-            WanderValue::Element(Element("false".to_owned())),
+            WanderValue::Element(Element("Bool.not".to_owned(), None, None)),
+            WanderValue::Element(Element("false".to_owned(), None, None)),
         ]))],
     }]);
     assert_eq!(res, expected);
 }
+
+#[test]
+fn calls_have_spans() {
+    let tokens = wander::lexer::tokenize_and_filter("a b,\n  c").unwrap();
+    let spans: Vec<Span> = wander::parser::parse(tokens)
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .map(|Location(_, span)| span)
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            Span {
+                start: 0,
+                end: 1,
+                line: 1,
+                column: 1
+            },
+            Span {
+                start: 7,
+                end: 8,
+                line: 2,
+                column: 3
+            }
+        ]
+    );
+}
+
+#[test]
+fn parse_error_lists_expected_tokens() {
+    let res = &parse_str("test {a b )").unwrap_err()[0];
+    assert_eq!(res.message, "Unexpected `)`");
+    assert_eq!(res.expected, vec!["an Element".to_owned()]);
+    assert_eq!((res.span.line, res.span.column), (1, 11));
+}
+
+#[test]
+fn parse_error_at_end_of_script() {
+    let res = &parse_str("test {a b c").unwrap_err()[0];
+    assert_eq!(res.message, "Unexpected end of script");
+    assert_eq!(res.expected, vec!["`,`".to_owned(), "`}`".to_owned()]);
+}
+
+#[test]
+fn render_diagnostic() {
+    let script = "id x,\ntest {a b )";
+    let res = wander::run(
+        script,
+        &wander::prelude::common(),
+        &mut ligature_graph::LigatureGraph::new(),
+    );
+    let expected = "Unexpected `)`, expected an Element.
+ --> line 2, column 11
+  |
+2 | test {a b )
+  |           ^";
+    assert_eq!(res, Err(wander::WanderError(expected.to_owned())));
+}
+
+#[test]
+fn render_command_error() {
+    let script = "id x,\n  missing";
+    let res = wander::run(
+        script,
+        &wander::prelude::common(),
+        &mut ligature_graph::LigatureGraph::new(),
+    );
+    let expected = "Could not find command: missing
+ --> line 2, column 3
+  |
+2 |   missing
+  |   ^^^^^^^";
+    assert_eq!(res, Err(wander::WanderError(expected.to_owned())));
+}
+
+#[test]
+fn parse_recovers_after_errors() {
+    let res = parse_str("test {a b}, ok, test (a [ b), {a b c}, ok2").unwrap_err();
+    let messages: Vec<&str> = res.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec!["Unexpected `}`", "Unexpected `[`", "Unexpected `{`"]
+    );
+    assert_eq!(res[2].expected, vec!["a command name".to_owned()]);
+}
+
+#[test]
+fn parse_recovers_inside_of_networks() {
+    let res = parse_str("foo {a b c d, e f g}, bar").unwrap_err();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].message, "Unexpected `d`");
+}
+
+#[test]
+fn parse_deeply_nested_quotes() {
+    let script = format!("test {}{}", "(".repeat(10000), ")".repeat(10000));
+    let res = parse_str(&script).unwrap_err();
+    assert_eq!(res.len(), 1);
+    assert!(res[0].message.starts_with("Quotes can't be nested"));
+}
+
+#[test]
+fn parse_empty_call() {
+    let res = parse_str("a,, b").unwrap_err();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].message, "Unexpected `,`");
+}
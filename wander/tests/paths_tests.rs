@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::Entry;
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderError, WanderValue};
+
+fn run_on_family(script: &str) -> Result<WanderValue, WanderError> {
+    run(
+        &format!("store family {{ann parentOf bob, bob parentOf cat, cat livesIn york}}, {script}"),
+        &common(),
+        &mut LigatureGraph::new(),
+    )
+}
+
+#[test]
+fn ancestors() {
+    assert_eq!(
+        run_on_family("path family \"^parentOf+\" cat"),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("cat", "^parentOf+", "bob"),
+            Entry::role("cat", "^parentOf+", "ann"),
+        ])))
+    );
+}
+
+#[test]
+fn path_from_every_element() {
+    assert_eq!(
+        run_on_family("path family parentOf/parentOf ?"),
+        Ok(WanderValue::Network(BTreeSet::from([Entry::role(
+            "ann",
+            "parentOf/parentOf",
+            "cat"
+        )])))
+    );
+}
+
+#[test]
+fn bad_path() {
+    assert!(run_on_family("path family \"(parentOf\" ann").is_err());
+}
+
+#[test]
+fn reachable_from_an_element() {
+    assert_eq!(
+        run_on_family("reachable family bob"),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("bob", "reaches", "cat"),
+            Entry::role("bob", "reaches", "york"),
+        ])))
+    );
+}
+
+#[test]
+fn shortest_path() {
+    assert_eq!(
+        run_on_family("shortest-path family ann york"),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::role("ann", "parentOf", "bob"),
+            Entry::role("bob", "parentOf", "cat"),
+            Entry::role("cat", "livesIn", "york"),
+        ])))
+    );
+    assert_eq!(
+        run_on_family("shortest-path family york ann"),
+        Ok(WanderValue::Network(BTreeSet::new()))
+    );
+}
@@ -2,128 +2,80 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-// use wander::parser::ParserTerm;
-// use wander::{run, WanderValue};
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::lexer::tokenize_and_filter;
+use wander::parser::parse;
+use wander::prelude::common;
+use wander::{run, Call, Location, WanderValue};
 
-// //use crate::utilities::{introspect_str, parse_str};
-// use wander::interpreter::Expression;
-// use wander::preludes::common;
-// use wander::translation::translate;
+fn parse_pipelines(script: &str) -> Vec<Vec<Call>> {
+    parse(tokenize_and_filter(script).unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|pipeline| pipeline.into_iter().map(|Location(call, _)| call).collect())
+        .collect()
+}
 
-// #[test]
-// fn parse_pipe_value_to_name() {
-//     let res = introspect_str("false | not");
-//     let expected = Element::Grouping(vec![
-//         Element::Grouping(vec![Element::Boolean(false)]),
-//         Element::Pipe,
-//         Element::Grouping(vec![Element::Name("not".to_owned())]),
-//     ]);
-//     assert_eq!(res.element, expected);
-//     let expected = Expression::Application(vec![
-//         Expression::Name("not".to_owned()),
-//         Expression::Boolean(false),
-//     ]);
-//     assert_eq!(res.expression, expected);
-// }
+#[test]
+fn parse_pipe_value_to_name() {
+    let res = parse_pipelines("id x | id, ignore");
+    let expected = vec![
+        vec![
+            Call {
+                name: Element::new("id"),
+                arguments: vec![WanderValue::Element(Element::new("x"))],
+            },
+            Call {
+                name: Element::new("id"),
+                arguments: vec![],
+            },
+        ],
+        vec![Call {
+            name: Element::new("ignore"),
+            arguments: vec![],
+        }],
+    ];
+    assert_eq!(res, expected);
+}
 
-// #[test]
-// fn parse_pipe_value_to_application() {
-//     let res = parse_str("false | Bool.and true");
-//     let expected = Element::Grouping(vec![
-//         Element::Grouping(vec![Element::Boolean(false)]),
-//         Element::Pipe,
-//         Element::Grouping(vec![
-//             Element::Name("Bool.and".to_owned()),
-//             Element::Boolean(true),
-//         ]),
-//     ]);
-//     assert_eq!(res, expected);
-//     let res = translate(res).unwrap();
-//     let expected = Expression::Application(vec![
-//         Expression::Name("Bool.and".to_owned()),
-//         Expression::Boolean(true),
-//         Expression::Boolean(false),
-//     ]);
-//     assert_eq!(res, expected);
-// }
+#[test]
+fn parse_incomplete_pipes() {
+    for script in ["| id", "id x |", "id x | | id", "id x |, id"] {
+        let res = parse(tokenize_and_filter(script).unwrap()).unwrap_err();
+        assert_eq!(res.len(), 1, "{script}");
+        assert_eq!(res[0].expected, vec!["a command name".to_owned()]);
+    }
+}
 
-// #[test]
-// fn parse_pipe_application_to_application() {
-//     let res = parse_str("Bool.not false | Bool.and true");
-//     let expected = Element::Grouping(vec![
-//         Element::Grouping(vec![
-//             Element::Name("Bool.not".to_owned()),
-//             Element::Boolean(false),
-//         ]),
-//         Element::Pipe,
-//         Element::Grouping(vec![
-//             Element::Name("Bool.and".to_owned()),
-//             Element::Boolean(true),
-//         ]),
-//     ]);
-//     assert_eq!(res, expected);
-//     let res = translate(res).unwrap();
-//     let expected = Expression::Application(vec![
-//         Expression::Name("Bool.and".to_owned()),
-//         Expression::Boolean(true),
-//         Expression::Application(vec![
-//             Expression::Name("Bool.not".to_owned()),
-//             Expression::Boolean(false),
-//         ]),
-//     ]);
-//     assert_eq!(res, expected);
-// }
+#[test]
+fn run_pipe_value_to_name() {
+    let res = run("id x | id", &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("x"))));
+}
 
-// #[test]
-// fn run_pipe_value_to_name() {
-//     let res = run("false | Bool.not", &mut common::<NoHostType>());
-//     let res = res.first().unwrap().unwrap();
-//     let expected = WanderValue::Bool(true);
-//     assert_eq!(res, expected);
-// }
+#[test]
+fn run_pipe_as_last_argument() {
+    let script = "store people {alice knows bob}, read people | union {bob knows carol}";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    let expected = BTreeSet::from([
+        Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("bob"),
+            role: Element::new("knows"),
+        },
+        Entry::Role {
+            first: Element::new("bob"),
+            second: Element::new("carol"),
+            role: Element::new("knows"),
+        },
+    ]);
+    assert_eq!(res, Ok(WanderValue::Network(expected)));
+}
 
-// #[test]
-// fn run_pipe_value_to_application() {
-//     let res = run("false | Bool.and true", &mut common::<NoHostType>()).first().unwrap().unwrap();
-//     let expected = WanderValue::Bool(false);
-//     assert_eq!(res, expected);
-// }
-
-// #[test]
-// fn run_pipe_application_to_application() {
-//     let res = run(
-//         "Bool.not false | Bool.and true",
-//         &mut common::<NoHostType>(),
-//     ).first().unwrap().unwrap();
-//     let expected = WanderValue::Bool(true);
-//     assert_eq!(res, expected);
-// }
-
-// #[test]
-// fn run_multiple_pipes() {
-//     let res = run(
-//         "Bool.not false | Bool.and true | Bool.not",
-//         &mut common::<NoHostType>(),
-//     ).first().unwrap().unwrap();
-//     let expected = WanderValue::Bool(false);
-//     assert_eq!(res, expected);
-// }
-
-// #[test]
-// #[ignore = "https://github.com/almibe/wander/issues/35"]
-// fn run_pipe_in_let_expression() {
-//     let res = run("let in true | Bool.not end", &mut common::<NoHostType>()).first().unwrap().unwrap();
-//     let expected = WanderValue::Bool(false);
-//     assert_eq!(res, expected);
-// }
-
-// #[test]
-// #[ignore = "https://github.com/almibe/wander/issues/35"]
-// fn run_pipe_in_let_expression_decl() {
-//     let res = run(
-//         "let val x = true | Bool.not in x end",
-//         &mut common::<NoHostType>(),
-//     ).first().unwrap().unwrap();
-//     let expected = WanderValue::Bool(false);
-//     assert_eq!(res, expected);
-// }
+#[test]
+fn run_chained_pipes() {
+    let res = run("id x | id | id", &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("x"))));
+}
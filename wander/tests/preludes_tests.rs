@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use ligature::Element;
 use ligature_graph::LigatureGraph;
 use std::collections::BTreeSet;
 use wander::prelude::common;
@@ -37,3 +38,88 @@ fn failing_assert_eq_call() {
     let res = run(input, &common(), &mut LigatureGraph::new());
     assert!(res.is_err());
 }
+
+#[test]
+fn import_and_export_csv() {
+    let path = std::env::temp_dir().join(format!(
+        "wander_import_and_export_csv_{}.csv",
+        std::process::id()
+    ));
+    std::fs::write(&path, "id,name\nalice,Alice\n").unwrap();
+    let mut ligature = LigatureGraph::new();
+    let input = format!("import-csv people \"{}\" id Person", path.display());
+    let res = run(&input, &common(), &mut ligature);
+    assert_eq!(res, Ok(WanderValue::Network(BTreeSet::new())));
+    let res = run("export-csv people id", &common(), &mut ligature);
+    let expected = Ok(WanderValue::Element(Element(
+        "id,name\nalice,Alice\n".to_owned(),
+        None,
+        None,
+    )));
+    assert_eq!(res, expected);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn store_replaces_a_collection() {
+    let script = "store people {alice : Person}, store people {bob : Person}, read people";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    let expected = BTreeSet::from([ligature::Entry::extends("bob", "Person")]);
+    assert_eq!(res, Ok(WanderValue::Network(expected)));
+}
+
+#[test]
+fn write_collection_as_dot() {
+    let mut ligature = LigatureGraph::new();
+    let res = run("store people {alice : Person}", &common(), &mut ligature);
+    assert!(res.is_ok());
+    let res = run("dot people", &common(), &mut ligature);
+    let expected = Ok(WanderValue::Element(Element(
+        "digraph {\n  \"Person\" [shape=box, style=filled, fillcolor=lightblue];\n  \"alice\";\n  \"alice\" -> \"Person\" [label=\":\", style=dashed];\n}\n".to_owned(),
+        None,
+        None,
+    )));
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn import_and_export_csv_with_a_mapping() {
+    let path = std::env::temp_dir().join(format!(
+        "wander_import_and_export_csv_with_a_mapping_{}.csv",
+        std::process::id()
+    ));
+    std::fs::write(&path, "id,name,kind,ignored\nalice,Alice,Person,x\n").unwrap();
+    let mut ligature = LigatureGraph::new();
+    let input = format!(
+        "import-csv people \"{}\" id {{name role hasName, kind : Concept}}",
+        path.display()
+    );
+    let res = run(&input, &common(), &mut ligature);
+    assert_eq!(res, Ok(WanderValue::Network(BTreeSet::new())));
+    let res = run("read people", &common(), &mut ligature);
+    let expected = BTreeSet::from([
+        ligature::Entry::role("alice", "hasName", "Alice"),
+        ligature::Entry::extends("alice", "Person"),
+    ]);
+    assert_eq!(res, Ok(WanderValue::Network(expected)));
+    let script = "store mapping {name role hasName, kind : Concept}, export-csv people id mapping";
+    let res = run(script, &common(), &mut ligature);
+    let expected = Ok(WanderValue::Element(Element::new(
+        "id,name,kind\nalice,Alice,Person\n",
+    )));
+    assert_eq!(res, expected);
+    let res = run(
+        "export-csv people id (id {name role hasName})",
+        &common(),
+        &mut ligature,
+    );
+    let expected = Ok(WanderValue::Element(Element::new("id,name\nalice,Alice\n")));
+    assert_eq!(res, expected);
+    let res = run(
+        "export-csv people id {name hasName Alice}",
+        &common(),
+        &mut ligature,
+    );
+    assert!(res.is_err());
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::Entry;
+use ligature_graph::LigatureGraph;
+use wander::prelude::common;
+use wander::{run, WanderError, WanderValue};
+
+const PEOPLE: &str = "store people {alice knows bob, bob knows carol, carol knows alice, alice : Person, bob : Person}";
+
+fn run_query(query: &str) -> Result<WanderValue, WanderError> {
+    run(
+        &format!("{PEOPLE}, {query}"),
+        &common(),
+        &mut LigatureGraph::new(),
+    )
+}
+
+fn network(entries: &[Entry]) -> Result<WanderValue, WanderError> {
+    Ok(WanderValue::Network(entries.iter().cloned().collect()))
+}
+
+#[test]
+fn query_single_pattern() {
+    assert_eq!(
+        run_query("query people {alice knows ?who}"),
+        network(&[Entry::role("alice", "knows", "bob")])
+    );
+}
+
+#[test]
+fn query_joins_patterns() {
+    assert_eq!(
+        run_query("query people {?a knows ?b, ?b : Person}"),
+        network(&[
+            Entry::role("alice", "knows", "bob"),
+            Entry::role("carol", "knows", "alice"),
+            Entry::extends("alice", "Person"),
+            Entry::extends("bob", "Person"),
+        ])
+    );
+}
+
+#[test]
+fn query_with_wildcards() {
+    assert_eq!(
+        run_query("query people {? knows alice}"),
+        network(&[Entry::role("carol", "knows", "alice")])
+    );
+}
+
+#[test]
+fn query_with_template() {
+    assert_eq!(
+        run_query("query people {?a knows ?b, ?b knows ?c} {?a knowsOfKnows ?c}"),
+        network(&[
+            Entry::role("alice", "knowsOfKnows", "carol"),
+            Entry::role("bob", "knowsOfKnows", "alice"),
+            Entry::role("carol", "knowsOfKnows", "bob"),
+        ])
+    );
+}
+
+#[test]
+fn query_without_matches() {
+    assert_eq!(run_query("query people {?a likes ?b}"), network(&[]));
+}
+
+#[test]
+fn query_with_unbound_template_variable() {
+    assert!(run_query("query people {?a knows ?b} {?a likes ?c}").is_err());
+}
+
+#[test]
+fn query_missing_collection() {
+    assert!(run_query("query missing {?a knows ?b}").is_err());
+    assert!(run_query("query people").is_err());
+}
@@ -0,0 +1,140 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::lexer::tokenize_and_filter;
+use wander::parser::parse;
+use wander::prelude::common;
+use wander::{run, Call, Location, Quote, WanderValue};
+
+fn network() -> BTreeSet<Entry> {
+    BTreeSet::from([Entry::Role {
+        first: Element::new("a"),
+        second: Element::new("c"),
+        role: Element::new("b"),
+    }])
+}
+
+#[test]
+fn parse_nested_quotes() {
+    let res: Vec<Call> = parse(tokenize_and_filter("eval (if (eq a a) (id \"x y\") ())").unwrap())
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .map(|Location(call, _)| call)
+        .collect();
+    let expected = vec![Call {
+        name: Element::new("eval"),
+        arguments: vec![WanderValue::Quote(Quote(vec![
+            WanderValue::Element(Element::new("if")),
+            WanderValue::Quote(Quote(vec![
+                WanderValue::Element(Element::new("eq")),
+                WanderValue::Element(Element::new("a")),
+                WanderValue::Element(Element::new("a")),
+            ])),
+            WanderValue::Quote(Quote(vec![
+                WanderValue::Element(Element::new("id")),
+                WanderValue::Element(Element::new("x y")),
+            ])),
+            WanderValue::Quote(Quote(vec![])),
+        ]))],
+    }];
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn eval_runs_a_quote() {
+    assert_eq!(
+        run("eval (id x)", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("x")))
+    );
+    assert_eq!(
+        run(
+            "eval (if (eq a b) (id yes) (id no))",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("no")))
+    );
+    assert_eq!(
+        run("eval ({a b c})", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Network(network()))
+    );
+}
+
+#[test]
+fn apply_appends_arguments() {
+    assert_eq!(
+        run(
+            "apply (union {a b c}) {}",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(network()))
+    );
+    assert_eq!(
+        run("id x | apply (id)", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("x")))
+    );
+}
+
+#[test]
+fn run_quotes_with_quote_heads() {
+    assert_eq!(
+        run(
+            "eval ((union {a b c}) {})",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(network()))
+    );
+    assert_eq!(
+        run("eval (((id)) x)", &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Element(Element::new("x")))
+    );
+}
+
+#[test]
+fn run_quotes_with_variable_heads() {
+    assert_eq!(
+        run(
+            "let f (union {a b c}), eval ($f {})",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(network()))
+    );
+    assert_eq!(
+        run(
+            "let f id, eval ($f x)",
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Element(Element::new("x")))
+    );
+    assert!(run("eval ($missing x)", &common(), &mut LigatureGraph::new()).is_err());
+}
+
+#[test]
+fn calling_a_network_fails() {
+    assert!(run("eval ({a b c} x)", &common(), &mut LigatureGraph::new()).is_err());
+}
+
+#[test]
+fn self_referencing_quotes_fail() {
+    let res = run("let q ($q), eval $q", &common(), &mut LigatureGraph::new()).unwrap_err();
+    assert!(res.0.contains("exceeded the maximum depth"), "{}", res.0);
+}
+
+#[test]
+fn quotes_that_eval_themselves_fail() {
+    let script = "let q (eval $q), eval $q";
+    let res = run(script, &common(), &mut LigatureGraph::new()).unwrap_err();
+    assert!(res.0.contains("exceeded the maximum depth"), "{}", res.0);
+    let script = "let q (apply $q), apply $q";
+    let res = run(script, &common(), &mut LigatureGraph::new()).unwrap_err();
+    assert!(res.0.contains("exceeded the maximum depth"), "{}", res.0);
+}
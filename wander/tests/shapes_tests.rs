@@ -0,0 +1,103 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::prelude::common;
+use wander::{run, WanderValue};
+
+const SHAPES: &str = "store shapes {
+    personName concept Person, personName role name, personName maxCount 1,
+    personWorksAt concept Person, personWorksAt role worksAt, personWorksAt valueConcept Company
+}";
+
+#[test]
+fn validate_returns_a_report() {
+    assert_eq!(
+        run(
+            &format!(
+                "{SHAPES}, validate {{alice : Person, alice worksAt bob, acme : Company}} shapes"
+            ),
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([Entry::Role {
+            first: Element::new("alice"),
+            second: Element::new("personWorksAt"),
+            role: Element::new("violates"),
+        }])))
+    );
+}
+
+#[test]
+fn assert_valid() {
+    assert_eq!(
+        run(
+            &format!("{SHAPES}, assert-valid {{alice : Person, alice name Alice}} shapes"),
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::new()))
+    );
+    assert!(run(
+        &format!(
+            "{SHAPES}, assert-valid {{alice : Person, alice name Alice, alice name Al}} shapes"
+        ),
+        &common(),
+        &mut LigatureGraph::new()
+    )
+    .unwrap_err()
+    .0
+    .starts_with("`alice` has 2 values for `name` but can have at most 1."));
+}
+
+#[test]
+fn store_valid_checks_the_whole_collection() {
+    let script = format!(
+        "{SHAPES}, store-valid people shapes {{alice : Person, alice name Alice}}, \
+        store-valid people shapes {{alice name Al}}"
+    );
+    assert!(run(&script, &common(), &mut LigatureGraph::new())
+        .unwrap_err()
+        .0
+        .starts_with("`alice` has 2 values for `name` but can have at most 1."));
+    assert_eq!(
+        run(
+            &format!(
+            "{SHAPES}, store-valid people shapes {{alice : Person, alice name Alice}}, read people"
+        ),
+            &common(),
+            &mut LigatureGraph::new()
+        ),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::Extends {
+                element: Element::new("alice"),
+                concept: Element::new("Person"),
+            },
+            Entry::Role {
+                first: Element::new("alice"),
+                second: Element::new("Alice"),
+                role: Element::new("name"),
+            },
+        ])))
+    );
+}
+
+#[test]
+fn store_valid_adds_to_the_collection() {
+    let script = format!(
+        "{SHAPES}, store-valid people shapes {{alice : Person, alice name Alice}}, \
+        store-valid people shapes {{bob : Person, bob name Bob}}, read people"
+    );
+    assert_eq!(
+        run(&script, &common(), &mut LigatureGraph::new()),
+        Ok(WanderValue::Network(BTreeSet::from([
+            Entry::extends("alice", "Person"),
+            Entry::role("alice", "name", "Alice"),
+            Entry::extends("bob", "Person"),
+            Entry::role("bob", "name", "Bob"),
+        ])))
+    );
+}
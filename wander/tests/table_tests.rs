@@ -0,0 +1,135 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use hashbag::HashBag;
+use ligature::{Element, Entry, LangTag};
+use ligature_graph::LigatureGraph;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use wander::prelude::common;
+use wander::{run, Table, WanderError, WanderValue};
+
+fn row(values: &[(&str, &str)]) -> BTreeMap<String, Element> {
+    values
+        .iter()
+        .map(|(name, value)| ((*name).to_owned(), Element::new(*value)))
+        .collect()
+}
+
+fn run_on_people(script: &str) -> Result<WanderValue, WanderError> {
+    run(
+        &format!(
+            "store people {{ann worksAt acme, bob worksAt acme, cat worksAt initech}}, {script}"
+        ),
+        &common(),
+        &mut LigatureGraph::new(),
+    )
+}
+
+#[test]
+fn table_from_query_results() {
+    let results = HashBag::from_iter([
+        row(&[("p", "bob")]),
+        row(&[("p", "ann")]),
+        row(&[("p", "ann")]),
+    ]);
+    assert_eq!(
+        Table::from(results),
+        Table(vec![
+            row(&[("p", "ann")]),
+            row(&[("p", "ann")]),
+            row(&[("p", "bob")])
+        ])
+    );
+}
+
+#[test]
+fn table_json() {
+    let mut named = row(&[("p", "ann")]);
+    named.insert(
+        "name".to_owned(),
+        Element("Ann".to_owned(), None, Some(LangTag("en".to_owned()))),
+    );
+    let table = Table(vec![named, row(&[("p", "bob")])]);
+    let value = table.to_json();
+    assert_eq!(
+        value,
+        json!([{"p": "ann", "name": {"value": "Ann", "lang": "en"}}, {"p": "bob"}])
+    );
+    assert_eq!(Table::from_json(&value), Ok(table));
+    assert!(Table::from_json(&json!({"p": "ann"})).is_err());
+}
+
+#[test]
+fn table_network_round_trip() {
+    let table = Table((0..11).map(|i| row(&[("n", &i.to_string())])).collect());
+    let network = table.to_network();
+    assert!(network.contains(&Entry::role("row00", "n", "0")));
+    assert!(network.contains(&Entry::role("row10", "n", "10")));
+    assert_eq!(Table::from_network(&network), Ok(table));
+    assert!(Table::from_network(&BTreeSet::from([
+        Entry::role("a", "b", "c"),
+        Entry::role("a", "b", "d")
+    ]))
+    .is_err());
+}
+
+#[test]
+fn bindings_command() {
+    assert_eq!(
+        run_on_people("bindings people {?p worksAt acme}"),
+        Ok(WanderValue::Table(Table(vec![
+            row(&[("p", "ann")]),
+            row(&[("p", "bob")])
+        ])))
+    );
+    assert_eq!(
+        run_on_people("bindings people {?p worksAt ?}"),
+        Ok(WanderValue::Table(Table(vec![
+            row(&[("p", "ann")]),
+            row(&[("p", "bob")]),
+            row(&[("p", "cat")])
+        ])))
+    );
+}
+
+#[test]
+fn display_tables_like_networks() {
+    let mut tagged = row(&[("p", "ann")]);
+    tagged.insert(
+        "name".to_owned(),
+        Element("Ann".to_owned(), None, Some(LangTag("en".to_owned()))),
+    );
+    let table = WanderValue::Table(Table(vec![tagged, row(&[("p", "bob")])]));
+    assert_eq!(table.to_string(), r#"[?name "Ann"@en ?p "ann", ?p "bob"]"#);
+}
+
+#[test]
+fn aggregate_into_a_table() {
+    assert_eq!(
+        run_on_people("aggregate people {?p worksAt ?c} {?n count ?p}"),
+        Ok(WanderValue::Table(Table(vec![row(&[("n", "3")])])))
+    );
+}
+
+#[test]
+fn convert_tables_and_networks() {
+    assert_eq!(
+        run_on_people("bindings people {?p worksAt initech} | to-network"),
+        Ok(WanderValue::Network(BTreeSet::from([Entry::role(
+            "row0", "p", "cat"
+        )])))
+    );
+    assert_eq!(
+        run_on_people("to-table {ann age 9, ann name Ann}"),
+        Ok(WanderValue::Table(Table(vec![row(&[
+            ("age", "9"),
+            ("name", "Ann")
+        ])])))
+    );
+    assert_eq!(
+        run_on_people("count (bindings people {?p worksAt ?c})"),
+        Ok(WanderValue::Element(Element::new("6")))
+    );
+}
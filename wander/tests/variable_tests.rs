@@ -0,0 +1,88 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use ligature::{Element, Entry, Ligature};
+use ligature_graph::LigatureGraph;
+use std::collections::BTreeSet;
+use wander::lexer::{tokenize_and_filter, Token};
+use wander::prelude::common;
+use wander::{run, Location, Quote, WanderValue};
+
+fn network() -> BTreeSet<Entry> {
+    BTreeSet::from([Entry::Role {
+        first: Element::new("alice"),
+        second: Element::new("bob"),
+        role: Element::new("knows"),
+    }])
+}
+
+#[test]
+fn tokenize_variable() {
+    let res: Vec<Token> = tokenize_and_filter("id $x")
+        .unwrap()
+        .into_iter()
+        .map(|Location(token, _)| token)
+        .collect();
+    let expected = vec![
+        Token::Element(Element::new("id")),
+        Token::Variable("x".to_owned()),
+    ];
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn bind_any_value() {
+    let commands = common();
+    let mut state = LigatureGraph::new();
+    let res = run("let x hello, id $x", &commands, &mut state);
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("hello"))));
+    let res = run("let x {alice knows bob}, id $x", &commands, &mut state);
+    assert_eq!(res, Ok(WanderValue::Network(network())));
+    let res = run("let x (id $y), id $x", &commands, &mut state);
+    let expected = WanderValue::Quote(Quote(vec![
+        WanderValue::Element(Element::new("id")),
+        WanderValue::Variable("y".to_owned()),
+    ]));
+    assert_eq!(res, Ok(expected));
+    assert_eq!(state.collections(), Ok(vec![]));
+}
+
+#[test]
+fn variables_in_quotes_and_pipes() {
+    let script = "let x hello, id hello | let y, assert-equal (id $x) $y, id $y";
+    let res = run(script, &common(), &mut LigatureGraph::new());
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("hello"))));
+}
+
+#[test]
+fn undefined_variable() {
+    let res = run("id $missing", &common(), &mut LigatureGraph::new()).unwrap_err();
+    assert!(res.0.starts_with("Variable `$missing` is not defined."));
+}
+
+#[test]
+fn definitions_have_their_own_scope() {
+    let commands = common();
+    let mut state = LigatureGraph::new();
+    let script = "let x outer, define f (x) (id $x), f inner";
+    let res = run(script, &commands, &mut state);
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("inner"))));
+    let script = "define f () (let y inner), f, id $y";
+    assert!(run(script, &commands, &mut state).is_err());
+    let script = "let x outer, define f () (id $x), f";
+    let res = run(script, &commands, &mut state);
+    assert_eq!(res, Ok(WanderValue::Element(Element::new("outer"))));
+    let script = "define g () (id $local), define f (local) (g), f x";
+    assert!(run(script, &commands, &mut state).is_err());
+}
+
+#[test]
+fn store_writes_collections() {
+    let mut state = LigatureGraph::new();
+    let script = "store people {alice knows bob}, store copy (read people)";
+    assert!(run(script, &common(), &mut state).is_ok());
+    assert_eq!(state.entries(&Element::new("people")), Ok(network()));
+    assert_eq!(state.entries(&Element::new("copy")), Ok(network()));
+    assert!(run("store people (id x)", &common(), &mut state).is_err());
+}